@@ -0,0 +1,386 @@
+//! Checks and fee routing shared by every instruction that buys from a curve,
+//! so `buy_tokens`, the `buy_tokens_batch` legs and the creator's initial buy
+//! in `create_token` can't drift apart on what a buy is allowed to do.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+
+use crate::error::PumpCloneError;
+use crate::events::{TokensPurchased, Trade};
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::utils;
+
+/// The accounts and settings a buy is checked against before it is priced.
+pub struct BuyGuard<'a, 'info> {
+    pub program_id: &'a Pubkey,
+    pub global_state: &'a GlobalState,
+    pub token_launch: &'a TokenLaunch,
+    pub buyer: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub buyer_blacklist: &'a AccountInfo<'info>,
+    pub buyer_whitelist: &'a AccountInfo<'info>,
+    pub market_maker: Option<&'a MarketMaker>,
+    pub instructions_sysvar: Option<&'a AccountInfo<'info>>,
+    /// Successful referrals on the buyer's own referral account
+    pub referrals: u32,
+}
+
+impl BuyGuard<'_, '_> {
+    /// Every check that doesn't depend on the price. Books the trade against
+    /// the wallet throttle, the curve's per-slot cap and its TWAP, so it runs
+    /// exactly once per buy.
+    pub fn check_before_quote(
+        &self,
+        curve: &mut BondingCurve,
+        position: Option<&mut UserPosition>,
+        sol_amount: u64,
+        deadline: i64,
+        clock: &Clock,
+    ) -> Result<()> {
+        let now = clock.unix_timestamp;
+        self.global_state.check_buys_allowed()?;
+        Blacklist::check_not_blacklisted(self.buyer_blacklist)?;
+        require!(sol_amount > 0, PumpCloneError::InvalidAmount);
+        require!(!curve.complete, PumpCloneError::BondingCurveCompleted);
+        curve.check_not_in_refund_mode()?;
+        require!(!utils::is_deadline_expired(now, deadline), PumpCloneError::OperationExpired);
+        self.token_launch.check_trading_hours(now)?;
+        curve.check_whitelist_phase(now, &self.buyer, Whitelist::is_whitelisted(self.buyer_whitelist)?)?;
+        require!(
+            sol_amount
+                <= utils::max_buy_allowance(
+                    self.global_state.max_buy_base,
+                    self.global_state.referral_buy_bonus,
+                    self.referrals,
+                ),
+            PumpCloneError::MaxPurchaseAmountExceeded
+        );
+
+        if self.global_state.wallet_throttle_applies(self.market_maker)? {
+            position
+                .ok_or(PumpCloneError::AccountNotInitialized)?
+                .check_wallet_throttle(
+                    now,
+                    sol_amount,
+                    self.global_state.wallet_trade_cooldown_secs,
+                    self.global_state.wallet_daily_sol_limit,
+                )?;
+        }
+        if self.global_state.atomic_trade_guard {
+            let instructions_sysvar = self
+                .instructions_sysvar
+                .ok_or(PumpCloneError::AccountNotInitialized)?;
+            let (instructions, current_index) =
+                utils::load_transaction_instructions(instructions_sysvar)?;
+            require!(
+                !utils::is_atomic_round_trip(
+                    &instructions,
+                    current_index,
+                    self.program_id,
+                    &utils::SELL_DISCRIMINATORS,
+                    &self.buyer,
+                    &self.bonding_curve,
+                ),
+                PumpCloneError::MevProtectionActive
+            );
+        }
+
+        curve.record_trade_in_slot(clock.slot, self.global_state.max_trades_per_slot)?;
+        curve.update_twap(now)
+    }
+
+    /// Caps on the tokens a priced buy would take.
+    pub fn check_quote(
+        &self,
+        curve: &BondingCurve,
+        position: Option<&UserPosition>,
+        token_amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        require!(token_amount > 0, PumpCloneError::InsufficientTokensForSale);
+        curve.check_anti_snipe_cap(now, position.map(|position| position.tokens_bought), token_amount)?;
+        self.global_state
+            .check_tokens_per_buy(token_amount, curve.real_token_reserves)
+    }
+}
+
+/// Pays a buy's creator fee from `buyer`: the launch's community share to
+/// `community_wallet` and the rest to `creator`, which must be the launch's
+/// fee destination.
+pub fn pay_creator_fee<'info>(
+    system_program: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    token_launch: &TokenLaunch,
+    creator: &AccountInfo<'info>,
+    community_wallet: Option<&AccountInfo<'info>>,
+    creator_fee: u64,
+) -> Result<()> {
+    require_keys_eq!(creator.key(), token_launch.fee_destination(), PumpCloneError::InvalidCreator);
+    let (creator_share, community_share) =
+        utils::split_creator_fee(creator_fee, token_launch.community_fee_bps)?;
+    if community_share > 0 {
+        let community_wallet = community_wallet.ok_or(PumpCloneError::InvalidFeeRecipient)?;
+        require_keys_eq!(
+            community_wallet.key(),
+            token_launch.community_wallet,
+            PumpCloneError::InvalidFeeRecipient
+        );
+    }
+
+    for (to, amount) in [(community_wallet, community_share), (Some(creator), creator_share)] {
+        if let (Some(to), true) = (to, amount > 0) {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: buyer.clone(),
+                        to: to.clone(),
+                    },
+                ),
+                amount,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// The buyer's position stored at `position_info`, or a blank one before
+/// their first buy into the curve. Nothing is created here, so the guards
+/// can run against it before the buy commits to anything.
+pub fn load_position(position_info: &AccountInfo) -> Result<UserPosition> {
+    if position_info.data_is_empty() {
+        return Ok(UserPosition::default());
+    }
+    Ok(Account::<UserPosition>::try_from(position_info)?.into_inner())
+}
+
+/// Writes `position` back to `position_info`, creating the account on the
+/// buyer's first buy into the curve. `position` must already be opened, so
+/// its bump signs for `[b"user_position", buyer, token_launch]`.
+pub fn store_position<'info>(
+    program_id: &Pubkey,
+    position_info: &AccountInfo<'info>,
+    buyer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    position: &UserPosition,
+) -> Result<()> {
+    if position_info.data_is_empty() {
+        utils::create_pda_account(
+            buyer,
+            position_info,
+            system_program,
+            UserPosition::LEN,
+            program_id,
+            &[b"user_position", buyer.key.as_ref(), position.token_launch.as_ref(), &[position.bump]],
+        )?;
+    }
+    position.try_serialize(&mut &mut position_info.try_borrow_mut_data()?[..])
+}
+
+/// A priced buy: what the buyer pays, to whom, and what they receive.
+pub struct BuyQuote {
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub creator_fee: u64,
+    pub platform_fee: u64,
+    /// SOL credited to the curve's reserves
+    pub net_sol_amount: u64,
+}
+
+/// Creator plus platform fee, in bps, `buyer` pays on a buy of `sol_amount`
+/// from `curve`, before any governance discount.
+pub fn buy_fee_bps(global_state: &GlobalState, curve: &BondingCurve, buyer: &Pubkey, sol_amount: u64) -> u16 {
+    let creator_fee_bps = match global_state.waive_creator_self_fee && *buyer == curve.creator {
+        true => 0,
+        false => curve.creator_fee_bps,
+    };
+    creator_fee_bps.saturating_add(curve.trading_fee_bps(utils::fee_tier_bps(
+        &global_state.platform_fee_tiers,
+        sol_amount,
+        curve.platform_fee_bps,
+    )))
+}
+
+/// Prices `leg` on `curve` with the fees the buyer actually pays, failing
+/// with `SlippageExceeded` when it returns less than `leg.min_tokens_out`.
+/// Tokens are priced on the SOL left after fees, which is what reaches the
+/// curve. `governance_balance` is the buyer's governance token balance, if
+/// they passed their account for the holder discount.
+pub fn quote_buy(
+    global_state: &GlobalState,
+    curve: &BondingCurve,
+    buyer: &Pubkey,
+    leg: &BuyLeg,
+    governance_balance: Option<u64>,
+) -> Result<BuyQuote> {
+    let creator_fee = utils::creator_fee_for_buyer(
+        utils::calculate_fee(leg.sol_amount, curve.creator_fee_bps)?,
+        buyer,
+        &curve.creator,
+        global_state.waive_creator_self_fee,
+    );
+    let platform_fee = utils::governance_discounted_fee(
+        utils::calculate_fee(
+            leg.sol_amount,
+            curve.trading_fee_bps(utils::fee_tier_bps(
+                &global_state.platform_fee_tiers,
+                leg.sol_amount,
+                curve.platform_fee_bps,
+            )),
+        )?,
+        global_state.governance_fee_discount_bps,
+        governance_balance,
+        global_state.governance_min_balance,
+    )?;
+    let net_sol_amount = leg
+        .sol_amount
+        .checked_sub(creator_fee)
+        .and_then(|amount| amount.checked_sub(platform_fee))
+        .ok_or(PumpCloneError::NumericalOverflow)?;
+
+    let token_amount = utils::calculate_buy_amount(
+        net_sol_amount,
+        curve.virtual_sol_reserves,
+        curve.virtual_token_reserves,
+    )?;
+    if token_amount == 0 || token_amount < leg.min_tokens_out {
+        return Err(utils::UtilsError::SlippageExceeded.into());
+    }
+
+    Ok(BuyQuote {
+        sol_amount: leg.sol_amount,
+        token_amount,
+        creator_fee,
+        platform_fee,
+        net_sol_amount,
+    })
+}
+
+/// The accounts a priced buy moves SOL and tokens between.
+pub struct BuyAccounts<'a, 'info> {
+    pub buyer: &'a AccountInfo<'info>,
+    pub bonding_curve: &'a AccountInfo<'info>,
+    pub curve_token_account: &'a AccountInfo<'info>,
+    pub buyer_token_account: &'a AccountInfo<'info>,
+    pub sol_vault: &'a AccountInfo<'info>,
+    pub fee_vault: &'a AccountInfo<'info>,
+    /// The launch's creator fee destination
+    pub creator: &'a AccountInfo<'info>,
+    pub community_wallet: Option<&'a AccountInfo<'info>>,
+    pub token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+impl BuyAccounts<'_, '_> {
+    /// Pays `quote` out of the buyer, hands over the tokens and books the
+    /// buy on the curve, the buyer's position and the platform totals.
+    #[allow(clippy::too_many_arguments)]
+    pub fn settle(
+        &self,
+        global_state: &mut GlobalState,
+        token_launch: &TokenLaunch,
+        curve: &mut BondingCurve,
+        position: Option<&mut UserPosition>,
+        quote: &BuyQuote,
+        decimals: u8,
+        now: i64,
+    ) -> Result<()> {
+        for (to, amount) in [(self.sol_vault, quote.net_sol_amount), (self.fee_vault, quote.platform_fee)] {
+            if amount > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        self.system_program.clone(),
+                        anchor_lang::system_program::Transfer {
+                            from: self.buyer.clone(),
+                            to: to.clone(),
+                        },
+                    ),
+                    amount,
+                )?;
+            }
+        }
+        pay_creator_fee(
+            self.system_program,
+            self.buyer,
+            token_launch,
+            self.creator,
+            self.community_wallet,
+            quote.creator_fee,
+        )?;
+        if quote.platform_fee > 0 {
+            global_state.record_fees_collected(quote.platform_fee)?;
+        }
+
+        let buyer_balance_before = token::accessor::amount(self.buyer_token_account)?;
+        token::transfer(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                Transfer {
+                    from: self.curve_token_account.clone(),
+                    to: self.buyer_token_account.clone(),
+                    authority: self.bonding_curve.clone(),
+                },
+                &[&[BONDING_CURVE_SEED, curve.mint.as_ref(), &[curve.bump]]],
+            ),
+            quote.token_amount,
+        )?;
+        curve.holders_count = utils::apply_holder_delta(
+            curve.holders_count,
+            buyer_balance_before,
+            buyer_balance_before.saturating_add(quote.token_amount),
+            utils::holder_balance_threshold(global_state.min_holder_balance, decimals),
+        );
+
+        let price_before = utils::canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves)?;
+        if let Some(position) = position {
+            position.update_after_buy(quote.net_sol_amount, quote.token_amount, price_before)?;
+        }
+        if let Some(completed) = curve.settle_buy(
+            quote.net_sol_amount,
+            quote.token_amount,
+            quote.sol_amount,
+            crate::FUNDING_GOAL,
+            global_state.migration_threshold,
+            now,
+        )? {
+            emit!(completed);
+        }
+        global_state.record_volume(quote.sol_amount)?;
+
+        let price = utils::canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves)?;
+        emit!(Trade {
+            side: TradeDirection::Buy,
+            wallet: self.buyer.key(),
+            mint: curve.mint,
+            sol_amount: quote.sol_amount,
+            token_amount: quote.token_amount,
+            creator_fee: quote.creator_fee,
+            platform_fee: quote.platform_fee,
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            virtual_token_reserves: curve.virtual_token_reserves,
+            real_sol_reserves: curve.real_sol_reserves,
+            real_token_reserves: curve.real_token_reserves,
+            price,
+            price_impact_bps: utils::price_impact_bps(price_before, price),
+            timestamp: now,
+            schema_version: Trade::SCHEMA_VERSION,
+        });
+        emit!(TokensPurchased {
+            buyer: self.buyer.key(),
+            mint: curve.mint,
+            sol_amount: quote.sol_amount,
+            token_amount: quote.token_amount,
+            creator_fee: quote.creator_fee,
+            platform_fee: quote.platform_fee,
+            virtual_sol_reserves: curve.virtual_sol_reserves,
+            virtual_token_reserves: curve.virtual_token_reserves,
+            price_before,
+            price_after: price,
+            // The buyer paid fees on top of what reached the curve
+            effective_price: utils::effective_price(quote.sol_amount, quote.token_amount)?,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke;
+
+use crate::error::PumpCloneError;
+use crate::state::MigrationTarget;
+
+mod raydium_amm_v4 {
+    anchor_lang::declare_id!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+}
+mod serum_dex {
+    anchor_lang::declare_id!("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX");
+}
+
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = raydium_amm_v4::ID;
+/// The order-book program Raydium AMM v4 pools list their market on
+pub const SERUM_DEX_PROGRAM_ID: Pubkey = serum_dex::ID;
+
+/// The pool (amm id) Raydium AMM v4 creates for `market`. Raydium derives
+/// it rather than taking a caller-chosen address.
+pub fn raydium_amm_id(amm_program: &Pubkey, market: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[amm_program.as_ref(), market.as_ref(), b"amm_associated_seed"],
+        amm_program,
+    )
+    .0
+}
+
+/// Seeds a graduated curve's liquidity into one AMM.
+pub trait DexAdapter {
+    /// Program the pool-init CPI is sent to
+    fn program_id(&self) -> Pubkey;
+
+    /// Creates the pool with `coin_amount` tokens against `pc_amount`
+    /// lamports of wSOL and returns its address.
+    fn create_pool(&self, coin_amount: u64, pc_amount: u64) -> Result<Pubkey>;
+}
+
+#[derive(AnchorSerialize)]
+struct RaydiumInitialize {
+    nonce: u8,
+    open_time: u64,
+    init_pc_amount: u64,
+    init_coin_amount: u64,
+}
+
+/// Raydium AMM v4 pool init. `accounts` are passed through in the order the
+/// AMM expects them.
+pub struct RaydiumAdapter<'a, 'info> {
+    pub program: &'a AccountInfo<'info>,
+    pub amm_pool: Pubkey,
+    pub accounts: &'a [AccountInfo<'info>],
+    pub open_time: u64,
+}
+
+impl DexAdapter for RaydiumAdapter<'_, '_> {
+    fn program_id(&self) -> Pubkey {
+        *self.program.key
+    }
+
+    fn create_pool(&self, coin_amount: u64, pc_amount: u64) -> Result<Pubkey> {
+        let data = RaydiumInitialize {
+            nonce: 0,
+            open_time: self.open_time,
+            init_pc_amount: pc_amount,
+            init_coin_amount: coin_amount,
+        }
+        .try_to_vec()?;
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                if account.is_writable {
+                    AccountMeta::new(*account.key, account.is_signer)
+                } else {
+                    AccountMeta::new_readonly(*account.key, account.is_signer)
+                }
+            })
+            .collect();
+
+        let mut account_infos = self.accounts.to_vec();
+        account_infos.push(self.program.clone());
+        invoke(
+            &Instruction {
+                program_id: *self.program.key,
+                accounts,
+                data,
+            },
+            &account_infos,
+        )?;
+
+        Ok(self.amm_pool)
+    }
+}
+
+/// Picks the adapter for `target`, rejecting a `program` that isn't that
+/// target's AMM.
+pub fn adapter_for<'a, 'info>(
+    target: MigrationTarget,
+    program: &'a AccountInfo<'info>,
+    amm_pool: Pubkey,
+    accounts: &'a [AccountInfo<'info>],
+    open_time: u64,
+) -> Result<Box<dyn DexAdapter + 'a>> {
+    require_keys_eq!(*program.key, target.program_id(), PumpCloneError::InvalidProgramAccount);
+    Ok(match target {
+        MigrationTarget::Raydium => Box::new(RaydiumAdapter {
+            program,
+            amm_pool,
+            accounts,
+            open_time,
+        }),
+    })
+}
@@ -17,6 +17,12 @@ pub enum PumpCloneError {
     #[msg("Invalid token metadata URI")]
     InvalidMetadataUri,
     
+    #[msg("Token metadata is locked")]
+    MetadataLocked,
+    
+    #[msg("Metadata account is not the mint's Metaplex PDA")]
+    InvalidMetadataAccount,
+    
     #[msg("Token already exists")]
     TokenAlreadyExists,
     
@@ -56,6 +62,12 @@ pub enum PumpCloneError {
     #[msg("Slippage tolerance exceeded")]
     SlippageToleranceExceeded,
     
+    #[msg("Invalid slippage tolerance")]
+    InvalidSlippageTolerance,
+    
+    #[msg("Default slippage requested but no user settings were provided")]
+    DefaultSlippageNotSet,
+    
     #[msg("Trading not active")]
     TradingNotActive,
     
@@ -86,9 +98,24 @@ pub enum PumpCloneError {
     #[msg("Liquidity already migrated")]
     LiquidityAlreadyMigrated,
     
+    #[msg("Bonding curve already graduated")]
+    AlreadyGraduated,
+    
+    #[msg("Bonding curve not graduated")]
+    NotGraduated,
+    
     #[msg("Insufficient liquidity for migration")]
     InsufficientLiquidityForMigration,
     
+    #[msg("Market cap below migration minimum")]
+    MarketCapBelowMinimum,
+    
+    #[msg("Migration price outside the TWAP band")]
+    MigrationPriceOutOfBand,
+    
+    #[msg("Curve has not migrated")]
+    CurveNotMigrated,
+    
     #[msg("Invalid migration parameters")]
     InvalidMigrationParameters,
     
@@ -122,9 +149,18 @@ pub enum PumpCloneError {
     #[msg("Invalid token account")]
     InvalidTokenAccount,
     
+    #[msg("Legacy seed does not derive an orphaned curve for this mint")]
+    InvalidLegacyCurveSeed,
+    
+    #[msg("Token account is frozen by the mint's freeze authority; thaw it before selling")]
+    TokenAccountFrozen,
+    
     #[msg("Invalid token mint")]
     InvalidTokenMint,
     
+    #[msg("Quote mint not allowed")]
+    QuoteMintNotAllowed,
+    
     #[msg("Invalid associated token account")]
     InvalidAssociatedTokenAccount,
     
@@ -134,6 +170,9 @@ pub enum PumpCloneError {
     #[msg("Insufficient token balance")]
     InsufficientTokenBalance,
     
+    #[msg("Cannot merge a position into itself")]
+    PositionMergeSelf,
+    
     #[msg("Invalid program account")]
     InvalidProgramAccount,
     
@@ -158,6 +197,9 @@ pub enum PumpCloneError {
     #[msg("Account not initialized")]
     AccountNotInitialized,
     
+    #[msg("Invalid remaining accounts")]
+    InvalidRemainingAccounts,
+    
     #[msg("Invalid account size")]
     InvalidAccountSize,
     
@@ -197,6 +239,9 @@ pub enum PumpCloneError {
     #[msg("Operation too early")]
     OperationTooEarly,
     
+    #[msg("Invalid trading window")]
+    InvalidTradingWindow,
+    
     #[msg("Invalid configuration")]
     InvalidConfiguration,
     
@@ -206,9 +251,48 @@ pub enum PumpCloneError {
     #[msg("Program paused")]
     ProgramPaused,
     
+    #[msg("This instruction is paused")]
+    InstructionPaused,
+    
+    #[msg("Global kill switch is engaged")]
+    KillSwitchEngaged,
+    
+    #[msg("Creator fees owed to an earlier destination must be claimed first")]
+    UnclaimedCreatorFees,
+    
+    #[msg("Curve is in refund mode; use claim_refund instead of trading")]
+    RefundModeActive,
+    
+    #[msg("Curve is not in refund mode")]
+    RefundModeNotActive,
+    
+    #[msg("Bonding curve already migrated")]
+    AlreadyMigrated,
+    
+    #[msg("Position still holds tokens")]
+    PositionNotEmpty,
+    
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    
     #[msg("Program not paused")]
     ProgramNotPaused,
     
+    #[msg("Buys are disabled; only sells are open")]
+    SellsOnlyMode,
+    
+    #[msg("Sell would exceed this wallet's cap on SOL extracted from the curve")]
+    SellExtractionCapExceeded,
+    
+    #[msg("Sell tax plus trading fees exceed the maximum")]
+    SellTaxTooHigh,
+    
+    #[msg("Sell tax and floor price can only be lowered once trading starts")]
+    SellTaxLocked,
+    
+    #[msg("Price is not below the floor")]
+    PriceAboveFloor,
+    
     #[msg("Emergency mode active")]
     EmergencyModeActive,
     
@@ -293,6 +377,12 @@ pub enum PumpCloneError {
     #[msg("Reward not available")]
     RewardNotAvailable,
     
+    #[msg("Reward round still accruing")]
+    RewardRoundOpen,
+    
+    #[msg("Too many positions in one batch")]
+    RewardBatchTooLarge,
+    
     #[msg("Invalid airdrop parameters")]
     InvalidAirdropParameters,
     
@@ -317,12 +407,18 @@ pub enum PumpCloneError {
     #[msg("Insufficient vested amount")]
     InsufficientVestedAmount,
     
+    #[msg("Sell vesting escrow account required")]
+    SellVestingEscrowRequired,
+    
     #[msg("Invalid lock period")]
     InvalidLockPeriod,
     
     #[msg("Tokens still locked")]
     TokensStillLocked,
     
+    #[msg("Not enough buys to sell yet")]
+    InsufficientBuysBeforeSell,
+    
     #[msg("Lock already released")]
     LockAlreadyReleased,
     
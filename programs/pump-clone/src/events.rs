@@ -0,0 +1,315 @@
+use anchor_lang::prelude::*;
+use crate::state::TradeDirection;
+
+/// Canonical record of a buy or sell. Supersedes `TokensPurchased` and
+/// `TokensSold`, which are still emitted alongside it while indexers move over.
+#[event]
+pub struct Trade {
+    pub side: TradeDirection,
+    pub wallet: Pubkey,
+    pub mint: Pubkey,
+    /// Gross SOL in for a buy, gross SOL out of the curve for a sell
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub creator_fee: u64,
+    pub platform_fee: u64,
+    /// Reserves once the trade has settled
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    /// Spot price after the trade, scaled by `PRICE_SCALE`
+    pub price: u64,
+    pub price_impact_bps: u64,
+    pub timestamp: i64,
+    pub schema_version: u8,
+}
+
+impl Trade {
+    pub const SCHEMA_VERSION: u8 = 1;
+}
+
+/// A buy, in the shape indexers consumed before `Trade`. Prices are scaled by
+/// `PRICE_SCALE`; `effective_price` is what the buyer paid per token, fees
+/// included.
+#[event]
+pub struct TokensPurchased {
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub creator_fee: u64,
+    pub platform_fee: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub effective_price: u64,
+    pub timestamp: i64,
+}
+
+/// A sell, in the shape indexers consumed before `Trade`. `effective_price`
+/// is what the seller received per token after fees and sell tax.
+#[event]
+pub struct TokensSold {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub creator_fee: u64,
+    pub platform_fee: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub price_before: u64,
+    pub price_after: u64,
+    pub effective_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenGraduated {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub real_sol_reserves: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+}
+
+/// A curve stopped trading. Figures are final and also kept on the curve.
+#[event]
+pub struct CurveCompleted {
+    pub mint: Pubkey,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub total_volume: u64,
+    pub holders_count: u32,
+    /// Seconds from launch to completion
+    pub time_to_completion: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurvePricingNormalized {
+    pub mint: Pubkey,
+    pub old_k_constant: u128,
+    pub new_k_constant: u128,
+    pub old_last_price: u64,
+    pub new_last_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub sol_amount: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MetadataLocked {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MetadataUpdated {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    /// The launch's metadata and socials after the update
+    pub uri: String,
+    pub image_uri: String,
+    pub website: String,
+    pub twitter: String,
+    pub telegram: String,
+    pub discord: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PortfolioSummary {
+    pub user: Pubkey,
+    pub positions: u32,
+    pub sol_invested: u64,
+    pub realized_profit: i64,
+    pub unrealized_profit: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeMilestoneReached {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub total_trades: u64,
+    pub bonus: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveClosed {
+    pub mint: Pubkey,
+    pub closed_by: Pubkey,
+    pub rent_recipient: Pubkey,
+    /// Lamports above the closed accounts' rent, swept to the fee vault
+    pub to_treasury: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ValuationReport {
+    pub mint: Pubkey,
+    pub price: u64,
+    pub total_supply: u64,
+    pub circulating_supply: u64,
+    pub fully_diluted_valuation: u64,
+    pub market_cap: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CurveTokensRescued {
+    pub mint: Pubkey,
+    pub orphaned_token_account: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct YieldEstimate {
+    pub mint: Pubkey,
+    pub locked_value: u64,
+    pub fees_accrued: u64,
+    pub elapsed_seconds: i64,
+    pub apy_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReservesAttested {
+    pub mint: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub slot: u64,
+    pub digest: [u8; 32],
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FloorBuybackExecuted {
+    pub mint: Pubkey,
+    pub sol_amount: u64,
+    pub tokens_burned: u64,
+    pub spot_price: u64,
+    pub floor_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LaunchCancelled {
+    pub mint: Pubkey,
+    pub creator: Pubkey,
+    pub tokens_burned: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub fee_recipient: Pubkey,
+    pub amount: u64,
+    pub total_fees_withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundModeSet {
+    pub mint: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RefundClaimed {
+    pub mint: Pubkey,
+    pub holder: Pubkey,
+    pub token_amount: u64,
+    pub sol_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SlippageRealized {
+    pub mint: Pubkey,
+    pub direction: TradeDirection,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub slippage_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityMigrated {
+    pub mint: Pubkey,
+    pub raydium_pool: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub virtual_sol_reserves: u64,
+    pub virtual_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BreakEven {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub token_balance: u64,
+    pub average_buy_price: u64,
+    pub break_even_price: u64,
+    pub current_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PauseToggled {
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GraduationDeferred {
+    pub mint: Pubkey,
+    pub last_graduation_slot: u64,
+    pub next_eligible_slot: u64,
+    pub timestamp: i64,
+}
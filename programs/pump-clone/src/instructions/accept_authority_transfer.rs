@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthorityTransfer<'info> {
+    pub pending_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Completes a rotation started by `propose_authority_transfer`.
+pub fn handler(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    let previous_authority = global_state.authority;
+    global_state.accept_authority(&ctx.accounts.pending_authority.key())?;
+
+    emit!(AuthorityTransferAccepted {
+        previous_authority,
+        authority: global_state.authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        constraint = global_state.creator_liquidity_enabled @ PumpCloneError::FeatureDisabled
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator,
+        constraint = !bonding_curve.complete @ PumpCloneError::BondingCurveCompleted
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, bonding_curve.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adding liquidity moves the curve's price like a buy, so it is held to the
+/// same pause rules.
+pub fn handler(ctx: Context<AddLiquidity>, sol_amount: u64) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    ctx.accounts.global_state.check_buys_allowed()?;
+    require!(sol_amount > 0, PumpCloneError::PurchaseAmountTooSmall);
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.creator.to_account_info(),
+                to: ctx.accounts.sol_vault.to_account_info(),
+            },
+        ),
+        sol_amount,
+    )?;
+
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    bonding_curve.add_liquidity(sol_amount)?;
+    bonding_curve.updated_at = Clock::get()?.unix_timestamp;
+
+    emit!(LiquidityAdded {
+        mint: bonding_curve.mint,
+        creator: ctx.accounts.creator.key(),
+        sol_amount,
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        timestamp: bonding_curve.updated_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct AttestReserves<'info> {
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+/// Snapshots the curve's real reserves at the current slot for publishing as
+/// proof-of-reserves. The digest binds the values to the curve and slot, so
+/// anyone can recompute it from the event and the account history.
+pub fn handler(ctx: Context<AttestReserves>) -> Result<ReserveAttestation> {
+    let clock = Clock::get()?;
+    let attestation = ctx
+        .accounts
+        .bonding_curve
+        .attest_reserves(ctx.accounts.bonding_curve.key(), clock.slot);
+
+    emit!(ReservesAttested {
+        mint: ctx.accounts.bonding_curve.mint,
+        bonding_curve: attestation.bonding_curve,
+        real_sol_reserves: attestation.real_sol_reserves,
+        real_token_reserves: attestation.real_token_reserves,
+        slot: attestation.slot,
+        digest: attestation.digest,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(attestation)
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::events::*;
+use super::initialize_global_state::fund_vault;
+use super::update_global_state::{apply_params, UpdateGlobalStateParams};
+
+#[derive(Accounts)]
+pub struct Bootstrap<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = GlobalState::LEN,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Platform fee vault shared by every curve
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates and fully configures `GlobalState` in one transaction, replacing
+/// `initialize_global_state` followed by a round of `update_global_state`
+/// calls. Only runs against an uninitialized account.
+pub fn handler(
+    ctx: Context<Bootstrap>,
+    config: BootstrapConfig,
+    settings: UpdateGlobalStateParams,
+) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.bootstrap(ctx.accounts.authority.key(), &config, ctx.bumps.global_state)?;
+    apply_params(global_state, settings)?;
+    fund_vault(
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.fee_vault.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(GlobalStateInitialized {
+        authority: global_state.authority,
+        fee_recipient: global_state.fee_recipient,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::{break_even_price, canonical_price};
+
+#[derive(Accounts)]
+pub struct BreakEvenPrice<'info> {
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        seeds = [b"user_position", user_position.user.as_ref(), token_launch.key().as_ref()],
+        bump = user_position.bump,
+        constraint = user_position.mint == token_launch.mint @ PumpCloneError::InvalidTokenMint
+    )]
+    pub user_position: Account<'info, UserPosition>,
+}
+
+/// Returns the spot price, scaled by `PRICE_SCALE`, at which selling the
+/// position nets zero PnL at the curve's current fee rates. Size-based fee
+/// tiers and the governance discount are not applied. Zero when the position
+/// holds no tokens.
+pub fn handler(ctx: Context<BreakEvenPrice>) -> Result<u64> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    let position = &ctx.accounts.user_position;
+
+    let trading_fee_bps = bonding_curve
+        .creator_fee_bps
+        .saturating_add(bonding_curve.trading_fee_bps(bonding_curve.platform_fee_bps));
+    let sell_fee_bps = trading_fee_bps.saturating_add(ctx.accounts.token_launch.sell_tax_bps);
+    let price = break_even_price(
+        position.token_balance,
+        position.average_buy_price,
+        trading_fee_bps,
+        sell_fee_bps,
+    )?;
+
+    emit!(BreakEven {
+        user: position.user,
+        mint: position.mint,
+        token_balance: position.token_balance,
+        average_buy_price: position.average_buy_price,
+        break_even_price: price,
+        current_price: canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(price)
+}
@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::buy::{self, BuyAccounts, BuyGuard};
+use crate::pda::{self, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct BuyTokensBatch<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: The buyer's blacklist PDA; empty when never listed
+    #[account(
+        seeds = [b"blacklist", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_blacklist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Platform fee vault, drained to `fee_recipient` by `withdraw_fees`
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    /// The buyer's own referral record; raises their per-wallet buy cap
+    #[account(
+        seeds = [b"referral", buyer.key().as_ref()],
+        bump = buyer_referral.bump
+    )]
+    pub buyer_referral: Option<Account<'info, Referral>>,
+
+    /// Exempts a registered market maker from per-wallet cooldowns and daily limits
+    #[account(
+        seeds = [b"market_maker", buyer.key().as_ref()],
+        bump = market_maker.bump
+    )]
+    pub market_maker: Option<Account<'info, MarketMaker>>,
+
+    /// CHECK: the instructions sysvar, required while the atomic trade guard is on
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    /// Qualifies the buyer for the governance-holder fee discount on every leg
+    #[account(
+        token::mint = global_state.governance_mint,
+        token::authority = buyer
+    )]
+    pub governance_token_account: Option<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Buys into several curves in one transaction. Remaining accounts are passed
+/// in groups of `BuyLeg::ACCOUNTS_PER_LEG`, one group per leg in `legs`, in
+/// the order listed on `BuyLeg::ACCOUNTS_PER_LEG`.
+///
+/// Each leg runs the same guards as `buy_tokens` (trading hours, whitelist
+/// phase, wallet throttle, atomic trade guard, anti-snipe cap, deadline),
+/// pays its creator fee the same way, and books the buy on the buyer's
+/// position, creating it once the leg's guards pass on their first buy into
+/// that curve. Any leg short of its `min_tokens_out` fails the whole batch.
+/// Legs skip the holder rewards, referral and milestone routing of a single
+/// buy.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyTokensBatch<'info>>,
+    legs: Vec<BuyLeg>,
+    deadline: i64,
+) -> Result<()> {
+    require!(!legs.is_empty(), PumpCloneError::InvalidAmount);
+    require!(
+        ctx.remaining_accounts.len() == legs.len() * BuyLeg::ACCOUNTS_PER_LEG,
+        PumpCloneError::InvalidRemainingAccounts
+    );
+
+    let buyer = ctx.accounts.buyer.key();
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    for (leg, accounts) in legs.iter().zip(ctx.remaining_accounts.chunks(BuyLeg::ACCOUNTS_PER_LEG)) {
+        let [curve_info, mint_info, curve_token_account, sol_vault, buyer_token_account, launch_info, creator, community_wallet, position_info, buyer_whitelist] =
+            accounts
+        else {
+            return err!(PumpCloneError::InvalidRemainingAccounts);
+        };
+        let mut bonding_curve: Account<BondingCurve> = Account::try_from(curve_info)?;
+        let mint: Account<Mint> = Account::try_from(mint_info)?;
+        let token_launch: Account<TokenLaunch> = Account::try_from(launch_info)?;
+        let mint_key = mint.key();
+
+        require_keys_eq!(bonding_curve.mint, mint_key, PumpCloneError::InvalidTokenMint);
+        let expected_curve = pda::bonding_curve(&mint_key).0;
+        require_keys_eq!(curve_info.key(), expected_curve, PumpCloneError::InvalidPdaDerivation);
+        require_keys_eq!(sol_vault.key(), pda::sol_vault(&mint_key).0, PumpCloneError::InvalidPdaDerivation);
+        require_keys_eq!(
+            launch_info.key(),
+            Pubkey::find_program_address(&[b"token_launch", mint_key.as_ref()], ctx.program_id).0,
+            PumpCloneError::InvalidPdaDerivation
+        );
+        require_keys_eq!(token_launch.mint, mint_key, PumpCloneError::InvalidTokenMint);
+        require_keys_eq!(
+            buyer_whitelist.key(),
+            Pubkey::find_program_address(&[b"whitelist", mint_key.as_ref(), buyer.as_ref()], ctx.program_id).0,
+            PumpCloneError::InvalidPdaDerivation
+        );
+        require_keys_eq!(
+            curve_token_account.key(),
+            get_associated_token_address(&expected_curve, &mint_key),
+            PumpCloneError::InvalidAssociatedTokenAccount
+        );
+        require_keys_eq!(
+            buyer_token_account.key(),
+            get_associated_token_address(&buyer, &mint_key),
+            PumpCloneError::InvalidAssociatedTokenAccount
+        );
+
+        let (expected_position, position_bump) = Pubkey::find_program_address(
+            &[b"user_position", buyer.as_ref(), launch_info.key().as_ref()],
+            ctx.program_id,
+        );
+        require_keys_eq!(position_info.key(), expected_position, PumpCloneError::InvalidPdaDerivation);
+        let mut user_position = buy::load_position(position_info)?;
+
+        let guard = BuyGuard {
+            program_id: ctx.program_id,
+            global_state: &ctx.accounts.global_state,
+            token_launch: &token_launch,
+            buyer,
+            bonding_curve: curve_info.key(),
+            buyer_blacklist: &ctx.accounts.buyer_blacklist.to_account_info(),
+            buyer_whitelist,
+            market_maker: ctx.accounts.market_maker.as_deref(),
+            instructions_sysvar: ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+            referrals: ctx
+                .accounts
+                .buyer_referral
+                .as_ref()
+                .map_or(0, |referral| referral.successful_referrals),
+        };
+        guard.check_before_quote(&mut bonding_curve, Some(&mut user_position), leg.sol_amount, deadline, &clock)?;
+
+        let quote = buy::quote_buy(
+            &ctx.accounts.global_state,
+            &bonding_curve,
+            &buyer,
+            leg,
+            ctx.accounts.governance_token_account.as_ref().map(|account| account.amount),
+        )?;
+        guard.check_quote(&bonding_curve, Some(&user_position), quote.token_amount, now)?;
+
+        // Like `buy_tokens`, the buyer's token account is only created once
+        // the leg has passed its guards
+        anchor_spl::associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            anchor_spl::associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: buyer_token_account.clone(),
+                authority: ctx.accounts.buyer.to_account_info(),
+                mint: mint_info.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
+        user_position.open(buyer, launch_info.key(), mint_key, bonding_curve.creator, position_bump);
+        BuyAccounts {
+            buyer: &ctx.accounts.buyer.to_account_info(),
+            bonding_curve: curve_info,
+            curve_token_account,
+            buyer_token_account,
+            sol_vault,
+            fee_vault: &ctx.accounts.fee_vault.to_account_info(),
+            creator,
+            community_wallet: Some(community_wallet),
+            token_program: &ctx.accounts.token_program.to_account_info(),
+            system_program: &ctx.accounts.system_program.to_account_info(),
+        }
+        .settle(
+            &mut ctx.accounts.global_state,
+            &token_launch,
+            &mut bonding_curve,
+            Some(&mut user_position),
+            &quote,
+            mint.decimals,
+            now,
+        )?;
+
+        bonding_curve.exit(ctx.program_id)?;
+        buy::store_position(
+            ctx.program_id,
+            position_info,
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            &user_position,
+        )?;
+    }
+
+    msg!("Batched buy of {} legs by {}", legs.len(), buyer);
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, CloseAccount, Mint, Token, TokenAccount};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct CancelLaunch<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(mut, address = token_launch.mint @ PumpCloneError::InvalidTokenMint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = bonding_curve
+    )]
+    pub bonding_curve_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, token_launch.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Aborts a launch before its first trade: burns the curve's supply and
+/// returns the rent of the launch, curve, token and vault accounts to the
+/// creator. The creation fee has already gone to the fee recipient and is
+/// not refunded.
+pub fn handler(ctx: Context<CancelLaunch>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    ctx.accounts.bonding_curve.check_cancellable()?;
+
+    let mint = ctx.accounts.token_launch.mint;
+    let curve_seeds: &[&[u8]] = &[BONDING_CURVE_SEED, mint.as_ref(), &[ctx.accounts.bonding_curve.bump]];
+
+    let tokens_burned = ctx.accounts.bonding_curve_token_account.amount;
+    if tokens_burned > 0 {
+        token::burn(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.bonding_curve_token_account.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                &[curve_seeds],
+            ),
+            tokens_burned,
+        )?;
+    }
+
+    token::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.bonding_curve_token_account.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: ctx.accounts.bonding_curve.to_account_info(),
+        },
+        &[curve_seeds],
+    ))?;
+
+    let vault_balance = ctx.accounts.sol_vault.lamports();
+    if vault_balance > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.sol_vault.to_account_info(),
+                    to: ctx.accounts.creator.to_account_info(),
+                },
+                &[&[SOL_VAULT_SEED, mint.as_ref(), &[ctx.bumps.sol_vault]]],
+            ),
+            vault_balance,
+        )?;
+    }
+
+    emit!(LaunchCancelled {
+        mint,
+        creator: ctx.accounts.creator.key(),
+        tokens_burned,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
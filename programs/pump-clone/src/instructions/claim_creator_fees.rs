@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use crate::pda::{GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    pub claimer: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, token_launch.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = token_launch.fee_destination() @ PumpCloneError::InvalidFeeRecipient
+    )]
+    pub fee_destination: SystemAccount<'info>,
+
+    /// Collects fees still owed to the destination in place before the last
+    /// change; without it those fees stay pending.
+    #[account(
+        mut,
+        address = token_launch.prior_fee_destination @ PumpCloneError::InvalidFeeRecipient
+    )]
+    pub prior_fee_destination: Option<SystemAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays accrued creator fees out of the SOL vault. Anyone may crank it since
+/// funds only ever go to the recorded destinations.
+pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+
+    let mint = ctx.accounts.token_launch.mint;
+    let vault_seeds: &[&[u8]] = &[SOL_VAULT_SEED, mint.as_ref(), &[ctx.bumps.sol_vault]];
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    let mut payouts = vec![(
+        ctx.accounts.fee_destination.to_account_info(),
+        ctx.accounts.token_launch.take_accrued_creator_fees(),
+    )];
+    if let Some(prior_fee_destination) = ctx.accounts.prior_fee_destination.as_ref() {
+        payouts.push((
+            prior_fee_destination.to_account_info(),
+            ctx.accounts.token_launch.take_prior_creator_fees(),
+        ));
+    }
+
+    for (destination, amount) in payouts {
+        if amount == 0 {
+            continue;
+        }
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.sol_vault.to_account_info(),
+                    to: destination.clone(),
+                },
+                &[vault_seeds],
+            ),
+            amount,
+        )?;
+        emit!(CreatorFeesClaimed {
+            mint,
+            destination: destination.key(),
+            amount,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
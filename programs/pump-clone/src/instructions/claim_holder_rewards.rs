@@ -0,0 +1,286 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::buy::BuyGuard;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::Trade;
+use crate::utils::{self, holder_reward_share, BondingCurveState};
+
+#[derive(Accounts)]
+pub struct ClaimHolderRewards<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"holder_rewards", holder_rewards.mint.as_ref()],
+        bump = holder_rewards.bump,
+        constraint = !holder_rewards.round_open @ PumpCloneError::RewardRoundOpen
+    )]
+    pub holder_rewards: Account<'info, HolderRewards>,
+
+    #[account(
+        mut,
+        has_one = user @ PumpCloneError::Unauthorized,
+        constraint = user_position.mint == holder_rewards.mint @ PumpCloneError::InvalidTokenMint
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        seeds = [b"user_settings", user.key().as_ref()],
+        bump = user_settings.bump
+    )]
+    pub user_settings: Option<Account<'info, UserSettings>>,
+
+    /// The accounts below are only needed when compounding into tokens
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, holder_rewards.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Option<Account<'info, BondingCurve>>,
+
+    #[account(
+        seeds = [b"token_launch", holder_rewards.mint.as_ref()],
+        bump = token_launch.bump
+    )]
+    pub token_launch: Option<Account<'info, TokenLaunch>>,
+
+    /// CHECK: The user's blacklist PDA; empty when never listed
+    #[account(
+        seeds = [b"blacklist", user.key().as_ref()],
+        bump
+    )]
+    pub user_blacklist: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The user's whitelist PDA for this mint; empty when not listed
+    #[account(
+        seeds = [b"whitelist", holder_rewards.mint.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_whitelist: Option<UncheckedAccount<'info>>,
+
+    /// The user's own referral record; raises their per-wallet buy cap
+    #[account(
+        seeds = [b"referral", user.key().as_ref()],
+        bump = user_referral.bump
+    )]
+    pub user_referral: Option<Account<'info, Referral>>,
+
+    /// Exempts a registered market maker from per-wallet cooldowns and daily limits
+    #[account(
+        seeds = [b"market_maker", user.key().as_ref()],
+        bump = market_maker.bump
+    )]
+    pub market_maker: Option<Account<'info, MarketMaker>>,
+
+    /// CHECK: the instructions sysvar, required while the atomic trade guard is on
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, holder_rewards.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = holder_rewards.mint,
+        token::authority = bonding_curve
+    )]
+    pub curve_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = holder_rewards.mint,
+        token::authority = user
+    )]
+    pub user_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+pub fn handler(ctx: Context<ClaimHolderRewards>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let holder_rewards = &mut ctx.accounts.holder_rewards;
+    let user_position = &mut ctx.accounts.user_position;
+
+    require!(
+        user_position.reward_round == holder_rewards.round,
+        PumpCloneError::RewardNotAvailable
+    );
+    require!(
+        user_position.rewards_claimed_round < holder_rewards.round,
+        PumpCloneError::RewardAlreadyClaimed
+    );
+
+    let amount = holder_reward_share(
+        holder_rewards.round_rewards,
+        user_position.reward_weight,
+        holder_rewards.total_weight,
+    )?;
+    require!(amount > 0, PumpCloneError::RewardNotAvailable);
+
+    user_position.rewards_claimed_round = holder_rewards.round;
+    holder_rewards.round_claimed = holder_rewards
+        .round_claimed
+        .checked_add(amount)
+        .ok_or(PumpCloneError::NumericalOverflow)?;
+
+    let compound = ctx
+        .accounts
+        .user_settings
+        .as_ref()
+        .map_or(false, |settings| settings.compound_holder_rewards);
+    if !compound {
+        **holder_rewards.to_account_info().try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? += amount;
+
+        msg!(
+            "Claimed {} lamports of holder rewards for round {}",
+            amount,
+            holder_rewards.round
+        );
+        return Ok(());
+    }
+
+    // Compounding buys from the curve with the reward, fee-free since the
+    // reward was itself paid out of trading fees, but otherwise under the
+    // same guards as any other buy. A plain claim still works while buys
+    // are paused.
+    let (
+        Some(bonding_curve),
+        Some(token_launch),
+        Some(user_blacklist),
+        Some(user_whitelist),
+        Some(sol_vault),
+        Some(curve_token_account),
+        Some(user_token_account),
+        Some(token_program),
+    ) = (
+        ctx.accounts.bonding_curve.as_mut(),
+        ctx.accounts.token_launch.as_ref(),
+        ctx.accounts.user_blacklist.as_ref(),
+        ctx.accounts.user_whitelist.as_ref(),
+        ctx.accounts.sol_vault.as_ref(),
+        ctx.accounts.curve_token_account.as_ref(),
+        ctx.accounts.user_token_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    )
+    else {
+        return err!(PumpCloneError::AccountNotInitialized);
+    };
+
+    let clock = Clock::get()?;
+    let guard = BuyGuard {
+        program_id: ctx.program_id,
+        global_state: &ctx.accounts.global_state,
+        token_launch,
+        buyer: ctx.accounts.user.key(),
+        bonding_curve: bonding_curve.key(),
+        buyer_blacklist: user_blacklist.as_ref(),
+        buyer_whitelist: user_whitelist.as_ref(),
+        market_maker: ctx.accounts.market_maker.as_deref(),
+        instructions_sysvar: ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+        referrals: ctx
+            .accounts
+            .user_referral
+            .as_ref()
+            .map_or(0, |referral| referral.successful_referrals),
+    };
+    guard.check_before_quote(bonding_curve, Some(&mut **user_position), amount, 0, &clock)?;
+
+    let token_amount = BondingCurveState {
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+    }
+    .calculate_tokens_for_sol(amount)?;
+    require!(
+        token_amount <= bonding_curve.real_token_reserves,
+        PumpCloneError::InsufficientTokensForSale
+    );
+    guard.check_quote(bonding_curve, Some(&**user_position), token_amount, clock.unix_timestamp)?;
+
+    **holder_rewards.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **sol_vault.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    let mint = holder_rewards.mint;
+    let user_balance_before = user_token_account.amount;
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: curve_token_account.to_account_info(),
+                to: user_token_account.to_account_info(),
+                authority: bonding_curve.to_account_info(),
+            },
+            &[&[BONDING_CURVE_SEED, mint.as_ref(), &[bonding_curve.bump]]],
+        ),
+        token_amount,
+    )?;
+
+    // Booked like any other buy, so the compounded tokens count towards
+    // holders, volume and completion
+    let global_state = &mut ctx.accounts.global_state;
+    bonding_curve.holders_count = utils::apply_holder_delta(
+        bonding_curve.holders_count,
+        user_balance_before,
+        user_balance_before.saturating_add(token_amount),
+        utils::holder_balance_threshold(global_state.min_holder_balance, token_launch.decimals),
+    );
+    let price_before = utils::canonical_price(bonding_curve.virtual_sol_reserves, bonding_curve.virtual_token_reserves)?;
+    if let Some(completed) = bonding_curve.settle_buy(
+        amount,
+        token_amount,
+        amount,
+        crate::FUNDING_GOAL,
+        global_state.migration_threshold,
+        clock.unix_timestamp,
+    )? {
+        emit!(completed);
+    }
+    global_state.record_volume(amount)?;
+
+    let price = utils::canonical_price(bonding_curve.virtual_sol_reserves, bonding_curve.virtual_token_reserves)?;
+    emit!(Trade {
+        side: TradeDirection::Buy,
+        wallet: ctx.accounts.user.key(),
+        mint,
+        sol_amount: amount,
+        token_amount,
+        creator_fee: 0,
+        platform_fee: 0,
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+        price,
+        price_impact_bps: utils::price_impact_bps(price_before, price),
+        timestamp: clock.unix_timestamp,
+        schema_version: Trade::SCHEMA_VERSION,
+    });
+
+    user_position.checkpoint_reward_weight(clock.unix_timestamp);
+    user_position.credit_compounded_reward(token_amount, amount)?;
+
+    msg!(
+        "Compounded {} lamports of holder rewards into {} tokens for round {}",
+        amount,
+        token_amount,
+        holder_rewards.round
+    );
+
+    Ok(())
+}
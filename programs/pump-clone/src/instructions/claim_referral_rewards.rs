@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", owner.key().as_ref()],
+        bump = referral.bump,
+        has_one = owner @ PumpCloneError::Unauthorized
+    )]
+    pub referral: Account<'info, Referral>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Pays out the referral cut accrued on the owner's referral account.
+pub fn handler(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let referral = &mut ctx.accounts.referral;
+
+    let amount = std::mem::take(&mut referral.pending_rewards);
+    require!(amount > 0, PumpCloneError::RewardNotAvailable);
+
+    **referral.to_account_info().try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    msg!(
+        "Claimed {} referral lamports ({} earned in total)",
+        amount,
+        referral.total_rewards
+    );
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    pub holder: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(mut, address = bonding_curve.mint @ PumpCloneError::InvalidTokenMint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = holder
+    )]
+    pub holder_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, bonding_curve.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Burns `token_amount` of the holder's tokens and pays back their pro-rata
+/// share of the curve's real SOL. Only available in refund mode, where it
+/// replaces `sell_tokens`, and paused whenever sells are.
+pub fn handler(ctx: Context<ClaimRefund>, token_amount: u64) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    ctx.accounts.global_state.check_sells_allowed()?;
+    let sol_amount = ctx.accounts.bonding_curve.refund_amount(token_amount)?;
+
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.holder_token_account.to_account_info(),
+                authority: ctx.accounts.holder.to_account_info(),
+            },
+        ),
+        token_amount,
+    )?;
+
+    let mint = ctx.accounts.bonding_curve.mint;
+    if sol_amount > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.sol_vault.to_account_info(),
+                    to: ctx.accounts.holder.to_account_info(),
+                },
+                &[&[SOL_VAULT_SEED, mint.as_ref(), &[ctx.bumps.sol_vault]]],
+            ),
+            sol_amount,
+        )?;
+    }
+
+    ctx.accounts.bonding_curve.settle_refund(token_amount, sol_amount)?;
+
+    emit!(RefundClaimed {
+        mint,
+        holder: ctx.accounts.holder.key(),
+        token_amount,
+        sol_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", creator_vesting.mint.as_ref()],
+        bump = creator_vesting.bump,
+        has_one = creator @ PumpCloneError::Unauthorized
+    )]
+    pub creator_vesting: Account<'info, CreatorVesting>,
+
+    #[account(
+        mut,
+        token::mint = creator_vesting.mint,
+        token::authority = creator_vesting
+    )]
+    pub vesting_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = creator_vesting.mint,
+        token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Releases the creator's launch allocation vested so far, net of earlier
+/// claims. Fails with `VestingNotStarted` before the cliff.
+pub fn handler(ctx: Context<ClaimVested>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let now = Clock::get()?.unix_timestamp;
+    let creator_vesting = &mut ctx.accounts.creator_vesting;
+    let amount = creator_vesting.claim(now)?;
+
+    let mint = creator_vesting.mint;
+    let seeds: &[&[u8]] = &[b"vesting", mint.as_ref(), &[creator_vesting.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vesting_token_account.to_account_info(),
+                to: ctx.accounts.creator_token_account.to_account_info(),
+                authority: creator_vesting.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    msg!(
+        "Claimed {} vested tokens ({} of {})",
+        amount,
+        creator_vesting.claimed,
+        creator_vesting.total_amount
+    );
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct ClaimVestedProceeds<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"sell_vesting", sell_vesting_escrow.mint.as_ref(), seller.key().as_ref()],
+        bump = sell_vesting_escrow.bump,
+        constraint = sell_vesting_escrow.seller == seller.key() @ PumpCloneError::Unauthorized
+    )]
+    pub sell_vesting_escrow: Account<'info, SellVestingEscrow>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+pub fn handler(ctx: Context<ClaimVestedProceeds>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let escrow = &mut ctx.accounts.sell_vesting_escrow;
+    let now = Clock::get()?.unix_timestamp;
+
+    let claimable = escrow.claimable_amount(now);
+    require!(claimable > 0, PumpCloneError::InsufficientVestedAmount);
+
+    escrow.claimed_amount = escrow
+        .claimed_amount
+        .checked_add(claimable)
+        .ok_or(PumpCloneError::NumericalOverflow)?;
+
+    **escrow.to_account_info().try_borrow_mut_lamports()? -= claimable;
+    **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += claimable;
+
+    msg!(
+        "Claimed {} vested lamports ({} of {})",
+        claimable,
+        escrow.claimed_amount,
+        escrow.total_amount
+    );
+
+    Ok(())
+}
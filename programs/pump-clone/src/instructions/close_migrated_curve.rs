@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::vault_close_payouts;
+
+#[derive(Accounts)]
+pub struct CloseMigratedCurve<'info> {
+    pub closer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        mut,
+        close = rent_recipient,
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, token_launch.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// CHECK: Any account chosen by the closer to receive the rent
+    #[account(mut)]
+    pub rent_recipient: UncheckedAccount<'info>,
+
+    /// Platform fee vault; receives whatever the closed accounts hold above
+    /// their rent, and vault dust when `vault_dust_to_fee_recipient` is set
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Reclaims rent held by a migrated curve's launch, curve and SOL vault
+/// accounts. The rent recipient only gets the rent-exempt minimum of each;
+/// any SOL above that is swept to the platform fee vault, as is sub-rent dust
+/// in the SOL vault when configured.
+pub fn handler(ctx: Context<CloseMigratedCurve>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let closer = ctx.accounts.closer.key();
+    ctx.accounts
+        .token_launch
+        .check_curve_closable(&closer, &ctx.accounts.global_state.authority)?;
+
+    let rent = Rent::get()?;
+    let mint = ctx.accounts.token_launch.mint;
+    let (to_rent_recipient, mut to_treasury) = vault_close_payouts(
+        ctx.accounts.sol_vault.lamports(),
+        rent.minimum_balance(0),
+        ctx.accounts.global_state.vault_dust_to_fee_recipient,
+    );
+    let vault_seeds: &[&[u8]] = &[SOL_VAULT_SEED, mint.as_ref(), &[ctx.bumps.sol_vault]];
+    for (to, amount) in [
+        (ctx.accounts.rent_recipient.to_account_info(), to_rent_recipient),
+        (ctx.accounts.fee_vault.to_account_info(), to_treasury),
+    ] {
+        if amount > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.sol_vault.to_account_info(),
+                        to,
+                    },
+                    &[vault_seeds],
+                ),
+                amount,
+            )?;
+        }
+    }
+
+    // The launch and curve accounts are closed to the rent recipient on
+    // exit; take anything above their rent out first
+    let fee_vault = ctx.accounts.fee_vault.to_account_info();
+    for account in [
+        ctx.accounts.token_launch.to_account_info(),
+        ctx.accounts.bonding_curve.to_account_info(),
+    ] {
+        let excess = account
+            .lamports()
+            .saturating_sub(rent.minimum_balance(account.data_len()));
+        if excess > 0 {
+            **account.try_borrow_mut_lamports()? -= excess;
+            **fee_vault.try_borrow_mut_lamports()? += excess;
+            to_treasury = to_treasury
+                .checked_add(excess)
+                .ok_or(PumpCloneError::NumericalOverflow)?;
+        }
+    }
+    ctx.accounts.global_state.record_fees_collected(to_treasury)?;
+
+    emit!(CurveClosed {
+        mint,
+        closed_by: closer,
+        rent_recipient: ctx.accounts.rent_recipient.key(),
+        to_treasury,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
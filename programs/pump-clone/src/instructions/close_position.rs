@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    pub closer: Signer<'info>,
+
+    /// Owner of the position; always receives the rent
+    #[account(mut)]
+    pub user: SystemAccount<'info>,
+
+    /// CHECK: The position's launch. `close_migrated_curve` closes it once
+    /// the launch has migrated, so an empty account here means migrated.
+    #[account(address = user_position.token_launch @ PumpCloneError::InvalidPdaDerivation)]
+    pub token_launch: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"user_position", user.key().as_ref(), user_position.token_launch.as_ref()],
+        bump = user_position.bump,
+        has_one = user @ PumpCloneError::Unauthorized
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Closes an emptied `UserPosition` and refunds its rent to the position's
+/// owner. Once the launch has migrated anyone may sweep empty positions,
+/// including after `close_migrated_curve` has closed the launch itself.
+pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let token_launch = &ctx.accounts.token_launch;
+    let launch_migrated = token_launch.data_is_empty()
+        || Account::<TokenLaunch>::try_from(token_launch)?.is_migrated;
+    ctx.accounts.user_position.check_closable(
+        &ctx.accounts.closer.key(),
+        launch_migrated,
+    )?;
+
+    msg!(
+        "Closed position {} for {}",
+        ctx.accounts.user_position.key(),
+        ctx.accounts.user.key()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct CreateReferral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Referral::LEN,
+        seeds = [b"referral", owner.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateReferral>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let referral = &mut ctx.accounts.referral;
+    referral.owner = ctx.accounts.owner.key();
+    referral.successful_referrals = 0;
+    referral.bump = ctx.bumps.referral;
+    Ok(())
+}
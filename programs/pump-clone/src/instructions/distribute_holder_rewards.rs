@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct DistributeHolderRewards<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = HolderRewards::LEN,
+        seeds = [b"holder_rewards", bonding_curve.mint.as_ref()],
+        bump
+    )]
+    pub holder_rewards: Account<'info, HolderRewards>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Snapshots holder weights for the current round. `UserPosition` accounts
+/// are passed as writable remaining accounts, at most
+/// `HolderRewards::MAX_POSITIONS_PER_BATCH` per call; positions already
+/// counted this round are skipped. Each position's weight is the
+/// balance-seconds it checkpointed on-chain since the previous round, so a
+/// top-up just before the snapshot earns next to nothing. Passing `finalize`
+/// closes the round and locks the pending pool balance in for claims; only
+/// the platform authority may finalize, once it has cranked every position.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, DistributeHolderRewards<'info>>,
+    finalize: bool,
+) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    if finalize {
+        ctx.accounts.global_state.check_authority(&ctx.accounts.payer.key())?;
+    }
+    require!(
+        ctx.remaining_accounts.len() <= HolderRewards::MAX_POSITIONS_PER_BATCH,
+        PumpCloneError::RewardBatchTooLarge
+    );
+
+    let holder_rewards = &mut ctx.accounts.holder_rewards;
+    let mint = ctx.accounts.bonding_curve.mint;
+
+    if holder_rewards.mint == Pubkey::default() {
+        holder_rewards.mint = mint;
+        holder_rewards.bump = ctx.bumps.holder_rewards;
+    }
+
+    if !holder_rewards.round_open {
+        // Roll anything unclaimed from the previous round into the next one
+        let unclaimed = holder_rewards
+            .round_rewards
+            .checked_sub(holder_rewards.round_claimed)
+            .ok_or(PumpCloneError::NumericalOverflow)?;
+        holder_rewards.pending_rewards = holder_rewards
+            .pending_rewards
+            .checked_add(unclaimed)
+            .ok_or(PumpCloneError::NumericalOverflow)?;
+
+        holder_rewards.round = holder_rewards
+            .round
+            .checked_add(1)
+            .ok_or(PumpCloneError::NumericalOverflow)?;
+        holder_rewards.round_open = true;
+        holder_rewards.snapshot_timestamp = Clock::get()?.unix_timestamp;
+        holder_rewards.total_weight = 0;
+        holder_rewards.round_rewards = 0;
+        holder_rewards.round_claimed = 0;
+    }
+
+    for account_info in ctx.remaining_accounts.iter() {
+        let mut position: Account<UserPosition> = Account::try_from(account_info)?;
+        require_keys_eq!(position.mint, mint, PumpCloneError::InvalidTokenMint);
+
+        if position.reward_round == holder_rewards.round {
+            continue;
+        }
+
+        let weight = position.take_reward_weight(holder_rewards.snapshot_timestamp);
+
+        position.reward_round = holder_rewards.round;
+        position.reward_weight = weight;
+        holder_rewards.total_weight = holder_rewards
+            .total_weight
+            .checked_add(weight)
+            .ok_or(PumpCloneError::NumericalOverflow)?;
+
+        position.exit(ctx.program_id)?;
+    }
+
+    if finalize {
+        holder_rewards.round_open = false;
+        holder_rewards.round_rewards = holder_rewards.pending_rewards;
+        holder_rewards.pending_rewards = 0;
+
+        msg!(
+            "Holder rewards round {} finalized: {} lamports over weight {}",
+            holder_rewards.round,
+            holder_rewards.round_rewards,
+            holder_rewards.total_weight
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::{canonical_price, spot_value};
+
+#[derive(Accounts)]
+pub struct Fdv<'info> {
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(address = bonding_curve.mint @ PumpCloneError::InvalidTokenMint)]
+    pub mint: Account<'info, Mint>,
+}
+
+/// Returns the fully-diluted valuation in lamports: spot price times the
+/// mint's whole supply. The circulating market cap, which excludes tokens
+/// still held by the curve, is reported alongside it.
+pub fn handler(ctx: Context<Fdv>) -> Result<u64> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    let total_supply = ctx.accounts.mint.supply;
+    let circulating_supply = total_supply.saturating_sub(bonding_curve.real_token_reserves);
+
+    let fully_diluted_valuation = spot_value(
+        bonding_curve.virtual_sol_reserves,
+        bonding_curve.virtual_token_reserves,
+        total_supply,
+    )?;
+    let market_cap = spot_value(
+        bonding_curve.virtual_sol_reserves,
+        bonding_curve.virtual_token_reserves,
+        circulating_supply,
+    )?;
+
+    emit!(ValuationReport {
+        mint: bonding_curve.mint,
+        price: canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?,
+        total_supply,
+        circulating_supply,
+        fully_diluted_valuation,
+        market_cap,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(fully_diluted_valuation)
+}
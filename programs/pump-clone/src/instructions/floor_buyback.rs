@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::{canonical_price, floor_buyback_amount, BondingCurveState};
+
+#[derive(Accounts)]
+pub struct FloorBuyback<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [b"price_floor", token_launch.mint.as_ref()],
+        bump
+    )]
+    pub price_floor_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [SOL_VAULT_SEED, token_launch.mint.as_ref()],
+        bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut, address = token_launch.mint @ PumpCloneError::InvalidTokenMint)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bonding_curve
+    )]
+    pub curve_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Permissionless: once the spot price drops below the creator's floor, the
+/// floor vault buys the price back up to the floor, or as far as its balance
+/// goes, and the tokens bought are burned. It is a
+/// buy, so it stops whenever buys are paused.
+pub fn handler(ctx: Context<FloorBuyback>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    ctx.accounts.global_state.check_buys_allowed()?;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    require!(!bonding_curve.complete, PumpCloneError::BondingCurveCompleted);
+
+    let spot_price = canonical_price(
+        bonding_curve.virtual_sol_reserves,
+        bonding_curve.virtual_token_reserves,
+    )?;
+    let sol_amount = floor_buyback_amount(
+        bonding_curve.virtual_sol_reserves,
+        bonding_curve.virtual_token_reserves,
+        ctx.accounts.token_launch.floor_price,
+        // The vault keeps its rent so it can go on collecting floor shares
+        ctx.accounts
+            .price_floor_vault
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0)),
+    )?;
+    require!(sol_amount > 0, PumpCloneError::PriceAboveFloor);
+
+    let token_amount = BondingCurveState {
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+    }
+    .calculate_tokens_for_sol(sol_amount)?;
+    require!(
+        token_amount > 0 && token_amount <= bonding_curve.real_token_reserves,
+        PumpCloneError::InsufficientTokensForSale
+    );
+
+    let mint = ctx.accounts.token_launch.mint;
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.price_floor_vault.to_account_info(),
+                to: ctx.accounts.sol_vault.to_account_info(),
+            },
+            &[&[b"price_floor", mint.as_ref(), &[ctx.bumps.price_floor_vault]]],
+        ),
+        sol_amount,
+    )?;
+
+    token::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.mint.to_account_info(),
+                from: ctx.accounts.curve_token_account.to_account_info(),
+                authority: bonding_curve.to_account_info(),
+            },
+            &[&[BONDING_CURVE_SEED, mint.as_ref(), &[bonding_curve.bump]]],
+        ),
+        token_amount,
+    )?;
+
+    // Settled like any other buy, then the burn leaves the supply for good
+    let now = Clock::get()?.unix_timestamp;
+    let global_state = &mut ctx.accounts.global_state;
+    if let Some(completed) = bonding_curve.settle_buy(
+        sol_amount,
+        token_amount,
+        sol_amount,
+        crate::FUNDING_GOAL,
+        global_state.migration_threshold,
+        now,
+    )? {
+        emit!(completed);
+    }
+    global_state.record_volume(sol_amount)?;
+    bonding_curve.tokens_burned = bonding_curve.tokens_burned
+        .checked_add(token_amount)
+        .ok_or(PumpCloneError::NumericalOverflow)?;
+    bonding_curve.total_supply = bonding_curve.total_supply
+        .checked_sub(token_amount)
+        .ok_or(PumpCloneError::NumericalOverflow)?;
+
+    emit!(FloorBuybackExecuted {
+        mint,
+        sol_amount,
+        tokens_burned: token_amount,
+        spot_price,
+        floor_price: ctx.accounts.token_launch.floor_price,
+        timestamp: now,
+    });
+
+    Ok(())
+}
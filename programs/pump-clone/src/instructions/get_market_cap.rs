@@ -0,0 +1,18 @@
+use anchor_lang::prelude::*;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct GetMarketCap<'info> {
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+/// Returns the circulating market cap in lamports through the transaction's
+/// return data. See `fdv` for the fully-diluted figure.
+pub fn handler(ctx: Context<GetMarketCap>) -> Result<u64> {
+    ctx.accounts.bonding_curve.market_cap()
+}
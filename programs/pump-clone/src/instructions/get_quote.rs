@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::utils::{fee_tier_bps, gross_trade_sol, quote_trade, BondingCurveState};
+
+#[derive(Accounts)]
+pub struct GetQuote<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [b"token_launch", bonding_curve.mint.as_ref()],
+        bump = token_launch.bump
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+/// Quotes a buy (`amount_in` lamports) or sell (`amount_in` tokens) against
+/// the curve's current reserves with the same math and fees as the trade
+/// handlers. Wallet-specific adjustments such as the governance discount are
+/// not applied. Mutates nothing; the quote is returned as return data.
+pub fn handler(ctx: Context<GetQuote>, direction: TradeDirection, amount_in: u64) -> Result<TradeQuote> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    let curve = BondingCurveState {
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+    };
+
+    let gross_sol = gross_trade_sol(&curve, direction, amount_in)?;
+    let platform_fee_bps = bonding_curve.trading_fee_bps(fee_tier_bps(
+        &ctx.accounts.global_state.platform_fee_tiers,
+        gross_sol,
+        bonding_curve.platform_fee_bps,
+    ));
+
+    let quote = quote_trade(
+        &curve,
+        direction,
+        amount_in,
+        bonding_curve.creator_fee_bps,
+        platform_fee_bps,
+        ctx.accounts.token_launch.sell_tax_bps,
+    )?;
+
+    msg!(
+        "Quote for {}: {} in, {} out, price after {}",
+        bonding_curve.mint,
+        quote.amount_in,
+        quote.amount_out,
+        quote.price_after
+    );
+
+    Ok(quote)
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::is_graduation_cooldown_elapsed;
+
+#[derive(Accounts)]
+pub struct Graduate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump,
+        constraint = bonding_curve.complete @ PumpCloneError::BondingCurveNotCompleted,
+        constraint = bonding_curve.graduated_slot == 0 @ PumpCloneError::AlreadyGraduated
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+pub fn handler(ctx: Context<Graduate>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let global_state = &mut ctx.accounts.global_state;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    let clock = Clock::get()?;
+
+    // Space out pool creations; a ready curve inside the cooldown is deferred
+    // rather than rejected so cranks can simply retry later.
+    if !is_graduation_cooldown_elapsed(
+        global_state.last_graduation_slot,
+        global_state.graduation_cooldown_slots,
+        clock.slot,
+    ) {
+        let next_eligible_slot = global_state
+            .last_graduation_slot
+            .saturating_add(global_state.graduation_cooldown_slots);
+
+        emit!(GraduationDeferred {
+            mint: bonding_curve.mint,
+            last_graduation_slot: global_state.last_graduation_slot,
+            next_eligible_slot,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Graduation deferred for {} until slot {}",
+            bonding_curve.mint,
+            next_eligible_slot
+        );
+
+        return Ok(());
+    }
+
+    bonding_curve.graduated_slot = clock.slot;
+    global_state.last_graduation_slot = clock.slot;
+
+    emit!(TokenGraduated {
+        mint: bonding_curve.mint,
+        bonding_curve: bonding_curve.key(),
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        slot: clock.slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
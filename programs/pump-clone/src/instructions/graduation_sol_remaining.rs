@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::utils::{gross_sol_for_net, REAL_SOL_RESERVES};
+
+#[derive(Accounts)]
+pub struct GraduationSolRemaining<'info> {
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+/// Returns the gross SOL buyers still need to spend, fees included, to push
+/// `real_sol_reserves` to the migration threshold at the current fee rate.
+pub fn handler(ctx: Context<GraduationSolRemaining>) -> Result<u64> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+
+    let net_needed = REAL_SOL_RESERVES.saturating_sub(bonding_curve.real_sol_reserves);
+    let gross = gross_sol_for_net(
+        net_needed,
+        bonding_curve.creator_fee_bps,
+        bonding_curve.trading_fee_bps(bonding_curve.platform_fee_bps),
+    )?;
+
+    msg!("Graduation requires {} lamports ({} net)", gross, net_needed);
+
+    Ok(gross)
+}
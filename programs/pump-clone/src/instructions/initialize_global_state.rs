@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct InitializeGlobalState<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = GlobalState::LEN,
+        seeds = [GLOBAL_STATE_SEED],
+        bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    /// Platform fee vault shared by every curve
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Tops a lamport vault (the platform fee vault, a curve's SOL vault) up to
+/// its rent-exempt minimum, so the first payment into it, however small,
+/// doesn't leave it below rent.
+pub fn fund_vault<'info>(
+    payer: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(vault.lamports());
+    if shortfall == 0 {
+        return Ok(());
+    }
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: payer.clone(),
+                to: vault.clone(),
+            },
+        ),
+        shortfall,
+    )
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{update_metadata_accounts_v2, Metadata, UpdateMetadataAccountsV2};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct LockMetadata<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator,
+        constraint = !token_launch.metadata_locked @ PumpCloneError::MetadataLocked
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// CHECK: Validated against the Metaplex PDA for the mint below
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+/// Irreversibly marks the token metadata immutable. The bonding curve is the
+/// update authority, so it signs the Metaplex CPI.
+pub fn handler(ctx: Context<LockMetadata>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let mint = ctx.accounts.token_launch.mint;
+
+    let (metadata_key, _) = Pubkey::find_program_address(
+        &[
+            b"metadata",
+            ctx.accounts.token_metadata_program.key().as_ref(),
+            mint.as_ref(),
+        ],
+        &ctx.accounts.token_metadata_program.key(),
+    );
+    require_keys_eq!(metadata_key, ctx.accounts.metadata.key(), PumpCloneError::InvalidMetadataAccount);
+
+    let seeds = &[
+        BONDING_CURVE_SEED.as_ref(),
+        mint.as_ref(),
+        &[ctx.accounts.bonding_curve.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    update_metadata_accounts_v2(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.bonding_curve.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        None,
+        None,
+        None,
+        Some(false),
+    )?;
+
+    let token_launch = &mut ctx.accounts.token_launch;
+    token_launch.metadata_locked = true;
+
+    emit!(MetadataLocked {
+        mint,
+        creator: ctx.accounts.creator.key(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, TokenAccount};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::{annualized_yield_bps, lp_position_yield};
+
+#[derive(Accounts)]
+pub struct LpYield<'info> {
+    #[account(
+        seeds = [b"lp_lock", lp_lock.mint.as_ref()],
+        bump = lp_lock.bump
+    )]
+    pub lp_lock: Account<'info, LpLock>,
+
+    #[account(address = lp_lock.lp_mint @ PumpCloneError::InvalidTokenMint)]
+    pub lp_mint: Account<'info, Mint>,
+
+    #[account(address = lp_lock.pool_sol_vault @ PumpCloneError::InvalidTokenAccount)]
+    pub pool_sol_vault: Account<'info, TokenAccount>,
+
+    #[account(address = lp_lock.pool_token_vault @ PumpCloneError::InvalidTokenAccount)]
+    pub pool_token_vault: Account<'info, TokenAccount>,
+}
+
+/// Estimates the annualized yield, in bps, of a migrated curve's locked LP
+/// position from the pool fees it has accrued since the lock was taken. The
+/// position and the pool's reserves are read from the lock and the pool's
+/// vaults; a lock with no elapsed time or no value reports zero.
+pub fn handler(ctx: Context<LpYield>) -> Result<u64> {
+    let lp_lock = &ctx.accounts.lp_lock;
+    let now = Clock::get()?.unix_timestamp;
+    let (locked_value, fees_accrued) = lp_position_yield(
+        lp_lock,
+        ctx.accounts.pool_sol_vault.amount,
+        ctx.accounts.pool_token_vault.amount,
+        ctx.accounts.lp_mint.supply,
+    )?;
+    let elapsed_seconds = now.saturating_sub(lp_lock.locked_at);
+    let apy_bps = annualized_yield_bps(fees_accrued, locked_value, elapsed_seconds);
+
+    emit!(YieldEstimate {
+        mint: lp_lock.mint,
+        locked_value,
+        fees_accrued,
+        elapsed_seconds,
+        apy_bps,
+        timestamp: now,
+    });
+
+    Ok(apy_bps)
+}
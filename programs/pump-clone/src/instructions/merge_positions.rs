@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct MergePositions<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        mut,
+        seeds = [b"user_position", user.key().as_ref(), token_launch.key().as_ref()],
+        bump = user_position.bump,
+        has_one = user @ PumpCloneError::Unauthorized,
+        constraint = user_position.mint == token_launch.mint @ PumpCloneError::InvalidTokenMint
+    )]
+    pub user_position: Account<'info, UserPosition>,
+
+    #[account(
+        mut,
+        close = user,
+        has_one = user @ PumpCloneError::Unauthorized,
+        constraint = duplicate_position.mint == user_position.mint @ PumpCloneError::InvalidTokenMint,
+        constraint = duplicate_position.key() != user_position.key() @ PumpCloneError::PositionMergeSelf
+    )]
+    pub duplicate_position: Account<'info, UserPosition>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Folds a stray `UserPosition` into the canonical PDA for the same user and
+/// token, then closes the stray and refunds its rent to the user.
+pub fn handler(ctx: Context<MergePositions>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let duplicate = &ctx.accounts.duplicate_position;
+    let user_position = &mut ctx.accounts.user_position;
+
+    user_position.merge(duplicate)?;
+
+    msg!(
+        "Merged position {} into {}",
+        duplicate.key(),
+        user_position.key()
+    );
+
+    Ok(())
+}
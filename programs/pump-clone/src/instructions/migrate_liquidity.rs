@@ -1,49 +1,72 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer, Mint};
 use anchor_spl::associated_token::AssociatedToken;
+use crate::dex::SERUM_DEX_PROGRAM_ID;
+use crate::error::PumpCloneError as PumpError;
+use crate::events::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
 use crate::state::*;
-use crate::errors::*;
-use crate::constants::*;
+use anchor_spl::token::spl_token::instruction::AuthorityType;
+use crate::utils::authorities_to_renounce;
+use super::preview_migration::migration_amounts;
 
 #[derive(Accounts)]
 pub struct MigrateLiquidity<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
     #[account(
         mut,
         seeds = [BONDING_CURVE_SEED, token_mint.key().as_ref()],
         bump = bonding_curve.bump,
-        constraint = bonding_curve.is_complete @ PumpError::CurveNotComplete,
+        constraint = bonding_curve.complete @ PumpError::BondingCurveNotCompleted,
         constraint = !bonding_curve.migrated @ PumpError::AlreadyMigrated,
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
 
     #[account(
         mut,
-        constraint = token_mint.key() == bonding_curve.token_mint @ PumpError::InvalidTokenMint,
+        seeds = [b"token_launch", token_mint.key().as_ref()],
+        bump = token_launch.bump,
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        mut,
+        address = bonding_curve.mint @ PumpError::InvalidTokenMint,
     )]
     pub token_mint: Account<'info, Mint>,
 
+    /// The curve's unsold supply, where `create_token` minted it
     #[account(
         mut,
-        seeds = [CURVE_TOKEN_ACCOUNT_SEED, bonding_curve.key().as_ref()],
-        bump,
-        constraint = curve_token_account.mint == token_mint.key() @ PumpError::InvalidTokenAccount,
-        constraint = curve_token_account.owner == bonding_curve.key() @ PumpError::InvalidTokenAccount,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
     )]
     pub curve_token_account: Account<'info, TokenAccount>,
 
+    /// The SOL the curve raised, as paid in by buys
     #[account(
         mut,
-        seeds = [CURVE_SOL_VAULT_SEED, bonding_curve.key().as_ref()],
+        seeds = [SOL_VAULT_SEED, token_mint.key().as_ref()],
         bump,
     )]
     pub curve_sol_vault: SystemAccount<'info>,
 
-    /// CHECK: Raydium AMM program ID
+    /// CHECK: AMM program for `global_state.migration_target`; Raydium by default
     #[account(
-        constraint = raydium_amm_program.key() == RAYDIUM_AMM_PROGRAM_ID @ PumpError::InvalidProgram,
+        constraint = raydium_amm_program.key() == global_state.migration_target.program_id() @ PumpError::InvalidProgramAccount,
     )]
     pub raydium_amm_program: UncheckedAccount<'info>,
 
+    /// CHECK: AMM pool recorded on the launch. For Raydium this is the amm
+    /// id, checked against the address Raydium derives from `serum_market`
+    #[account(mut)]
+    pub amm_pool: UncheckedAccount<'info>,
+
     /// CHECK: Raydium AMM authority
     #[account(mut)]
     pub amm_authority: UncheckedAccount<'info>,
@@ -56,6 +79,26 @@ pub struct MigrateLiquidity<'info> {
     #[account(mut)]
     pub lp_mint: UncheckedAccount<'info>,
 
+    #[account(
+        init,
+        payer = payer,
+        space = LpLock::LEN,
+        seeds = [b"lp_lock", token_mint.key().as_ref()],
+        bump
+    )]
+    pub lp_lock: Account<'info, LpLock>,
+
+    /// CHECK: The lock's LP token account, created once the AMM has minted
+    /// the LP supply
+    #[account(
+        mut,
+        address = anchor_spl::associated_token::get_associated_token_address(
+            &lp_lock.key(),
+            &lp_mint.key()
+        ) @ PumpError::InvalidTokenAccount
+    )]
+    pub lp_lock_token_account: UncheckedAccount<'info>,
+
     /// CHECK: Raydium AMM coin vault
     #[account(mut)]
     pub coin_vault: UncheckedAccount<'info>,
@@ -82,7 +125,7 @@ pub struct MigrateLiquidity<'info> {
 
     /// CHECK: Serum program
     #[account(
-        constraint = serum_program.key() == SERUM_PROGRAM_ID @ PumpError::InvalidProgram,
+        constraint = serum_program.key() == SERUM_DEX_PROGRAM_ID @ PumpError::InvalidProgramAccount,
     )]
     pub serum_program: UncheckedAccount<'info>,
 
@@ -117,7 +160,7 @@ pub struct MigrateLiquidity<'info> {
 
     #[account(
         mut,
-        constraint = wsol_mint.key() == WSOL_MINT @ PumpError::InvalidMint,
+        address = anchor_spl::token::spl_token::native_mint::ID @ PumpError::InvalidTokenMint,
     )]
     pub wsol_mint: Account<'info, Mint>,
 
@@ -148,6 +191,17 @@ pub struct MigrateLiquidity<'info> {
     /// CHECK: Migration authority for Raydium
     pub migration_authority: UncheckedAccount<'info>,
 
+    /// CHECK: The migration authority's LP token account, which Raydium
+    /// creates and mints the initial LP supply into
+    #[account(
+        mut,
+        address = anchor_spl::associated_token::get_associated_token_address(
+            &migration_authority.key(),
+            &lp_mint.key()
+        ) @ PumpError::InvalidTokenAccount
+    )]
+    pub migration_lp_account: UncheckedAccount<'info>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -159,7 +213,6 @@ pub struct MigrateLiquidity<'info> {
 
 impl<'info> MigrateLiquidity<'info> {
     pub fn transfer_tokens_to_migration(&self, amount: u64) -> Result<()> {
-        let bonding_curve_key = self.bonding_curve.key();
         let seeds = &[
             BONDING_CURVE_SEED,
             self.token_mint.key().as_ref(),
@@ -179,31 +232,40 @@ impl<'info> MigrateLiquidity<'info> {
         token::transfer(cpi_ctx, amount)
     }
 
-    pub fn wrap_sol(&self, amount: u64) -> Result<()> {
-        let bonding_curve_key = self.bonding_curve.key();
-        
-        // Transfer SOL from vault to curve's WSOL account
-        let transfer_instruction = anchor_lang::solana_program::system_instruction::transfer(
-            &self.curve_sol_vault.key(),
-            &self.curve_wsol_account.key(),
-            amount,
-        );
-
+    pub fn renounce_authority(&self, authority_type: AuthorityType) -> Result<()> {
         let seeds = &[
-            CURVE_SOL_VAULT_SEED,
-            bonding_curve_key.as_ref(),
-            &[self.bonding_curve.sol_vault_bump],
+            BONDING_CURVE_SEED,
+            self.token_mint.key().as_ref(),
+            &[self.bonding_curve.bump],
         ];
         let signer_seeds = &[&seeds[..]];
 
-        anchor_lang::solana_program::program::invoke_signed(
-            &transfer_instruction,
-            &[
-                self.curve_sol_vault.to_account_info(),
-                self.curve_wsol_account.to_account_info(),
+        let cpi_accounts = token::SetAuthority {
+            current_authority: self.bonding_curve.to_account_info(),
+            account_or_mint: self.token_mint.to_account_info(),
+        };
+
+        let cpi_program = self.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        token::set_authority(cpi_ctx, authority_type, None)
+    }
+
+    /// Moves `amount` lamports from the curve's SOL vault, whose bump is
+    /// `sol_vault_bump`, into its wSOL account.
+    pub fn wrap_sol(&self, amount: u64, sol_vault_bump: u8) -> Result<()> {
+        let mint_key = self.token_mint.key();
+        let seeds = &[SOL_VAULT_SEED, mint_key.as_ref(), &[sol_vault_bump]];
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
                 self.system_program.to_account_info(),
-            ],
-            signer_seeds,
+                anchor_lang::system_program::Transfer {
+                    from: self.curve_sol_vault.to_account_info(),
+                    to: self.curve_wsol_account.to_account_info(),
+                },
+                &[&seeds[..]],
+            ),
+            amount,
         )?;
 
         // Sync wrapped SOL account
@@ -223,8 +285,34 @@ impl<'info> MigrateLiquidity<'info> {
         Ok(())
     }
 
+    /// Accounts for Raydium AMM v4 `initialize`, in the order the AMM expects
+    pub fn raydium_pool_accounts(&self) -> Vec<AccountInfo<'info>> {
+        vec![
+            self.token_program.to_account_info(),
+            self.associated_token_program.to_account_info(),
+            self.system_program.to_account_info(),
+            self.rent.to_account_info(),
+            self.amm_pool.to_account_info(),
+            self.amm_authority.to_account_info(),
+            self.amm_open_orders.to_account_info(),
+            self.lp_mint.to_account_info(),
+            self.token_mint.to_account_info(),
+            self.wsol_mint.to_account_info(),
+            self.coin_vault.to_account_info(),
+            self.pc_vault.to_account_info(),
+            self.withdraw_queue.to_account_info(),
+            self.amm_target_orders.to_account_info(),
+            self.pool_temp_lp.to_account_info(),
+            self.serum_program.to_account_info(),
+            self.serum_market.to_account_info(),
+            self.migration_token_account.to_account_info(),
+            self.migration_wsol_account.to_account_info(),
+            self.migration_authority.to_account_info(),
+            self.migration_lp_account.to_account_info(),
+        ]
+    }
+
     pub fn transfer_wsol_to_migration(&self, amount: u64) -> Result<()> {
-        let bonding_curve_key = self.bonding_curve.key();
         let seeds = &[
             BONDING_CURVE_SEED,
             self.token_mint.key().as_ref(),
@@ -246,107 +334,141 @@ impl<'info> MigrateLiquidity<'info> {
 }
 
 pub fn handler(ctx: Context<MigrateLiquidity>) -> Result<()> {
+    crate::profile_marker!("migrate_liquidity:start");
+    ctx.accounts.global_state.check_migrations_allowed()?;
+    let migration_threshold = ctx.accounts.global_state.migration_threshold;
     let bonding_curve = &mut ctx.accounts.bonding_curve;
     let clock = Clock::get()?;
 
-    // Verify curve is complete and ready for migration
-    require!(bonding_curve.is_complete, PumpError::CurveNotComplete);
-    require!(!bonding_curve.migrated, PumpError::AlreadyMigrated);
-    require!(
-        bonding_curve.total_supply >= CURVE_COMPLETE_TOKEN_AMOUNT,
-        PumpError::InsufficientTokensForMigration
-    );
-
-    // Calculate migration amounts
-    let token_balance = ctx.accounts.curve_token_account.amount;
-    let sol_balance = ctx.accounts.curve_sol_vault.lamports();
-
-    require!(token_balance > 0, PumpError::InsufficientTokenBalance);
-    require!(sol_balance > 0, PumpError::InsufficientSolBalance);
-
-    // Reserve tokens for migration (20% of total supply)
-    let migration_token_amount = bonding_curve.total_supply
-        .checked_mul(MIGRATION_TOKEN_PERCENTAGE)
-        .ok_or(PumpError::MathOverflow)?
-        .checked_div(100)
-        .ok_or(PumpError::MathOverflow)?;
-
-    // Reserve SOL for migration (matching the bonding curve completion amount)
-    let migration_sol_amount = CURVE_COMPLETE_SOL_AMOUNT;
-
-    require!(
-        token_balance >= migration_token_amount,
-        PumpError::InsufficientTokensForMigration
-    );
-    require!(
-        sol_balance >= migration_sol_amount,
-        PumpError::InsufficientSolForMigration
-    );
+    // Verify curve is complete and ready for migration, and claim it.
+    // Persisting the flag now means any later read of the account, a
+    // re-entrant migrate included, already sees it migrated.
+    bonding_curve.begin_migration(migration_threshold)?;
+    bonding_curve.exit(ctx.program_id)?;
+
+    // Every funding, valuation and price check runs before the first CPI so
+    // the pool is never seeded with one side only; a failure in any later
+    // step reverts the whole transaction, transfers included.
+    let MigrationPreview {
+        token_amount: migration_token_amount,
+        sol_amount: migration_sol_amount,
+    } = migration_amounts(
+        &ctx.accounts.global_state,
+        bonding_curve,
+        ctx.accounts.curve_token_account.amount,
+        ctx.accounts.curve_sol_vault.lamports(),
+        clock.unix_timestamp,
+    )?;
 
     // Transfer tokens to migration authority
     ctx.accounts.transfer_tokens_to_migration(migration_token_amount)?;
 
     // Wrap SOL and transfer to migration authority
-    ctx.accounts.wrap_sol(migration_sol_amount)?;
+    ctx.accounts.wrap_sol(migration_sol_amount, ctx.bumps.curve_sol_vault)?;
     ctx.accounts.transfer_wsol_to_migration(migration_sol_amount)?;
 
-    // Create Raydium AMM pool instruction data
-    let initialize_instruction_data = raydium_amm::instruction::Initialize {
-        nonce: 0,
-        open_time: clock.unix_timestamp as u64,
-        init_pc_amount: migration_sol_amount,
-        init_coin_amount: migration_token_amount,
-    };
-
-    // Build accounts for Raydium AMM initialization
-    let raydium_accounts = vec![
-        AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.associated_token_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.system_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.rent.key(), false),
-        AccountMeta::new(ctx.accounts.amm_authority.key(), false),
-        AccountMeta::new(ctx.accounts.amm_open_orders.key(), false),
-        AccountMeta::new(ctx.accounts.lp_mint.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.token_mint.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.wsol_mint.key(), false),
-        AccountMeta::new(ctx.accounts.coin_vault.key(), false),
-        AccountMeta::new(ctx.accounts.pc_vault.key(), false),
-        AccountMeta::new(ctx.accounts.withdraw_queue.key(), false),
-        AccountMeta::new(ctx.accounts.amm_target_orders.key(), false),
-        AccountMeta::new(ctx.accounts.pool_temp_lp.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.serum_program.key(), false),
-        AccountMeta::new_readonly(ctx.accounts.serum_market.key(), false),
-        AccountMeta::new(ctx.accounts.migration_token_account.key(), true),
-        AccountMeta::new(ctx.accounts.migration_wsol_account.key(), true),
-        AccountMeta::new_readonly(ctx.accounts.migration_authority.key(), true),
-    ];
-
-    // Create Raydium AMM pool
-    let initialize_instruction = anchor_lang::solana_program::instruction::Instruction {
-        program_id: ctx.accounts.raydium_amm_program.key(),
-        accounts: raydium_accounts,
-        data: initialize_instruction_data.try_to_vec()?,
-    };
-
-    anchor_lang::solana_program::program::invoke(
-        &initialize_instruction,
-        &[
-            ctx.accounts.raydium_amm_program.to_account_info(),
+    crate::profile_marker!("migrate_liquidity:funded");
+
+    // Hand the token over to the market: no more minting or freezing
+    for authority_type in authorities_to_renounce(
+        ctx.accounts.global_state.renounce_authorities_on_migration,
+        &ctx.accounts.bonding_curve.key(),
+        ctx.accounts.token_mint.mint_authority.into(),
+        ctx.accounts.token_mint.freeze_authority.into(),
+    ) {
+        ctx.accounts.renounce_authority(authority_type)?;
+    }
+
+    // Link the launch to its pool; a failed pool creation below reverts this
+    let amm_pool = ctx.accounts.amm_pool.key();
+    ctx.accounts.token_launch.record_migration(amm_pool, clock.unix_timestamp)?;
+
+    // LP tokens land in the lock rather than with the migration authority
+    let lp_lock_duration = ctx.accounts.global_state.lp_lock_duration;
+    ctx.accounts.lp_lock.set_inner(LpLock {
+        mint: ctx.accounts.token_mint.key(),
+        lp_mint: ctx.accounts.lp_mint.key(),
+        creator: ctx.accounts.bonding_curve.creator,
+        unlock_ts: clock.unix_timestamp.saturating_add(lp_lock_duration),
+        locked_at: clock.unix_timestamp,
+        lp_amount: 0,
+        pool_sol_vault: ctx.accounts.pc_vault.key(),
+        pool_token_vault: ctx.accounts.coin_vault.key(),
+        seed_sol_amount: migration_sol_amount,
+        seed_token_amount: migration_token_amount,
+        seed_lp_supply: 0,
+        bump: ctx.bumps.lp_lock,
+    });
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    emit!(LiquidityMigrated {
+        mint: ctx.accounts.token_mint.key(),
+        raydium_pool: amm_pool,
+        sol_amount: migration_sol_amount,
+        token_amount: migration_token_amount,
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+        timestamp: clock.unix_timestamp,
+    });
+
+    // Raydium derives the pool id from the market, so only that address
+    // can be the pool the launch records
+    let migration_target = ctx.accounts.global_state.migration_target;
+    match migration_target {
+        MigrationTarget::Raydium => require_keys_eq!(
+            amm_pool,
+            crate::dex::raydium_amm_id(&ctx.accounts.raydium_amm_program.key(), &ctx.accounts.serum_market.key()),
+            PumpError::InvalidPdaDerivation
+        ),
+    }
+
+    // Seed the pool through the configured target's adapter
+    let amm_program = ctx.accounts.raydium_amm_program.to_account_info();
+    let pool_accounts = ctx.accounts.raydium_pool_accounts();
+    crate::dex::adapter_for(
+        migration_target,
+        &amm_program,
+        amm_pool,
+        &pool_accounts,
+        clock.unix_timestamp as u64,
+    )?
+    .create_pool(migration_token_amount, migration_sol_amount)?;
+
+    // Move the freshly minted LP into the lock, which only exists once the
+    // AMM has created the LP mint
+    anchor_spl::associated_token::create(CpiContext::new(
+        ctx.accounts.associated_token_program.to_account_info(),
+        anchor_spl::associated_token::Create {
+            payer: ctx.accounts.payer.to_account_info(),
+            associated_token: ctx.accounts.lp_lock_token_account.to_account_info(),
+            authority: ctx.accounts.lp_lock.to_account_info(),
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+    let lp_amount = token::accessor::amount(&ctx.accounts.migration_lp_account.to_account_info())?;
+    require!(lp_amount > 0, PumpError::InvalidTokenAccount);
+    token::transfer(
+        CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
-            ctx.accounts.associated_token_program.to_account_info(),
-            ctx.accounts.system_program.to_account_info(),
-            ctx.accounts.rent.to_account_info(),
-            ctx.accounts.amm_authority.to_account_info(),
-            ctx.accounts.amm_open_orders.to_account_info(),
-            ctx.accounts.lp_mint.to_account_info(),
-            ctx.accounts.token_mint.to_account_info(),
-            ctx.accounts.wsol_mint.to_account_info(),
-            ctx.accounts.coin_vault.to_account_info(),
-            ctx.accounts.pc_vault.to_account_info(),
-            ctx.accounts.withdraw_queue.to_account_info(),
-            ctx.accounts.amm_target_orders.to_account_info(),
-            ctx.accounts.pool_temp_lp.to_account_info(),
-            ctx.accounts.serum_program.to_account_info(),
-            ctx.accounts.serum_market.to_account_info(),
-            ctx.accounts.migration_token_account.to_account_info(),
-            ctx.accounts.
\ No newline at end of file
+            Transfer {
+                from: ctx.accounts.migration_lp_account.to_account_info(),
+                to: ctx.accounts.lp_lock_token_account.to_account_info(),
+                authority: ctx.accounts.migration_authority.to_account_info(),
+            },
+        ),
+        lp_amount,
+    )?;
+    require!(
+        token::accessor::amount(&ctx.accounts.lp_lock_token_account.to_account_info())? == lp_amount,
+        PumpError::InvalidTokenAccount
+    );
+    let lp_supply = Mint::try_deserialize(&mut &ctx.accounts.lp_mint.try_borrow_data()?[..])?.supply;
+    ctx.accounts.lp_lock.lp_amount = lp_amount;
+    ctx.accounts.lp_lock.seed_lp_supply = lp_supply;
+
+    crate::profile_marker!("migrate_liquidity:end");
+    Ok(())
+}
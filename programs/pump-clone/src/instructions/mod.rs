@@ -1,17 +1,109 @@
 pub mod create_token;
 pub mod buy_tokens;
 pub mod sell_tokens;
-pub mod migrate_to_raydium;
+pub mod migrate_liquidity;
 pub mod initialize_global_state;
 pub mod update_global_state;
 pub mod withdraw_fees;
 pub mod set_token_metadata;
+pub mod graduate;
+pub mod claim_vested_proceeds;
+pub mod normalize_curve_pricing;
+pub mod set_trading_hours;
+pub mod distribute_holder_rewards;
+pub mod claim_holder_rewards;
+pub mod add_liquidity;
+pub mod lock_metadata;
+pub mod graduation_sol_remaining;
+pub mod merge_positions;
+pub mod user_portfolio;
+pub mod close_migrated_curve;
+pub mod create_referral;
+pub mod set_user_settings;
+pub mod fdv;
+pub mod set_creator_fee_split;
+pub mod token_price;
+pub mod set_market_maker;
+pub mod rescue_curve_tokens;
+pub mod bootstrap;
+pub mod lp_yield;
+pub mod attest_reserves;
+pub mod set_sell_tax;
+pub mod floor_buyback;
+pub mod cancel_launch;
+pub mod get_quote;
+pub mod set_creator_fee_destination;
+pub mod claim_creator_fees;
+pub mod set_refund_mode;
+pub mod claim_refund;
+pub mod realized_slippage;
+pub mod propose_authority_transfer;
+pub mod accept_authority_transfer;
+pub mod set_paused;
+pub mod update_token_metadata;
+pub mod break_even;
+pub mod close_position;
+pub mod get_market_cap;
+pub mod claim_vested;
+pub mod unlock_lp;
+pub mod register_referral;
+pub mod claim_referral_rewards;
+pub mod set_blacklist;
+pub mod set_whitelist;
+pub mod buy_tokens_batch;
+pub mod preview_migration;
 
 pub use create_token::*;
 pub use buy_tokens::*;
 pub use sell_tokens::*;
-pub use migrate_to_raydium::*;
+pub use migrate_liquidity::*;
 pub use initialize_global_state::*;
 pub use update_global_state::*;
 pub use withdraw_fees::*;
-pub use set_token_metadata::*;
\ No newline at end of file
+pub use set_token_metadata::*;
+pub use graduate::*;
+pub use claim_vested_proceeds::*;
+pub use normalize_curve_pricing::*;
+pub use set_trading_hours::*;
+pub use distribute_holder_rewards::*;
+pub use claim_holder_rewards::*;
+pub use add_liquidity::*;
+pub use lock_metadata::*;
+pub use graduation_sol_remaining::*;
+pub use merge_positions::*;
+pub use user_portfolio::*;
+pub use close_migrated_curve::*;
+pub use create_referral::*;
+pub use set_user_settings::*;
+pub use fdv::*;
+pub use set_creator_fee_split::*;
+pub use token_price::*;
+pub use set_market_maker::*;
+pub use rescue_curve_tokens::*;
+pub use bootstrap::*;
+pub use lp_yield::*;
+pub use attest_reserves::*;
+pub use set_sell_tax::*;
+pub use floor_buyback::*;
+pub use cancel_launch::*;
+pub use get_quote::*;
+pub use set_creator_fee_destination::*;
+pub use claim_creator_fees::*;
+pub use set_refund_mode::*;
+pub use claim_refund::*;
+pub use realized_slippage::*;
+pub use propose_authority_transfer::*;
+pub use accept_authority_transfer::*;
+pub use set_paused::*;
+pub use update_token_metadata::*;
+pub use break_even::*;
+pub use close_position::*;
+pub use get_market_cap::*;
+pub use claim_vested::*;
+pub use unlock_lp::*;
+pub use register_referral::*;
+pub use claim_referral_rewards::*;
+pub use set_blacklist::*;
+pub use set_whitelist::*;
+pub use buy_tokens_batch::*;
+pub use preview_migration::*;
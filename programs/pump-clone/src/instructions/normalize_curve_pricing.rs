@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct NormalizeCurvePricing<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+pub fn handler(ctx: Context<NormalizeCurvePricing>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+
+    let old_k_constant = bonding_curve.k_constant;
+    let old_last_price = bonding_curve.last_price;
+
+    bonding_curve.normalize_pricing()?;
+    bonding_curve.verify_invariants()?;
+
+    emit!(CurvePricingNormalized {
+        mint: bonding_curve.mint,
+        old_k_constant,
+        new_k_constant: bonding_curve.k_constant,
+        old_last_price,
+        new_last_price: bonding_curve.last_price,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use crate::error::PumpCloneError;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
+use crate::state::*;
+use crate::utils::{
+    meets_min_market_cap, plan_migration, safe_mul_div, within_price_band, BondingCurveState,
+    MigrationShortfall, PRICE_SCALE, REAL_SOL_RESERVES,
+};
+
+#[derive(Accounts)]
+pub struct PreviewMigration<'info> {
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        associated_token::mint = bonding_curve.mint,
+        associated_token::authority = bonding_curve
+    )]
+    pub curve_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [SOL_VAULT_SEED, bonding_curve.mint.as_ref()],
+        bump
+    )]
+    pub curve_sol_vault: SystemAccount<'info>,
+}
+
+/// Pool seed amounts for `bonding_curve` given its vault balances, after
+/// every check `migrate_liquidity` makes once the curve is claimed: the
+/// listing market cap, both vaults' funding, and the seed price against the
+/// TWAP. Shared with `migrate_liquidity` so the preview can't drift from what
+/// migrate moves.
+pub(crate) fn migration_amounts(
+    global_state: &GlobalState,
+    bonding_curve: &BondingCurve,
+    token_balance: u64,
+    sol_balance: u64,
+    now: i64,
+) -> Result<MigrationPreview> {
+    // Require a sensible listing valuation on top of the SOL threshold
+    let curve_state = BondingCurveState {
+        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+        virtual_token_reserves: bonding_curve.virtual_token_reserves,
+        real_sol_reserves: bonding_curve.real_sol_reserves,
+        real_token_reserves: bonding_curve.real_token_reserves,
+    };
+    let market_cap = curve_state.get_market_cap_for_supply(bonding_curve.total_supply)?;
+    require!(
+        meets_min_market_cap(market_cap, global_state.min_migration_market_cap),
+        PumpCloneError::MarketCapBelowMinimum
+    );
+
+    // 20% of total supply, against the SOL the curve completes at
+    let preview = match plan_migration(
+        bonding_curve.total_supply,
+        REAL_SOL_RESERVES,
+        token_balance,
+        sol_balance,
+        Rent::get()?.minimum_balance(0),
+    )? {
+        Ok(preview) => preview,
+        Err(MigrationShortfall::Tokens) => return err!(PumpCloneError::InsufficientTokenBalance),
+        Err(MigrationShortfall::Sol) => return err!(PumpCloneError::InsufficientLiquidityForMigration),
+    };
+
+    // Refuse to list at a price pushed away from the TWAP just before
+    // graduation; migration succeeds once the average catches up.
+    if global_state.migration_price_band_bps > 0 {
+        let seed_price = safe_mul_div(preview.sol_amount, PRICE_SCALE, preview.token_amount)?;
+        if let Some(twap) = bonding_curve.twap_price(now)? {
+            require!(
+                within_price_band(seed_price, twap, global_state.migration_price_band_bps),
+                PumpCloneError::MigrationPriceOutOfBand
+            );
+        }
+    }
+
+    Ok(preview)
+}
+
+/// Returns the token and SOL amounts `migrate_liquidity` would seed the pool
+/// with, failing wherever the migration itself would fail before moving any
+/// funds. Mutates nothing; the amounts are returned as return data.
+pub fn handler(ctx: Context<PreviewMigration>) -> Result<MigrationPreview> {
+    let global_state = &ctx.accounts.global_state;
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    global_state.check_migrations_allowed()?;
+    bonding_curve.check_migration_ready(global_state.migration_threshold)?;
+
+    let preview = migration_amounts(
+        global_state,
+        bonding_curve,
+        ctx.accounts.curve_token_account.amount,
+        ctx.accounts.curve_sol_vault.lamports(),
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    msg!(
+        "Migration preview for {}: {} tokens, {} lamports",
+        bonding_curve.mint,
+        preview.token_amount,
+        preview.sol_amount
+    );
+
+    Ok(preview)
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthorityTransfer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Nominates `new_authority`; nothing changes until it accepts, so a mistyped
+/// key can simply be replaced by proposing again.
+pub fn handler(ctx: Context<ProposeAuthorityTransfer>, new_authority: Pubkey) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.propose_authority(&ctx.accounts.authority.key(), new_authority)?;
+
+    emit!(AuthorityTransferProposed {
+        authority: global_state.authority,
+        pending_authority: new_authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
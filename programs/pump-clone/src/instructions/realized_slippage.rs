@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::events::*;
+use crate::utils::realized_slippage_bps;
+
+#[derive(Accounts)]
+pub struct RealizedSlippage<'info> {
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+/// Reconstructs the slippage a past trade actually paid, in bps, from its
+/// event data and the curve's reserves before it. The program keeps no trade
+/// history on-chain, so the record is read from the logs and passed in.
+pub fn handler(ctx: Context<RealizedSlippage>, record: TradeRecord) -> Result<u64> {
+    let slippage_bps = realized_slippage_bps(&record)?;
+
+    emit!(SlippageRealized {
+        mint: ctx.accounts.bonding_curve.mint,
+        direction: record.direction,
+        amount_in: record.amount_in,
+        amount_out: record.amount_out,
+        slippage_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(slippage_bps)
+}
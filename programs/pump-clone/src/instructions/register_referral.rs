@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+
+#[derive(Accounts)]
+pub struct RegisterReferral<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Referral::LEN,
+        seeds = [b"referral", owner.key().as_ref()],
+        bump
+    )]
+    pub referral: Account<'info, Referral>,
+
+    /// The wallet that referred the caller; only passed on the registration
+    /// that names it
+    #[account(
+        mut,
+        seeds = [b"referral", referrer_referral.owner.as_ref()],
+        bump = referrer_referral.bump
+    )]
+    pub referrer_referral: Option<Account<'info, Referral>>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the caller's referral account if needed and sets its code.
+/// Calling it again replaces the code; counts and rewards are kept. The
+/// referrer is counted here, once per referred wallet, rather than on buys
+/// where any wallet could name one.
+pub fn handler(ctx: Context<RegisterReferral>, code: String) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    Referral::validate_code(&code)?;
+
+    let referral = &mut ctx.accounts.referral;
+    referral.owner = ctx.accounts.owner.key();
+    referral.code = code;
+    referral.bump = ctx.bumps.referral;
+    if let Some(referrer_referral) = ctx.accounts.referrer_referral.as_mut() {
+        referral.record_referrer(referrer_referral)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils;
+
+#[derive(Accounts)]
+#[instruction(legacy_seed: Vec<u8>)]
+pub struct RescueCurveTokens<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, mint.key().as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// CHECK: Checked in the handler against the legacy seed derivation
+    pub orphaned_curve: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = orphaned_curve
+    )]
+    pub orphaned_token_account: Account<'info, TokenAccount>,
+
+    /// The live curve's token account or a recovery wallet's
+    #[account(
+        mut,
+        token::mint = mint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Moves tokens out of an ATA owned by a curve PDA derived under a retired
+/// seed prefix, which no live instruction can sign for any more. The curve's
+/// reserves are left untouched; tokens sent back to the live curve sit in its
+/// account until reconciled.
+pub fn handler(ctx: Context<RescueCurveTokens>, legacy_seed: Vec<u8>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let mint = ctx.accounts.mint.key();
+    let (orphaned_curve, bump) = utils::legacy_curve_address(
+        &legacy_seed,
+        &mint,
+        ctx.program_id,
+        &ctx.accounts.bonding_curve.key(),
+    )
+    .ok_or(PumpCloneError::InvalidLegacyCurveSeed)?;
+    require_keys_eq!(
+        ctx.accounts.orphaned_curve.key(),
+        orphaned_curve,
+        PumpCloneError::InvalidLegacyCurveSeed
+    );
+
+    let amount = ctx.accounts.orphaned_token_account.amount;
+    require!(amount > 0, PumpCloneError::InsufficientTokenBalance);
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.orphaned_token_account.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.orphaned_curve.to_account_info(),
+            },
+            &[&[legacy_seed.as_slice(), mint.as_ref(), &[bump]]],
+        ),
+        amount,
+    )?;
+
+    emit!(CurveTokensRescued {
+        mint,
+        orphaned_token_account: ctx.accounts.orphaned_token_account.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
 use crate::state::*;
-use crate::errors::*;
-use crate::events::*;
+use crate::error::*;
 
 #[derive(Accounts)]
 pub struct SellTokens<'info> {
@@ -12,147 +12,189 @@ pub struct SellTokens<'info> {
     #[account(
         mut,
         seeds = [b"token_launch", token_launch.mint.as_ref()],
-        bump = token_launch.bump,
-        constraint = token_launch.is_active @ PumpCloneError::LaunchNotActive,
-        constraint = !token_launch.migrated @ PumpCloneError::AlreadyMigrated
+        bump = token_launch.bump
     )]
     pub token_launch: Account<'info, TokenLaunch>,
     
+    #[account(address = token_launch.mint @ PumpCloneError::InvalidTokenMint)]
+    pub mint: Account<'info, Mint>,
+    
     #[account(
         mut,
-        associated_token::mint = token_launch.mint,
+        associated_token::mint = mint,
         associated_token::authority = seller
     )]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    pub associated_user: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        seeds = [b"bonding_curve", token_launch.mint.as_ref()],
-        bump = bonding_curve.bump
+        seeds = [BONDING_CURVE_SEED, mint.key().as_ref()],
+        bump = bonding_curve.bump,
+        constraint = !bonding_curve.migrated @ PumpCloneError::AlreadyMigrated
     )]
     pub bonding_curve: Account<'info, BondingCurve>,
     
     #[account(
         mut,
-        associated_token::mint = token_launch.mint,
+        associated_token::mint = mint,
         associated_token::authority = bonding_curve
     )]
-    pub bonding_curve_token_account: Account<'info, TokenAccount>,
+    pub associated_bonding_curve: Account<'info, TokenAccount>,
     
     #[account(
         mut,
-        constraint = sol_vault.key() == bonding_curve.sol_vault @ PumpCloneError::InvalidSolVault
+        seeds = [SOL_VAULT_SEED, mint.key().as_ref()],
+        bump
     )]
     pub sol_vault: SystemAccount<'info>,
     
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
 
-pub fn sell_tokens(ctx: Context<SellTokens>, token_amount: u64) -> Result<()> {
-    require!(token_amount > 0, PumpCloneError::InvalidAmount);
-    
-    let bonding_curve = &mut ctx.accounts.bonding_curve;
-    let token_launch = &mut ctx.accounts.token_launch;
-    
-    // Check if seller has enough tokens
-    require!(
-        ctx.accounts.seller_token_account.amount >= token_amount,
-        PumpCloneError::InsufficientTokenBalance
-    );
-    
-    // Calculate SOL amount to receive based on bonding curve
-    let sol_amount = calculate_sell_price(
-        bonding_curve.virtual_token_reserves,
-        bonding_curve.virtual_sol_reserves,
-        token_amount,
-        bonding_curve.k_constant
-    )?;
-    
-    require!(sol_amount > 0, PumpCloneError::InvalidSellAmount);
-    
-    // Check if bonding curve has enough SOL
-    require!(
-        bonding_curve.real_sol_reserves >= sol_amount,
-        PumpCloneError::InsufficientSolReserves
-    );
-    
-    // Update bonding curve reserves
-    bonding_curve.virtual_token_reserves = bonding_curve
-        .virtual_token_reserves
-        .checked_add(token_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
     
-    bonding_curve.virtual_sol_reserves = bonding_curve
-        .virtual_sol_reserves
-        .checked_sub(sol_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    /// CHECK: The seller's vesting escrow; created by the handler on the
+    /// first vested sell once every guard has passed, and loaded as a
+    /// `SellVestingEscrow` otherwise
+    #[account(
+        mut,
+        seeds = [b"sell_vesting", token_launch.mint.as_ref(), seller.key().as_ref()],
+        bump
+    )]
+    pub sell_vesting_escrow: Option<UncheckedAccount<'info>>,
     
-    bonding_curve.real_token_reserves = bonding_curve
-        .real_token_reserves
-        .checked_add(token_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    /// CHECK: The seller's blacklist PDA; empty when never listed
+    #[account(
+        seeds = [b"blacklist", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_blacklist: UncheckedAccount<'info>,
     
-    bonding_curve.real_sol_reserves = bonding_curve
-        .real_sol_reserves
-        .checked_sub(sol_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    /// Required when the token sets a minimum number of buys before selling
+    #[account(
+        mut,
+        seeds = [b"user_position", seller.key().as_ref(), token_launch.key().as_ref()],
+        bump = seller_position.bump,
+        constraint = seller_position.mint == token_launch.mint @ PumpCloneError::InvalidTokenMint
+    )]
+    pub seller_position: Option<Account<'info, UserPosition>>,
     
-    // Update token launch stats
-    token_launch.total_supply_sold = token_launch
-        .total_supply_sold
-        .checked_sub(token_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    #[account(
+        seeds = [b"user_settings", seller.key().as_ref()],
+        bump = user_settings.bump
+    )]
+    pub user_settings: Option<Account<'info, UserSettings>>,
     
-    token_launch.sol_raised = token_launch
-        .sol_raised
-        .checked_sub(sol_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    /// Exempts a registered market maker from per-wallet cooldowns and daily limits
+    #[account(
+        seeds = [b"market_maker", seller.key().as_ref()],
+        bump = market_maker.bump
+    )]
+    pub market_maker: Option<Account<'info, MarketMaker>>,
     
-    // Transfer tokens from seller to bonding curve
-    let transfer_tokens_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.seller_token_account.to_account_info(),
-            to: ctx.accounts.bonding_curve_token_account.to_account_info(),
-            authority: ctx.accounts.seller.to_account_info(),
-        },
-    );
-    
-    token::transfer(transfer_tokens_ctx, token_amount)?;
-    
-    // Transfer SOL from vault to seller
-    let sol_vault_info = ctx.accounts.sol_vault.to_account_info();
-    let seller_info = ctx.accounts.seller.to_account_info();
-    
-    **sol_vault_info.try_borrow_mut_lamports()? = sol_vault_info
-        .lamports()
-        .checked_sub(sol_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    /// CHECK: the instructions sysvar, required while the atomic trade guard is on
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: Option<UncheckedAccount<'info>>,
     
-    **seller_info.try_borrow_mut_lamports()? = seller_info
-        .lamports()
-        .checked_add(sol_amount)
-        .ok_or(PumpCloneError::MathOverflow)?;
+    /// Qualifies the seller for the governance-holder fee discount
+    #[account(
+        token::mint = global_state.governance_mint,
+        token::authority = seller
+    )]
+    pub governance_token_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Receives the launch's community share of the creator fee
+    #[account(
+        mut,
+        address = token_launch.community_wallet @ PumpCloneError::InvalidFeeRecipient
+    )]
+    pub community_wallet: Option<SystemAccount<'info>>,
     
-    // Update last trade timestamp
-    bonding_curve.last_trade_timestamp = Clock::get()?.unix_timestamp;
-    
-    // Emit sell event
-    emit!(TokenSold {
-        mint: token_launch.mint,
-        seller: ctx.accounts.seller.key(),
-        token_amount,
-        sol_amount,
-        virtual_token_reserves: bonding_curve.virtual_token_reserves,
-        virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
-        timestamp: Clock::get()?.unix_timestamp,
-    });
-    
-    Ok(())
+    /// Receives the floor share of the creator's sell tax
+    #[account(
+        mut,
+        seeds = [b"price_floor", token_launch.mint.as_ref()],
+        bump
+    )]
+    pub price_floor_vault: Option<SystemAccount<'info>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// What a sell of some tokens pays out, and where the rest of its gross SOL
+/// goes.
+pub struct SellQuote {
+    /// Gross SOL the tokens fetch from the curve
+    pub sol_amount: u64,
+    pub creator_fee: u64,
+    pub platform_fee: u64,
+    pub sell_tax: u64,
+    /// Part of `sell_tax` sent to the price-floor vault
+    pub floor_share: u64,
+    /// What the seller receives
+    pub net_sol_amount: u64,
+}
+
+impl SellTokens<'_> {
+    /// Prices a sell of `token_amount` at the current reserves with the fees,
+    /// tier, governance discount and sell tax the sell handler charges.
+    pub fn quote(&self, token_amount: u64) -> Result<SellQuote> {
+        let bonding_curve = &self.bonding_curve;
+        let sol_amount = crate::utils::calculate_sell_amount(
+            token_amount,
+            bonding_curve.virtual_token_reserves,
+            bonding_curve.virtual_sol_reserves,
+        )?;
+
+        let creator_fee = crate::utils::calculate_fee(sol_amount, bonding_curve.creator_fee_bps)?;
+        let platform_fee = crate::utils::calculate_fee(
+            sol_amount,
+            bonding_curve.trading_fee_bps(crate::utils::fee_tier_bps(
+                &self.global_state.platform_fee_tiers,
+                sol_amount,
+                bonding_curve.platform_fee_bps,
+            )),
+        )?;
+        let platform_fee = crate::utils::governance_discounted_fee(
+            platform_fee,
+            self.global_state.governance_fee_discount_bps,
+            self.governance_token_account.as_ref().map(|account| account.amount),
+            self.global_state.governance_min_balance,
+        )?;
+        let (sell_tax, floor_share) = crate::utils::split_sell_tax(
+            sol_amount,
+            self.token_launch.sell_tax_bps,
+            self.token_launch.sell_tax_floor_share_bps,
+        )?;
+
+        let net_sol_amount = sol_amount
+            .checked_sub(creator_fee)
+            .and_then(|amount| amount.checked_sub(platform_fee))
+            .and_then(|amount| amount.checked_sub(sell_tax))
+            .ok_or(PumpCloneError::NumericalOverflow)?;
+
+        Ok(SellQuote {
+            sol_amount,
+            creator_fee,
+            platform_fee,
+            sell_tax,
+            floor_share,
+            net_sol_amount,
+        })
+    }
 }
 
-fn calculate_sell_price(
+pub(crate) fn calculate_sell_price(
     virtual_token_reserves: u64,
     virtual_sol_reserves: u64,
     token_amount: u64,
@@ -167,9 +209,12 @@ fn calculate_sell_price(
         .checked_add(token_amount)
         .ok_or(PumpCloneError::MathOverflow)?;
     
-    let new_sol_reserves = (k_constant as u64)
-        .checked_div(new_token_reserves)
+    // `k` exceeds u64::MAX with the default reserves, so divide in u128
+    let new_sol_reserves = k_constant
+        .checked_div(new_token_reserves as u128)
         .ok_or(PumpCloneError::MathOverflow)?;
+    let new_sol_reserves = u64::try_from(new_sol_reserves)
+        .map_err(|_| PumpCloneError::MathOverflow)?;
     
     let sol_amount = virtual_sol_reserves
         .checked_sub(new_sol_reserves)
@@ -184,5 +229,5 @@ fn calculate_sell_price(
     
     sol_amount
         .checked_sub(fee)
-        .ok_or(PumpCloneError::MathOverflow)
+        .ok_or(PumpCloneError::MathOverflow.into())
 }
\ No newline at end of file
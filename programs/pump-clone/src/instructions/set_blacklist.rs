@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetBlacklist<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Blacklist::LEN,
+        seeds = [b"blacklist", wallet.as_ref()],
+        bump
+    )]
+    pub blacklist: Account<'info, Blacklist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Bans `wallet` from buying and selling, or lifts the ban. Lifting keeps
+/// the account so the wallet can be listed again cheaply.
+pub fn handler(ctx: Context<SetBlacklist>, wallet: Pubkey, is_blacklisted: bool) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+
+    let blacklist = &mut ctx.accounts.blacklist;
+    blacklist.wallet = wallet;
+    blacklist.is_blacklisted = is_blacklisted;
+    blacklist.bump = ctx.bumps.blacklist;
+
+    msg!("Wallet {} blacklisted: {}", wallet, is_blacklisted);
+
+    Ok(())
+}
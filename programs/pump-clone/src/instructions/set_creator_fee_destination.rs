@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct SetCreatorFeeDestination<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Changes where claimed creator fees go. Whether fees accrued before the
+/// change follow the new destination is set platform-wide by
+/// `accrued_creator_fees_follow_destination`.
+pub fn handler(ctx: Context<SetCreatorFeeDestination>, destination: Pubkey) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let accrued_follow = ctx.accounts.global_state.accrued_creator_fees_follow_destination;
+
+    let token_launch = &mut ctx.accounts.token_launch;
+    token_launch.change_fee_destination(destination, accrued_follow)?;
+
+    msg!(
+        "Creator fees for {} now go to {}; {} lamports still owed to {}",
+        token_launch.mint,
+        token_launch.fee_destination(),
+        token_launch.prior_accrued_creator_fees,
+        token_launch.prior_fee_destination
+    );
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct SetCreatorFeeSplit<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Routes `community_fee_bps` of every creator fee to `community_wallet`; the
+/// creator keeps the rest. Zero bps sends the whole fee to the creator.
+pub fn handler(
+    ctx: Context<SetCreatorFeeSplit>,
+    community_fee_bps: u16,
+    community_wallet: Pubkey,
+) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    require!(community_fee_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+    require!(
+        community_fee_bps == 0 || community_wallet != Pubkey::default(),
+        PumpCloneError::InvalidFeeRecipient
+    );
+
+    let token_launch = &mut ctx.accounts.token_launch;
+    token_launch.community_fee_bps = community_fee_bps;
+    token_launch.community_wallet = community_wallet;
+
+    msg!(
+        "Creator fee split for {}: {} bps to {}",
+        token_launch.mint,
+        community_fee_bps,
+        community_wallet
+    );
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetMarketMaker<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = MarketMaker::LEN,
+        seeds = [b"market_maker", wallet.as_ref()],
+        bump
+    )]
+    pub market_maker: Account<'info, MarketMaker>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Registers `wallet` as a market maker, or toggles an existing registration.
+/// Deactivating keeps the account so the wallet can be re-enabled later.
+pub fn handler(ctx: Context<SetMarketMaker>, wallet: Pubkey, is_active: bool) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    require!(wallet != Pubkey::default(), PumpCloneError::InvalidMarketMaker);
+
+    let market_maker = &mut ctx.accounts.market_maker;
+    market_maker.wallet = wallet;
+    market_maker.is_active = is_active;
+    market_maker.bump = ctx.bumps.market_maker;
+
+    msg!("Market maker {} active: {}", wallet, is_active);
+
+    Ok(())
+}
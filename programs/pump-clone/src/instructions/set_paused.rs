@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Emergency pause: while set, token creation, trading and migration are
+/// rejected with `ProgramPaused`.
+pub fn handler(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.is_paused = paused;
+
+    emit!(PauseToggled {
+        authority: global_state.authority,
+        paused,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
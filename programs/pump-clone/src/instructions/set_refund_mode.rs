@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetRefundMode<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+}
+
+/// Puts a stalled or aborted curve into refund mode, closing buys and sells
+/// and opening `claim_refund`. Refund mode is one-way: refunds burn tokens
+/// and pay out reserves, so the curve can't reopen for trading afterwards.
+pub fn handler(ctx: Context<SetRefundMode>, enabled: bool) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let bonding_curve = &mut ctx.accounts.bonding_curve;
+    require!(!bonding_curve.complete, PumpCloneError::BondingCurveCompleted);
+    bonding_curve.check_not_in_refund_mode()?;
+    require!(enabled, PumpCloneError::RefundModeNotActive);
+
+    bonding_curve.refund_mode = enabled;
+
+    emit!(RefundModeSet {
+        mint: bonding_curve.mint,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::utils::combined_sell_fees_within_cap;
+
+#[derive(Accounts)]
+pub struct SetSellTax<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// Funded up to rent here so the first floor share sent to it lands
+    #[account(
+        mut,
+        seeds = [b"price_floor", token_launch.mint.as_ref()],
+        bump
+    )]
+    pub price_floor_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets a sell tax whose `floor_share_bps` portion funds the token's price
+/// floor. The tax together with the trading fees may not exceed
+/// `MAX_COMBINED_SELL_FEE_BPS`, and once the curve has traded the tax and
+/// floor can only be lowered.
+pub fn handler(
+    ctx: Context<SetSellTax>,
+    sell_tax_bps: u16,
+    floor_share_bps: u16,
+    floor_price: u64,
+) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let bonding_curve = &ctx.accounts.bonding_curve;
+    require!(
+        combined_sell_fees_within_cap(
+            sell_tax_bps,
+            bonding_curve.creator_fee_bps,
+            bonding_curve.platform_fee_bps,
+        ),
+        PumpCloneError::SellTaxTooHigh
+    );
+    require!(floor_share_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+
+    let trading_started = bonding_curve.first_buy_at != 0;
+    let token_launch = &mut ctx.accounts.token_launch;
+    token_launch.set_sell_tax(sell_tax_bps, floor_share_bps, floor_price, trading_started)?;
+    super::initialize_global_state::fund_vault(
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.price_floor_vault.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    msg!(
+        "Sell tax for {}: {} bps, {} bps of it to the floor at price {}",
+        token_launch.mint,
+        sell_tax_bps,
+        floor_share_bps,
+        floor_price
+    );
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+use crate::utils::SECONDS_PER_DAY;
+
+#[derive(Accounts)]
+pub struct SetTradingHours<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator,
+        constraint = !token_launch.is_migrated @ PumpCloneError::LiquidityAlreadyMigrated
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+/// Sets the daily UTC trading window in seconds since midnight. Passing the
+/// same value for both bounds disables the schedule.
+pub fn handler(ctx: Context<SetTradingHours>, start: u32, end: u32) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    require!(
+        (start as i64) < SECONDS_PER_DAY && (end as i64) < SECONDS_PER_DAY,
+        PumpCloneError::InvalidTradingWindow
+    );
+
+    let token_launch = &mut ctx.accounts.token_launch;
+    token_launch.trading_window_start = start;
+    token_launch.trading_window_end = end;
+
+    msg!("Trading window for {} set to [{}, {})", token_launch.mint, start, end);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct SetUserSettings<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserSettings::LEN,
+        seeds = [b"user_settings", user.key().as_ref()],
+        bump
+    )]
+    pub user_settings: Account<'info, UserSettings>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Stores the slippage `buy_tokens`/`sell_tokens` use when called with
+/// `USE_DEFAULT_SLIPPAGE`, and whether holder rewards are claimed as tokens.
+pub fn handler(
+    ctx: Context<SetUserSettings>,
+    max_slippage_bps: u16,
+    compound_holder_rewards: bool,
+) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    require!(max_slippage_bps <= 10_000, PumpCloneError::InvalidSlippageTolerance);
+
+    let user_settings = &mut ctx.accounts.user_settings;
+    user_settings.user = ctx.accounts.user.key();
+    user_settings.max_slippage_bps = max_slippage_bps;
+    user_settings.compound_holder_rewards = compound_holder_rewards;
+    user_settings.bump = ctx.bumps.user_settings;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct SetWhitelist<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump,
+        has_one = creator @ PumpCloneError::Unauthorized
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = Whitelist::LEN,
+        seeds = [b"whitelist", bonding_curve.mint.as_ref(), wallet.as_ref()],
+        bump
+    )]
+    pub whitelist: Account<'info, Whitelist>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Adds `wallet` to, or removes it from, the launch's whitelist phase. Only
+/// matters until `whitelist_end_ts`; after that anyone can buy.
+pub fn handler(ctx: Context<SetWhitelist>, wallet: Pubkey, is_whitelisted: bool) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+
+    let whitelist = &mut ctx.accounts.whitelist;
+    whitelist.mint = ctx.accounts.bonding_curve.mint;
+    whitelist.wallet = wallet;
+    whitelist.is_whitelisted = is_whitelisted;
+    whitelist.bump = ctx.bumps.whitelist;
+
+    msg!("Wallet {} whitelisted for {}: {}", wallet, whitelist.mint, is_whitelisted);
+
+    Ok(())
+}
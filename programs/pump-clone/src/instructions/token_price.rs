@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::pda::BONDING_CURVE_SEED;
+use crate::state::*;
+use crate::error::*;
+use crate::utils::{price_in_quote_micro_units, SOL_DECIMALS};
+
+#[derive(Accounts)]
+pub struct TokenPrice<'info> {
+    #[account(
+        seeds = [BONDING_CURVE_SEED, bonding_curve.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    #[account(address = bonding_curve.mint @ PumpCloneError::InvalidTokenMint)]
+    pub mint: Account<'info, Mint>,
+}
+
+/// Returns the spot price of one whole token in micro-SOL.
+pub fn handler(ctx: Context<TokenPrice>) -> Result<u64> {
+    let bonding_curve = &ctx.accounts.bonding_curve;
+
+    let price = price_in_quote_micro_units(
+        bonding_curve.virtual_sol_reserves,
+        bonding_curve.virtual_token_reserves,
+        ctx.accounts.mint.decimals,
+        SOL_DECIMALS,
+    )?;
+
+    msg!("Price of {}: {} micro-SOL per token", bonding_curve.mint, price);
+
+    Ok(price)
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(Accounts)]
+pub struct UnlockLp<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"lp_lock", lp_lock.mint.as_ref()],
+        bump = lp_lock.bump,
+        has_one = creator @ PumpCloneError::Unauthorized
+    )]
+    pub lp_lock: Account<'info, LpLock>,
+
+    #[account(
+        mut,
+        token::mint = lp_lock.lp_mint,
+        token::authority = lp_lock
+    )]
+    pub lp_lock_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = lp_lock.lp_mint,
+        token::authority = creator
+    )]
+    pub creator_lp_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Releases a migrated pool's whole locked LP balance to the creator once
+/// `unlock_ts` has passed.
+pub fn handler(ctx: Context<UnlockLp>) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    let lp_lock = &ctx.accounts.lp_lock;
+    lp_lock.check_unlocked(Clock::get()?.unix_timestamp)?;
+
+    let amount = ctx.accounts.lp_lock_token_account.amount;
+    let seeds: &[&[u8]] = &[b"lp_lock", lp_lock.mint.as_ref(), &[lp_lock.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.lp_lock_token_account.to_account_info(),
+                to: ctx.accounts.creator_lp_account.to_account_info(),
+                authority: lp_lock.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    msg!("Unlocked {} LP tokens for {}", amount, lp_lock.mint);
+
+    Ok(())
+}
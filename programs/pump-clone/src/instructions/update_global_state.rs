@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateGlobalStateParams {
+    pub fee_recipient: Option<Pubkey>,
+    pub is_paused: Option<bool>,
+    pub graduation_cooldown_slots: Option<u64>,
+    pub min_migration_market_cap: Option<u64>,
+    pub holder_rewards_bps: Option<u16>,
+    /// Whole tokens; scaled by each mint's decimals when counting holders.
+    pub min_holder_balance: Option<u64>,
+    pub creator_liquidity_enabled: Option<bool>,
+    pub max_trades_per_slot: Option<u16>,
+    pub waive_creator_self_fee: Option<bool>,
+    pub quote_mint_allowlist: Option<[Pubkey; GlobalState::MAX_QUOTE_MINTS]>,
+    /// Trades between creator milestone bonuses; zero disables them.
+    pub trade_milestone_interval: Option<u64>,
+    pub trade_milestone_bonus: Option<u64>,
+    pub migration_price_band_bps: Option<u16>,
+    pub allow_creator_initial_buy: Option<bool>,
+    pub renounce_authorities_on_migration: Option<bool>,
+    pub min_initial_virtual_sol_reserves: Option<u64>,
+    pub max_buy_base: Option<u64>,
+    pub referral_buy_bonus: Option<u64>,
+    pub sells_only: Option<bool>,
+    pub wallet_trade_cooldown_secs: Option<i64>,
+    pub wallet_daily_sol_limit: Option<u64>,
+    pub atomic_trade_guard: Option<bool>,
+    pub max_sell_extraction_multiple_bps: Option<u32>,
+    /// Bitmask of `GlobalState::PAUSE_*` flags
+    pub paused_instructions: Option<u8>,
+    pub governance_mint: Option<Pubkey>,
+    pub governance_min_balance: Option<u64>,
+    pub governance_fee_discount_bps: Option<u16>,
+    pub platform_fee_tiers: Option<[FeeTier; GlobalState::MAX_FEE_TIERS]>,
+    pub vault_dust_to_fee_recipient: Option<bool>,
+    pub global_kill_switch: Option<bool>,
+    pub accrued_creator_fees_follow_destination: Option<bool>,
+    pub max_allowed_slippage_bps: Option<u16>,
+    pub max_fee_bps: Option<u16>,
+    pub max_tokens_per_buy_bps: Option<u16>,
+    pub lp_lock_duration: Option<i64>,
+    pub referral_fee_bps: Option<u16>,
+    pub max_price_drop_bps_per_slot: Option<u16>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateGlobalState<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump,
+        has_one = authority @ PumpCloneError::InvalidAuthority
+    )]
+    pub global_state: Account<'info, GlobalState>,
+}
+
+pub fn handler(ctx: Context<UpdateGlobalState>, params: UpdateGlobalStateParams) -> Result<()> {
+    apply_params(&mut ctx.accounts.global_state, params)
+}
+
+/// Writes every setting present in `params`. Shared with `bootstrap` so both
+/// paths validate settings the same way.
+pub fn apply_params(global_state: &mut GlobalState, params: UpdateGlobalStateParams) -> Result<()> {
+    if let Some(fee_recipient) = params.fee_recipient {
+        global_state.fee_recipient = fee_recipient;
+    }
+
+    if let Some(is_paused) = params.is_paused {
+        global_state.is_paused = is_paused;
+    }
+
+    if let Some(graduation_cooldown_slots) = params.graduation_cooldown_slots {
+        global_state.graduation_cooldown_slots = graduation_cooldown_slots;
+    }
+
+    if let Some(min_migration_market_cap) = params.min_migration_market_cap {
+        global_state.min_migration_market_cap = min_migration_market_cap;
+    }
+
+    if let Some(holder_rewards_bps) = params.holder_rewards_bps {
+        require!(holder_rewards_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+        global_state.holder_rewards_bps = holder_rewards_bps;
+    }
+
+    if let Some(min_holder_balance) = params.min_holder_balance {
+        global_state.min_holder_balance = min_holder_balance;
+    }
+
+    if let Some(creator_liquidity_enabled) = params.creator_liquidity_enabled {
+        global_state.creator_liquidity_enabled = creator_liquidity_enabled;
+    }
+
+    if let Some(max_trades_per_slot) = params.max_trades_per_slot {
+        global_state.max_trades_per_slot = max_trades_per_slot;
+    }
+
+    if let Some(waive_creator_self_fee) = params.waive_creator_self_fee {
+        global_state.waive_creator_self_fee = waive_creator_self_fee;
+    }
+
+    if let Some(quote_mint_allowlist) = params.quote_mint_allowlist {
+        global_state.quote_mint_allowlist = quote_mint_allowlist;
+    }
+
+    if let Some(trade_milestone_interval) = params.trade_milestone_interval {
+        global_state.trade_milestone_interval = trade_milestone_interval;
+    }
+
+    if let Some(trade_milestone_bonus) = params.trade_milestone_bonus {
+        global_state.trade_milestone_bonus = trade_milestone_bonus;
+    }
+
+    if let Some(migration_price_band_bps) = params.migration_price_band_bps {
+        require!(migration_price_band_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+        global_state.migration_price_band_bps = migration_price_band_bps;
+    }
+
+    if let Some(allow_creator_initial_buy) = params.allow_creator_initial_buy {
+        global_state.allow_creator_initial_buy = allow_creator_initial_buy;
+    }
+
+    if let Some(renounce_authorities_on_migration) = params.renounce_authorities_on_migration {
+        global_state.renounce_authorities_on_migration = renounce_authorities_on_migration;
+    }
+
+    if let Some(min_initial_virtual_sol_reserves) = params.min_initial_virtual_sol_reserves {
+        global_state.min_initial_virtual_sol_reserves = min_initial_virtual_sol_reserves;
+    }
+
+    if let Some(max_buy_base) = params.max_buy_base {
+        global_state.max_buy_base = max_buy_base;
+    }
+
+    if let Some(referral_buy_bonus) = params.referral_buy_bonus {
+        global_state.referral_buy_bonus = referral_buy_bonus;
+    }
+
+    if let Some(sells_only) = params.sells_only {
+        global_state.sells_only = sells_only;
+    }
+
+    if let Some(wallet_trade_cooldown_secs) = params.wallet_trade_cooldown_secs {
+        require!(wallet_trade_cooldown_secs >= 0, PumpCloneError::InvalidConfiguration);
+        global_state.wallet_trade_cooldown_secs = wallet_trade_cooldown_secs;
+    }
+
+    if let Some(wallet_daily_sol_limit) = params.wallet_daily_sol_limit {
+        global_state.wallet_daily_sol_limit = wallet_daily_sol_limit;
+    }
+
+    if let Some(atomic_trade_guard) = params.atomic_trade_guard {
+        global_state.atomic_trade_guard = atomic_trade_guard;
+    }
+
+    if let Some(max_sell_extraction_multiple_bps) = params.max_sell_extraction_multiple_bps {
+        global_state.max_sell_extraction_multiple_bps = max_sell_extraction_multiple_bps;
+    }
+
+    if let Some(paused_instructions) = params.paused_instructions {
+        global_state.paused_instructions = paused_instructions;
+    }
+
+    if let Some(governance_mint) = params.governance_mint {
+        global_state.governance_mint = governance_mint;
+    }
+
+    if let Some(governance_min_balance) = params.governance_min_balance {
+        global_state.governance_min_balance = governance_min_balance;
+    }
+
+    if let Some(governance_fee_discount_bps) = params.governance_fee_discount_bps {
+        require!(governance_fee_discount_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+        global_state.governance_fee_discount_bps = governance_fee_discount_bps;
+    }
+
+    if let Some(platform_fee_tiers) = params.platform_fee_tiers {
+        require!(
+            crate::utils::validate_fee_tiers(&platform_fee_tiers),
+            PumpCloneError::InvalidTierRequirements
+        );
+        global_state.platform_fee_tiers = platform_fee_tiers;
+    }
+
+    if let Some(vault_dust_to_fee_recipient) = params.vault_dust_to_fee_recipient {
+        global_state.vault_dust_to_fee_recipient = vault_dust_to_fee_recipient;
+    }
+
+    if let Some(global_kill_switch) = params.global_kill_switch {
+        global_state.global_kill_switch = global_kill_switch;
+    }
+
+    if let Some(follow) = params.accrued_creator_fees_follow_destination {
+        global_state.accrued_creator_fees_follow_destination = follow;
+    }
+
+    if let Some(max_allowed_slippage_bps) = params.max_allowed_slippage_bps {
+        require!(max_allowed_slippage_bps <= 10_000, PumpCloneError::InvalidSlippageTolerance);
+        global_state.max_allowed_slippage_bps = max_allowed_slippage_bps;
+    }
+
+    if let Some(max_fee_bps) = params.max_fee_bps {
+        require!(max_fee_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+        global_state.max_fee_bps = max_fee_bps;
+    }
+
+    if let Some(max_tokens_per_buy_bps) = params.max_tokens_per_buy_bps {
+        require!(max_tokens_per_buy_bps <= 10_000, PumpCloneError::InvalidConfiguration);
+        global_state.max_tokens_per_buy_bps = max_tokens_per_buy_bps;
+    }
+
+    if let Some(lp_lock_duration) = params.lp_lock_duration {
+        require!(lp_lock_duration >= 0, PumpCloneError::InvalidConfiguration);
+        global_state.lp_lock_duration = lp_lock_duration;
+    }
+
+    if let Some(referral_fee_bps) = params.referral_fee_bps {
+        require!(referral_fee_bps <= 10_000, PumpCloneError::InvalidFeePercentage);
+        global_state.referral_fee_bps = referral_fee_bps;
+    }
+
+    if let Some(max_price_drop_bps_per_slot) = params.max_price_drop_bps_per_slot {
+        require!(max_price_drop_bps_per_slot <= 10_000, PumpCloneError::InvalidConfiguration);
+        global_state.max_price_drop_bps_per_slot = max_price_drop_bps_per_slot;
+    }
+
+    Ok(())
+}
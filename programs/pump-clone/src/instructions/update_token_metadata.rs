@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::metadata::{
+    mpl_token_metadata::types::DataV2, update_metadata_accounts_v2, Metadata,
+    UpdateMetadataAccountsV2,
+};
+use crate::pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED};
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct UpdateTokenMetadata<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"token_launch", token_launch.mint.as_ref()],
+        bump = token_launch.bump,
+        has_one = creator @ PumpCloneError::InvalidCreator
+    )]
+    pub token_launch: Account<'info, TokenLaunch>,
+
+    #[account(
+        seeds = [BONDING_CURVE_SEED, token_launch.mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, BondingCurve>,
+
+    /// CHECK: Validated against the Metaplex PDA for the mint below
+    #[account(mut)]
+    pub metadata: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+impl<'info> UpdateTokenMetadata<'info> {
+    /// Pushes the launch's `metadata_uri` to the Metaplex metadata account.
+    fn update_metadata_uri(&self) -> Result<()> {
+        let mint = self.token_launch.mint;
+
+        let (metadata_key, _) = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                self.token_metadata_program.key().as_ref(),
+                mint.as_ref(),
+            ],
+            &self.token_metadata_program.key(),
+        );
+        require_keys_eq!(metadata_key, self.metadata.key(), PumpCloneError::InvalidMetadataAccount);
+
+        let seeds = &[
+            BONDING_CURVE_SEED.as_ref(),
+            mint.as_ref(),
+            &[self.bonding_curve.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        let token_launch = &self.token_launch;
+        update_metadata_accounts_v2(
+            CpiContext::new_with_signer(
+                self.token_metadata_program.to_account_info(),
+                UpdateMetadataAccountsV2 {
+                    metadata: self.metadata.to_account_info(),
+                    update_authority: self.bonding_curve.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            None,
+            Some(DataV2 {
+                name: token_launch.name.clone(),
+                symbol: token_launch.symbol.clone(),
+                uri: token_launch.metadata_uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            }),
+            None,
+            None,
+        )
+    }
+}
+
+/// Updates the launch's socials and, when `update.uri` is set, points the
+/// token metadata at the new URI. Every field is frozen once `lock_metadata`
+/// has run, or after the first buy on curves launched with
+/// `lock_metadata_on_first_buy`.
+pub fn handler(ctx: Context<UpdateTokenMetadata>, update: MetadataUpdate) -> Result<()> {
+    ctx.accounts.global_state.check_not_killed()?;
+    ctx.accounts.bonding_curve.check_metadata_updatable()?;
+    let uri_changed = update.uri.is_some();
+    ctx.accounts.token_launch.apply_metadata_update(update)?;
+    let mint = ctx.accounts.token_launch.mint;
+
+    if uri_changed {
+        ctx.accounts.update_metadata_uri()?;
+    }
+
+    let token_launch = &ctx.accounts.token_launch;
+    emit!(MetadataUpdated {
+        mint,
+        creator: ctx.accounts.creator.key(),
+        uri: token_launch.metadata_uri.clone(),
+        image_uri: token_launch.image_uri.clone(),
+        website: token_launch.website.clone(),
+        twitter: token_launch.twitter.clone(),
+        telegram: token_launch.telegram.clone(),
+        discord: token_launch.discord.clone(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::canonical_price;
+
+#[derive(Accounts)]
+pub struct UserPortfolio<'info> {
+    /// CHECK: Only used to match position ownership; no signature required
+    pub user: UncheckedAccount<'info>,
+}
+
+/// Aggregates PnL across the user's positions. Remaining accounts are passed
+/// in `(UserPosition, BondingCurve)` pairs; each curve prices the open
+/// balance of the position before it.
+pub fn handler<'info>(ctx: Context<'_, '_, '_, 'info, UserPortfolio<'info>>) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() % 2 == 0,
+        PumpCloneError::InvalidRemainingAccounts
+    );
+
+    let user = ctx.accounts.user.key();
+    let mut totals = PortfolioTotals::default();
+
+    for pair in ctx.remaining_accounts.chunks(2) {
+        let position: Account<UserPosition> = Account::try_from(&pair[0])?;
+        let bonding_curve: Account<BondingCurve> = Account::try_from(&pair[1])?;
+
+        require_keys_eq!(position.user, user, PumpCloneError::Unauthorized);
+        require_keys_eq!(position.mint, bonding_curve.mint, PumpCloneError::InvalidTokenMint);
+
+        let current_price = canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?;
+        totals.add_position(&position, current_price)?;
+    }
+
+    emit!(PortfolioSummary {
+        user,
+        positions: totals.positions,
+        sol_invested: totals.sol_invested,
+        realized_profit: totals.realized_profit,
+        unrealized_profit: totals.unrealized_profit,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use crate::pda::GLOBAL_STATE_SEED;
+use crate::state::*;
+use crate::error::*;
+use crate::events::*;
+use crate::utils::withdrawable_fees;
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [GLOBAL_STATE_SEED],
+        bump = global_state.bump
+    )]
+    pub global_state: Account<'info, GlobalState>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_vault"],
+        bump
+    )]
+    pub fee_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = global_state.fee_recipient @ PumpCloneError::InvalidFeeRecipient
+    )]
+    pub fee_recipient: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Moves collected platform fees to `fee_recipient`, leaving the vault at its
+/// rent-exempt minimum. Only the platform authority may withdraw.
+pub fn handler(ctx: Context<WithdrawFees>) -> Result<()> {
+    let global_state = &ctx.accounts.global_state;
+    global_state.check_authority(&ctx.accounts.authority.key())?;
+    global_state.check_not_killed()?;
+
+    let amount = withdrawable_fees(
+        ctx.accounts.fee_vault.lamports(),
+        Rent::get()?.minimum_balance(0),
+    );
+    require!(amount > 0, PumpCloneError::InsufficientFunds);
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.fee_vault.to_account_info(),
+                to: ctx.accounts.fee_recipient.to_account_info(),
+            },
+            &[&[b"fee_vault", &[ctx.bumps.fee_vault]]],
+        ),
+        amount,
+    )?;
+
+    let global_state = &mut ctx.accounts.global_state;
+    global_state.record_fees_withdrawn(amount)?;
+
+    emit!(FeesWithdrawn {
+        fee_recipient: ctx.accounts.fee_recipient.key(),
+        amount,
+        total_fees_withdrawn: global_state.total_fees_withdrawn,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -2,12 +2,24 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 use anchor_spl::associated_token::AssociatedToken;
 
+pub mod buy;
+pub mod dex;
+pub mod error;
+pub mod events;
+pub mod instructions;
+pub mod pda;
+pub mod state;
+pub mod utils;
+
+use error::PumpCloneError as PumpError;
+use events::*;
+use instructions::*;
+use state::*;
+use utils::{calculate_buy_amount, calculate_slippage, requires_sell_vesting, unwrapped_payment_amount};
+
 declare_id!("PumpC1oneProgram11111111111111111111111111");
 
-const BONDING_CURVE_SEED: &[u8] = b"bonding_curve";
-const TOKEN_VAULT_SEED: &[u8] = b"token_vault";
-const SOL_VAULT_SEED: &[u8] = b"sol_vault";
-const GLOBAL_STATE_SEED: &[u8] = b"global_state";
+use pda::{BONDING_CURVE_SEED, GLOBAL_STATE_SEED, SOL_VAULT_SEED};
 
 const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000; // 1.073M tokens
 const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30_000_000_000; // 30 SOL
@@ -30,6 +42,53 @@ pub mod pump_clone {
         global_state.total_tokens_created = 0;
         global_state.total_volume = 0;
         global_state.is_paused = false;
+        global_state.last_graduation_slot = 0;
+        global_state.graduation_cooldown_slots = 0;
+        global_state.min_migration_market_cap = 0;
+        global_state.holder_rewards_bps = 0;
+        global_state.min_holder_balance = 0;
+        global_state.creator_liquidity_enabled = false;
+        global_state.max_trades_per_slot = 0;
+        global_state.waive_creator_self_fee = false;
+        global_state.quote_mint_allowlist = [Pubkey::default(); GlobalState::MAX_QUOTE_MINTS];
+        global_state.trade_milestone_interval = 0;
+        global_state.trade_milestone_bonus = 0;
+        global_state.migration_price_band_bps = 0;
+        global_state.allow_creator_initial_buy = true;
+        global_state.renounce_authorities_on_migration = true;
+        global_state.min_initial_virtual_sol_reserves = 0;
+        global_state.max_buy_base = 0;
+        global_state.referral_buy_bonus = 0;
+        global_state.sells_only = false;
+        global_state.wallet_trade_cooldown_secs = 0;
+        global_state.wallet_daily_sol_limit = 0;
+        global_state.atomic_trade_guard = false;
+        global_state.max_sell_extraction_multiple_bps = 0;
+        global_state.paused_instructions = 0;
+        global_state.governance_mint = Pubkey::default();
+        global_state.governance_min_balance = 0;
+        global_state.governance_fee_discount_bps = 0;
+        global_state.platform_fee_tiers = [FeeTier::default(); GlobalState::MAX_FEE_TIERS];
+        global_state.vault_dust_to_fee_recipient = false;
+        global_state.global_kill_switch = false;
+        global_state.accrued_creator_fees_follow_destination = false;
+        global_state.max_allowed_slippage_bps = GlobalState::DEFAULT_MAX_ALLOWED_SLIPPAGE_BPS;
+        global_state.total_fees_collected = 0;
+        global_state.total_fees_withdrawn = 0;
+        global_state.pending_authority = None;
+        global_state.max_fee_bps = GlobalState::DEFAULT_MAX_FEE_BPS;
+        global_state.max_tokens_per_buy_bps = 0;
+        global_state.lp_lock_duration = GlobalState::DEFAULT_LP_LOCK_DURATION;
+        global_state.migration_target = MigrationTarget::Raydium;
+        global_state.referral_fee_bps = 0;
+        global_state.max_price_drop_bps_per_slot = 0;
+        global_state.bump = ctx.bumps.global_state;
+
+        instructions::initialize_global_state::fund_vault(
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.fee_vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
 
         emit!(GlobalStateInitialized {
             authority: global_state.authority,
@@ -39,36 +98,507 @@ pub mod pump_clone {
         Ok(())
     }
 
+    pub fn update_global_state(
+        ctx: Context<UpdateGlobalState>,
+        params: UpdateGlobalStateParams,
+    ) -> Result<()> {
+        instructions::update_global_state::handler(ctx, params)
+    }
+
+    pub fn graduate(ctx: Context<Graduate>) -> Result<()> {
+        instructions::graduate::handler(ctx)
+    }
+
+    pub fn claim_vested_proceeds(ctx: Context<ClaimVestedProceeds>) -> Result<()> {
+        instructions::claim_vested_proceeds::handler(ctx)
+    }
+
+    pub fn normalize_curve_pricing(ctx: Context<NormalizeCurvePricing>) -> Result<()> {
+        instructions::normalize_curve_pricing::handler(ctx)
+    }
+
+    pub fn set_trading_hours(ctx: Context<SetTradingHours>, start: u32, end: u32) -> Result<()> {
+        instructions::set_trading_hours::handler(ctx, start, end)
+    }
+
+    pub fn distribute_holder_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeHolderRewards<'info>>,
+        finalize: bool,
+    ) -> Result<()> {
+        instructions::distribute_holder_rewards::handler(ctx, finalize)
+    }
+
+    pub fn claim_holder_rewards(ctx: Context<ClaimHolderRewards>) -> Result<()> {
+        instructions::claim_holder_rewards::handler(ctx)
+    }
+
+    pub fn add_liquidity(ctx: Context<AddLiquidity>, sol_amount: u64) -> Result<()> {
+        instructions::add_liquidity::handler(ctx, sol_amount)
+    }
+
+    pub fn lock_metadata(ctx: Context<LockMetadata>) -> Result<()> {
+        instructions::lock_metadata::handler(ctx)
+    }
+
+    pub fn graduation_sol_remaining(ctx: Context<GraduationSolRemaining>) -> Result<u64> {
+        instructions::graduation_sol_remaining::handler(ctx)
+    }
+
+    pub fn merge_positions(ctx: Context<MergePositions>) -> Result<()> {
+        instructions::merge_positions::handler(ctx)
+    }
+
+    pub fn user_portfolio<'info>(
+        ctx: Context<'_, '_, '_, 'info, UserPortfolio<'info>>,
+    ) -> Result<()> {
+        instructions::user_portfolio::handler(ctx)
+    }
+
+    pub fn close_migrated_curve(ctx: Context<CloseMigratedCurve>) -> Result<()> {
+        instructions::close_migrated_curve::handler(ctx)
+    }
+
+    pub fn create_referral(ctx: Context<CreateReferral>) -> Result<()> {
+        instructions::create_referral::handler(ctx)
+    }
+
+    pub fn set_user_settings(
+        ctx: Context<SetUserSettings>,
+        max_slippage_bps: u16,
+        compound_holder_rewards: bool,
+    ) -> Result<()> {
+        instructions::set_user_settings::handler(ctx, max_slippage_bps, compound_holder_rewards)
+    }
+
+    pub fn fdv(ctx: Context<Fdv>) -> Result<u64> {
+        instructions::fdv::handler(ctx)
+    }
+
+    pub fn set_creator_fee_split(
+        ctx: Context<SetCreatorFeeSplit>,
+        community_fee_bps: u16,
+        community_wallet: Pubkey,
+    ) -> Result<()> {
+        instructions::set_creator_fee_split::handler(ctx, community_fee_bps, community_wallet)
+    }
+
+    pub fn token_price(ctx: Context<TokenPrice>) -> Result<u64> {
+        instructions::token_price::handler(ctx)
+    }
+
+    pub fn set_market_maker(
+        ctx: Context<SetMarketMaker>,
+        wallet: Pubkey,
+        is_active: bool,
+    ) -> Result<()> {
+        instructions::set_market_maker::handler(ctx, wallet, is_active)
+    }
+
+    pub fn rescue_curve_tokens(
+        ctx: Context<RescueCurveTokens>,
+        legacy_seed: Vec<u8>,
+    ) -> Result<()> {
+        instructions::rescue_curve_tokens::handler(ctx, legacy_seed)
+    }
+
+    pub fn bootstrap(
+        ctx: Context<Bootstrap>,
+        config: BootstrapConfig,
+        settings: UpdateGlobalStateParams,
+    ) -> Result<()> {
+        instructions::bootstrap::handler(ctx, config, settings)
+    }
+
+    pub fn lp_yield(ctx: Context<LpYield>) -> Result<u64> {
+        instructions::lp_yield::handler(ctx)
+    }
+
+    pub fn attest_reserves(ctx: Context<AttestReserves>) -> Result<ReserveAttestation> {
+        instructions::attest_reserves::handler(ctx)
+    }
+
+    pub fn set_sell_tax(
+        ctx: Context<SetSellTax>,
+        sell_tax_bps: u16,
+        floor_share_bps: u16,
+        floor_price: u64,
+    ) -> Result<()> {
+        instructions::set_sell_tax::handler(ctx, sell_tax_bps, floor_share_bps, floor_price)
+    }
+
+    pub fn floor_buyback(ctx: Context<FloorBuyback>) -> Result<()> {
+        instructions::floor_buyback::handler(ctx)
+    }
+
+    pub fn cancel_launch(ctx: Context<CancelLaunch>) -> Result<()> {
+        instructions::cancel_launch::handler(ctx)
+    }
+
+    /// Buys exactly `token_amount` tokens for at most `max_sol_in`. Only the
+    /// quoted cost is debited, so the unused `max_sol_in - cost` never leaves
+    /// the buyer. The quote rounds the same way as the fill, so paying `cost`
+    /// delivers at least `token_amount`; anything less fails the buy.
+    pub fn buy_exact_tokens(
+        ctx: Context<BuyTokens>,
+        token_amount: u64,
+        max_sol_in: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let bonding_curve = &ctx.accounts.bonding_curve;
+        let (cost, _) = utils::BondingCurveState {
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+        }
+        .quote_exact_tokens_out(token_amount, max_sol_in)?;
+
+        // Buys are priced on what reaches the curve, so the fees go on top
+        // of the curve's cost; a tier that only applies at the grossed-up
+        // size is priced in on a second pass
+        let buyer = ctx.accounts.buyer.key();
+        let global_state = &ctx.accounts.global_state;
+        let fee_bps = buy::buy_fee_bps(global_state, bonding_curve, &buyer, cost);
+        let mut sol_amount = utils::gross_up_for_fees(cost, fee_bps)?;
+        let fee_bps_at_size = buy::buy_fee_bps(global_state, bonding_curve, &buyer, sol_amount);
+        if fee_bps_at_size > fee_bps {
+            sol_amount = utils::gross_up_for_fees(cost, fee_bps_at_size)?;
+        }
+        require!(sol_amount <= max_sol_in, PumpError::SlippageToleranceExceeded);
+
+        // `max_sol_in` bounds the price; price impact is still held to the
+        // platform ceiling rather than the settings sentinel
+        let max_slippage_bps = ctx.accounts.global_state.max_allowed_slippage_bps as u64;
+        let buyer_token_account = ctx.accounts.associated_user.to_account_info();
+        let balance_before = token::accessor::amount(&buyer_token_account).unwrap_or(0);
+        buy_tokens(ctx, sol_amount, max_slippage_bps, deadline)?;
+
+        let delivered = token::accessor::amount(&buyer_token_account)?
+            .checked_sub(balance_before)
+            .ok_or(PumpError::Overflow)?;
+        require!(delivered >= token_amount, PumpError::SlippageToleranceExceeded);
+        Ok(())
+    }
+
+    /// Sell bounded by a tolerance in bps instead of a precomputed floor. The
+    /// quote is checked against the spot value before the trade; `min_sol_output`
+    /// is still enforced on top as a hard floor.
+    pub fn sell_tokens_with_slippage(
+        ctx: Context<SellTokens>,
+        token_amount: u64,
+        max_slippage_bps: u16,
+        min_sol_output: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        require!(max_slippage_bps <= 10_000, PumpError::InvalidSlippageTolerance);
+        // Bound what the seller actually receives, with the same quote the
+        // sell settles at
+        let quote = ctx.accounts.quote(token_amount)?;
+        let bonding_curve = &ctx.accounts.bonding_curve;
+        utils::check_sell_slippage(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+            token_amount,
+            quote.net_sol_amount,
+            max_slippage_bps,
+        )
+        .map_err(|_| PumpError::SlippageToleranceExceeded)?;
+
+        sell_tokens(ctx, token_amount, min_sol_output, deadline)
+    }
+
+    pub fn get_quote(
+        ctx: Context<GetQuote>,
+        direction: TradeDirection,
+        amount_in: u64,
+    ) -> Result<TradeQuote> {
+        instructions::get_quote::handler(ctx, direction, amount_in)
+    }
+
+    pub fn set_creator_fee_destination(
+        ctx: Context<SetCreatorFeeDestination>,
+        destination: Pubkey,
+    ) -> Result<()> {
+        instructions::set_creator_fee_destination::handler(ctx, destination)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees::handler(ctx)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>) -> Result<()> {
+        instructions::withdraw_fees::handler(ctx)
+    }
+
+    pub fn set_refund_mode(ctx: Context<SetRefundMode>, enabled: bool) -> Result<()> {
+        instructions::set_refund_mode::handler(ctx, enabled)
+    }
+
+    pub fn claim_refund(ctx: Context<ClaimRefund>, token_amount: u64) -> Result<()> {
+        instructions::claim_refund::handler(ctx, token_amount)
+    }
+
+    pub fn realized_slippage(ctx: Context<RealizedSlippage>, record: TradeRecord) -> Result<u64> {
+        instructions::realized_slippage::handler(ctx, record)
+    }
+
+    pub fn propose_authority_transfer(
+        ctx: Context<ProposeAuthorityTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_authority_transfer::handler(ctx, new_authority)
+    }
+
+    pub fn accept_authority_transfer(ctx: Context<AcceptAuthorityTransfer>) -> Result<()> {
+        instructions::accept_authority_transfer::handler(ctx)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: bool) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
+    }
+
+    pub fn update_token_metadata(ctx: Context<UpdateTokenMetadata>, update: MetadataUpdate) -> Result<()> {
+        instructions::update_token_metadata::handler(ctx, update)
+    }
+
+    pub fn break_even(ctx: Context<BreakEvenPrice>) -> Result<u64> {
+        instructions::break_even::handler(ctx)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::close_position::handler(ctx)
+    }
+
+    pub fn get_market_cap(ctx: Context<GetMarketCap>) -> Result<u64> {
+        instructions::get_market_cap::handler(ctx)
+    }
+
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        instructions::claim_vested::handler(ctx)
+    }
+
+    pub fn unlock_lp(ctx: Context<UnlockLp>) -> Result<()> {
+        instructions::unlock_lp::handler(ctx)
+    }
+
+    pub fn register_referral(ctx: Context<RegisterReferral>, code: String) -> Result<()> {
+        instructions::register_referral::handler(ctx, code)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        instructions::claim_referral_rewards::handler(ctx)
+    }
+
+    pub fn set_blacklist(ctx: Context<SetBlacklist>, wallet: Pubkey, is_blacklisted: bool) -> Result<()> {
+        instructions::set_blacklist::handler(ctx, wallet, is_blacklisted)
+    }
+
+    pub fn set_whitelist(ctx: Context<SetWhitelist>, wallet: Pubkey, is_whitelisted: bool) -> Result<()> {
+        instructions::set_whitelist::handler(ctx, wallet, is_whitelisted)
+    }
+
+    pub fn buy_tokens_batch<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyTokensBatch<'info>>,
+        legs: Vec<BuyLeg>,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::buy_tokens_batch::handler(ctx, legs, deadline)
+    }
+
+    pub fn preview_migration(ctx: Context<PreviewMigration>) -> Result<MigrationPreview> {
+        instructions::preview_migration::handler(ctx)
+    }
+
+    pub fn migrate_liquidity(ctx: Context<MigrateLiquidity>) -> Result<()> {
+        instructions::migrate_liquidity::handler(ctx)
+    }
+
     pub fn create_token(
         ctx: Context<CreateToken>,
         name: String,
         symbol: String,
         uri: String,
         initial_buy: u64,
+        config: LaunchConfig,
     ) -> Result<()> {
-        require!(!ctx.accounts.global_state.is_paused, PumpError::ProgramPaused);
+        ctx.accounts.global_state.check_creates_allowed()?;
         require!(name.len() <= 32, PumpError::NameTooLong);
         require!(symbol.len() <= 10, PumpError::SymbolTooLong);
         require!(uri.len() <= 200, PumpError::UriTooLong);
+        require!(config.sell_vesting_duration >= 0, PumpError::InvalidVestingSchedule);
+        require!(
+            config.creator_vesting_cliff >= 0
+                && config.creator_vesting_cliff <= config.creator_vesting_duration,
+            PumpError::InvalidVestingSchedule
+        );
+        require!(config.anti_snipe_duration >= 0, PumpError::InvalidConfiguration);
+        require!(config.whitelist_duration >= 0, PumpError::InvalidConfiguration);
+        let initial_virtual_sol_reserves = match config.initial_virtual_sol_reserves {
+            0 => INITIAL_VIRTUAL_SOL_RESERVES,
+            reserves => reserves,
+        };
+        let total_supply = match config.total_supply {
+            0 => utils::MAX_TOKEN_SUPPLY,
+            supply => supply,
+        };
+        let initial_virtual_token_reserves =
+            utils::scale_token_reserve(INITIAL_VIRTUAL_TOKEN_RESERVES, total_supply)?;
+        let initial_real_token_reserves =
+            utils::scale_token_reserve(INITIAL_REAL_TOKEN_RESERVES, total_supply)?;
+        let creator_fee_bps = ctx
+            .accounts
+            .global_state
+            .launch_fee_bps(config.creator_fee_bps, CREATOR_FEE_BASIS_POINTS)?;
+        let platform_fee_bps = ctx
+            .accounts
+            .global_state
+            .launch_fee_bps(config.platform_fee_bps, PLATFORM_FEE_BASIS_POINTS)?;
+        require!(
+            utils::meets_min_initial_reserves(
+                initial_virtual_sol_reserves,
+                ctx.accounts.global_state.min_initial_virtual_sol_reserves,
+            ),
+            PumpError::InvalidBondingCurveParams
+        );
+        require!(
+            utils::is_initial_buy_permitted(
+                initial_buy,
+                ctx.accounts.global_state.allow_creator_initial_buy,
+            ),
+            PumpError::MaxPurchaseAmountExceeded
+        );
+        // Curves are quoted in SOL until multi-quote launches land
+        require!(
+            utils::is_quote_mint_allowed(
+                &ctx.accounts.global_state.quote_mint_allowlist,
+                &anchor_spl::token::spl_token::native_mint::ID,
+            ),
+            PumpError::QuoteMintNotAllowed
+        );
+        require!(
+            config.fee_decay_start_bps <= 10_000
+                && config.fee_decay_floor_bps <= config.fee_decay_start_bps,
+            PumpError::InvalidFeePercentage
+        );
+        require!(
+            config.reserve_drift_tolerance_bps <= 10_000
+                && config.max_sold_before_graduation_bps <= 10_000,
+            PumpError::InvalidFeePercentage
+        );
 
         let bonding_curve = &mut ctx.accounts.bonding_curve;
         let mint = &ctx.accounts.mint;
         let creator = &ctx.accounts.creator;
 
+        bonding_curve.token_launch = ctx.accounts.token_launch.key();
+        bonding_curve.bump = ctx.bumps.bonding_curve;
         bonding_curve.creator = creator.key();
         bonding_curve.mint = mint.key();
-        bonding_curve.virtual_token_reserves = INITIAL_VIRTUAL_TOKEN_RESERVES;
-        bonding_curve.virtual_sol_reserves = INITIAL_VIRTUAL_SOL_RESERVES;
-        bonding_curve.real_token_reserves = INITIAL_REAL_TOKEN_RESERVES;
+        bonding_curve.virtual_token_reserves = initial_virtual_token_reserves;
+        bonding_curve.virtual_sol_reserves = initial_virtual_sol_reserves;
+        bonding_curve.real_token_reserves = initial_real_token_reserves;
         bonding_curve.real_sol_reserves = 0;
-        bonding_curve.token_total_supply = 1_000_000_000_000_000; // 1B tokens
+        bonding_curve.initial_virtual_token_reserves = initial_virtual_token_reserves;
+        bonding_curve.initial_virtual_sol_reserves = initial_virtual_sol_reserves;
+        bonding_curve.initial_real_token_reserves = initial_real_token_reserves;
+        bonding_curve.last_price = utils::canonical_price(
+            initial_virtual_sol_reserves,
+            initial_virtual_token_reserves,
+        )?;
+        bonding_curve.ath_price = bonding_curve.last_price;
+        bonding_curve.total_supply = total_supply;
         bonding_curve.complete = false;
+        bonding_curve.migrated = false;
         bonding_curve.created_at = Clock::get()?.unix_timestamp;
+        bonding_curve.sell_vesting_threshold = config.sell_vesting_threshold;
+        bonding_curve.sell_vesting_duration = config.sell_vesting_duration;
+        bonding_curve.fee_decay_start_bps = config.fee_decay_start_bps;
+        bonding_curve.fee_decay_floor_bps = config.fee_decay_floor_bps;
+        bonding_curve.fee_decay_volume = config.fee_decay_volume;
+        bonding_curve.min_buys_before_sell = config.min_buys_before_sell;
+        bonding_curve.max_holders_before_graduation = config.max_holders_before_graduation;
+        bonding_curve.reserve_drift_tolerance_bps = config.reserve_drift_tolerance_bps;
+        bonding_curve.max_sold_before_graduation_bps = config.max_sold_before_graduation_bps;
+        bonding_curve.anti_snipe_duration = config.anti_snipe_duration;
+        bonding_curve.max_buy_per_wallet = config.max_buy_per_wallet;
+        bonding_curve.creator_fee_bps = creator_fee_bps;
+        bonding_curve.platform_fee_bps = platform_fee_bps;
+        bonding_curve.lock_metadata_on_first_buy = config.lock_metadata_on_first_buy;
+        bonding_curve.whitelist_end_ts = match config.whitelist_duration {
+            0 => 0,
+            duration => bonding_curve.created_at.saturating_add(duration),
+        };
 
-        let global_state = &mut ctx.accounts.global_state;
-        global_state.total_tokens_created = global_state.total_tokens_created
-            .checked_add(1)
-            .ok_or(PumpError::Overflow)?;
+        let token_launch = &mut ctx.accounts.token_launch;
+        token_launch.creator = creator.key();
+        token_launch.mint = mint.key();
+        token_launch.bonding_curve = bonding_curve.key();
+        token_launch.metadata_uri = uri.clone();
+        token_launch.name = name.clone();
+        token_launch.symbol = symbol.clone();
+        token_launch.created_at = bonding_curve.created_at;
+        token_launch.total_supply = total_supply;
+        token_launch.decimals = mint.decimals;
+        token_launch.bump = ctx.bumps.token_launch;
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token::MintTo {
+                    mint: mint.to_account_info(),
+                    to: ctx.accounts.associated_bonding_curve.to_account_info(),
+                    authority: bonding_curve.to_account_info(),
+                },
+                &[&[BONDING_CURVE_SEED, mint.key().as_ref(), &[bonding_curve.bump]]],
+            ),
+            total_supply,
+        )?;
+
+        // The curve is the update authority, so `update_token_metadata` and
+        // `lock_metadata` can sign for it until the creator locks it
+        let (metadata_key, _) = Pubkey::find_program_address(
+            &[
+                b"metadata",
+                ctx.accounts.token_metadata_program.key().as_ref(),
+                mint.key().as_ref(),
+            ],
+            &ctx.accounts.token_metadata_program.key(),
+        );
+        require_keys_eq!(metadata_key, ctx.accounts.metadata.key(), PumpError::InvalidMetadataAccount);
+        anchor_spl::metadata::create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_metadata_program.to_account_info(),
+                anchor_spl::metadata::CreateMetadataAccountsV3 {
+                    metadata: ctx.accounts.metadata.to_account_info(),
+                    mint: mint.to_account_info(),
+                    mint_authority: bonding_curve.to_account_info(),
+                    payer: creator.to_account_info(),
+                    update_authority: bonding_curve.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                &[&[BONDING_CURVE_SEED, mint.key().as_ref(), &[bonding_curve.bump]]],
+            ),
+            anchor_spl::metadata::mpl_token_metadata::types::DataV2 {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            true,
+            true,
+            None,
+        )?;
+
+        instructions::initialize_global_state::fund_vault(
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.sol_vault.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+        ctx.accounts.global_state.record_token_created()?;
 
         emit!(TokenCreated {
             mint: mint.key(),
@@ -81,25 +611,81 @@ pub mod pump_clone {
         });
 
         if initial_buy > 0 {
-            let buy_ctx = Context::new(
-                ctx.program_id,
-                &mut BuyTokens {
-                    buyer: ctx.accounts.creator.clone(),
-                    mint: ctx.accounts.mint.clone(),
-                    bonding_curve: ctx.accounts.bonding_curve.clone(),
-                    associated_bonding_curve: ctx.accounts.associated_bonding_curve.clone(),
-                    associated_user: ctx.accounts.associated_user.clone(),
-                    token_vault: ctx.accounts.token_vault.clone(),
-                    sol_vault: ctx.accounts.sol_vault.clone(),
-                    global_state: ctx.accounts.global_state.clone(),
-                    token_program: ctx.accounts.token_program.clone(),
-                    associated_token_program: ctx.accounts.associated_token_program.clone(),
-                    system_program: ctx.accounts.system_program.clone(),
-                },
-                &[],
-                BuyTokens::bumps(),
-            );
-            buy_tokens(buy_ctx, initial_buy, u64::MAX)?;
+            // Priced and settled exactly like any other buy, at the launch
+            // reserves; the creator must get at least one token for it
+            let quote = buy::quote_buy(
+                &ctx.accounts.global_state,
+                &ctx.accounts.bonding_curve,
+                &ctx.accounts.creator.key(),
+                &BuyLeg { sol_amount: initial_buy, min_tokens_out: 1 },
+                None,
+            )?;
+            let creator_balance_before = ctx.accounts.associated_user.amount;
+            let created_at = ctx.accounts.bonding_curve.created_at;
+            buy::BuyAccounts {
+                buyer: &ctx.accounts.creator.to_account_info(),
+                bonding_curve: &ctx.accounts.bonding_curve.to_account_info(),
+                curve_token_account: &ctx.accounts.associated_bonding_curve.to_account_info(),
+                buyer_token_account: &ctx.accounts.associated_user.to_account_info(),
+                sol_vault: &ctx.accounts.sol_vault.to_account_info(),
+                fee_vault: &ctx.accounts.fee_vault.to_account_info(),
+                creator: &ctx.accounts.creator.to_account_info(),
+                community_wallet: None,
+                token_program: &ctx.accounts.token_program.to_account_info(),
+                system_program: &ctx.accounts.system_program.to_account_info(),
+            }
+            .settle(
+                &mut ctx.accounts.global_state,
+                &ctx.accounts.token_launch,
+                &mut ctx.accounts.bonding_curve,
+                None,
+                &quote,
+                ctx.accounts.mint.decimals,
+                created_at,
+            )?;
+            ctx.accounts.associated_user.reload()?;
+
+            // The creator's allocation goes straight into escrow and is
+            // released by `claim_vested`
+            let vested_tokens = ctx
+                .accounts
+                .associated_user
+                .amount
+                .checked_sub(creator_balance_before)
+                .ok_or(PumpError::Overflow)?;
+            let vesting_token_account = ctx
+                .accounts
+                .vesting_token_account
+                .as_ref()
+                .ok_or(PumpError::AccountNotInitialized)?;
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.associated_user.to_account_info(),
+                        to: vesting_token_account.to_account_info(),
+                        authority: ctx.accounts.creator.to_account_info(),
+                    },
+                ),
+                vested_tokens,
+            )?;
+
+            let start_ts = Clock::get()?.unix_timestamp;
+            let creator_vesting = ctx
+                .accounts
+                .creator_vesting
+                .as_mut()
+                .ok_or(PumpError::AccountNotInitialized)?;
+            creator_vesting.set_inner(CreatorVesting {
+                creator: ctx.accounts.creator.key(),
+                mint: ctx.accounts.mint.key(),
+                total_amount: vested_tokens,
+                start_ts,
+                cliff_ts: start_ts.saturating_add(config.creator_vesting_cliff),
+                duration: config.creator_vesting_duration,
+                claimed: 0,
+                bump: ctx.bumps.creator_vesting.unwrap_or_default(),
+            });
         }
 
         Ok(())
@@ -109,35 +695,72 @@ pub mod pump_clone {
         ctx: Context<BuyTokens>,
         sol_amount: u64,
         max_slippage_bps: u64,
+        deadline: i64,
     ) -> Result<()> {
-        require!(!ctx.accounts.global_state.is_paused, PumpError::ProgramPaused);
-        require!(sol_amount > 0, PumpError::InvalidAmount);
-        require!(!ctx.accounts.bonding_curve.complete, PumpError::BondingCurveComplete);
-
-        let bonding_curve = &mut ctx.accounts.bonding_curve;
-        
-        let token_amount = calculate_buy_amount(
+        crate::profile_marker!("buy_tokens:start");
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        let mut user_position = ctx
+            .accounts
+            .user_position
+            .as_ref()
+            .map(|position| buy::load_position(position))
+            .transpose()?;
+        let guard = buy::BuyGuard {
+            program_id: ctx.program_id,
+            global_state: &ctx.accounts.global_state,
+            token_launch: &ctx.accounts.token_launch,
+            buyer: ctx.accounts.buyer.key(),
+            bonding_curve: ctx.accounts.bonding_curve.key(),
+            buyer_blacklist: &ctx.accounts.buyer_blacklist.to_account_info(),
+            buyer_whitelist: &ctx.accounts.buyer_whitelist.to_account_info(),
+            market_maker: ctx.accounts.market_maker.as_deref(),
+            instructions_sysvar: ctx.accounts.instructions_sysvar.as_ref().map(|sysvar| sysvar.as_ref()),
+            referrals: ctx
+                .accounts
+                .buyer_referral
+                .as_ref()
+                .map_or(0, |referral| referral.successful_referrals),
+        };
+        guard.check_before_quote(
+            &mut ctx.accounts.bonding_curve,
+            user_position.as_mut(),
             sol_amount,
-            bonding_curve.virtual_sol_reserves,
-            bonding_curve.virtual_token_reserves,
+            deadline,
+            &clock,
         )?;
+        if let Some(buyer_wsol_account) = ctx.accounts.buyer_wsol_account.as_ref() {
+            unwrapped_payment_amount(sol_amount, buyer_wsol_account.amount)?;
+        }
 
-        require!(token_amount > 0, PumpError::InsufficientOutput);
-
-        let slippage_bps = calculate_slippage(sol_amount, token_amount, bonding_curve)?;
-        require!(slippage_bps <= max_slippage_bps, PumpError::SlippageExceeded);
-
+        let bonding_curve = &mut ctx.accounts.bonding_curve;
         let creator_fee = sol_amount
-            .checked_mul(CREATOR_FEE_BASIS_POINTS as u64)
+            .checked_mul(bonding_curve.creator_fee_bps as u64)
             .ok_or(PumpError::Overflow)?
             .checked_div(10000)
             .ok_or(PumpError::Overflow)?;
+        let creator_fee = utils::creator_fee_for_buyer(
+            creator_fee,
+            &ctx.accounts.buyer.key(),
+            &bonding_curve.creator,
+            ctx.accounts.global_state.waive_creator_self_fee,
+        );
 
         let platform_fee = sol_amount
-            .checked_mul(PLATFORM_FEE_BASIS_POINTS as u64)
+            .checked_mul(bonding_curve.trading_fee_bps(utils::fee_tier_bps(
+                &ctx.accounts.global_state.platform_fee_tiers,
+                sol_amount,
+                bonding_curve.platform_fee_bps,
+            )) as u64)
             .ok_or(PumpError::Overflow)?
             .checked_div(10000)
             .ok_or(PumpError::Overflow)?;
+        let platform_fee = utils::governance_discounted_fee(
+            platform_fee,
+            ctx.accounts.global_state.governance_fee_discount_bps,
+            ctx.accounts.governance_token_account.as_ref().map(|account| account.amount),
+            ctx.accounts.global_state.governance_min_balance,
+        )?;
 
         let net_sol_amount = sol_amount
             .checked_sub(creator_fee)
@@ -145,6 +768,104 @@ pub mod pump_clone {
             .checked_sub(platform_fee)
             .ok_or(PumpError::Overflow)?;
 
+        // Only the SOL that reaches the curve buys tokens
+        let token_amount = calculate_buy_amount(
+            net_sol_amount,
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?;
+        guard.check_quote(bonding_curve, user_position.as_ref(), token_amount, now)?;
+
+        let slippage_bps = calculate_slippage(
+            net_sol_amount,
+            token_amount,
+            bonding_curve.slippage_reference_price(now)?,
+        )?;
+        let max_slippage_bps = match max_slippage_bps {
+            utils::AUTO_SLIPPAGE => utils::auto_slippage_bps(
+                sol_amount,
+                bonding_curve.virtual_sol_reserves,
+                ctx.accounts.global_state.max_allowed_slippage_bps,
+            ) as u64,
+            requested => utils::resolve_max_slippage_bps(
+                requested,
+                ctx.accounts.user_settings.as_ref().map(|settings| settings.max_slippage_bps),
+            )?,
+        };
+        require!(slippage_bps <= max_slippage_bps, PumpError::SlippageExceeded);
+
+        crate::profile_marker!("buy_tokens:quoted");
+
+        // Every guard above runs before any account is created or lamports
+        // move, so a rejected buy costs the caller nothing beyond the tx fee.
+        // The scratch wSOL account, the buyer's token account and their
+        // position are all created from here on.
+
+        // Integrations holding wSOL unwrap into native lamports first so the
+        // remainder of the buy path is identical for both payment sources.
+        // Only `sol_amount` is unwrapped: it moves into a scratch account
+        // that is closed back to the buyer, whose other wSOL stays wrapped.
+        if let Some(buyer_wsol_account) = ctx.accounts.buyer_wsol_account.as_ref() {
+            let (wsol_unwrap_account, native_mint) = ctx
+                .accounts
+                .wsol_unwrap_account
+                .as_ref()
+                .zip(ctx.accounts.native_mint.as_ref())
+                .ok_or(PumpError::AccountNotInitialized)?;
+            let buyer_key = ctx.accounts.buyer.key();
+            utils::create_pda_account(
+                &ctx.accounts.buyer.to_account_info(),
+                &wsol_unwrap_account.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                TokenAccount::LEN,
+                &ctx.accounts.token_program.key(),
+                &[
+                    b"wsol_unwrap",
+                    buyer_key.as_ref(),
+                    &[ctx.bumps.wsol_unwrap_account.unwrap_or_default()],
+                ],
+            )?;
+            token::initialize_account3(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::InitializeAccount3 {
+                    account: wsol_unwrap_account.to_account_info(),
+                    mint: native_mint.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ))?;
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: buyer_wsol_account.to_account_info(),
+                        to: wsol_unwrap_account.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    },
+                ),
+                sol_amount,
+            )?;
+            token::close_account(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token::CloseAccount {
+                    account: wsol_unwrap_account.to_account_info(),
+                    destination: ctx.accounts.buyer.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                },
+            ))?;
+        }
+
+        anchor_spl::associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            anchor_spl::associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: ctx.accounts.associated_user.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        ))?;
+
         // Transfer SOL from buyer to sol vault
         let transfer_instruction = anchor_lang::solana_program::system_instruction::transfer(
             &ctx.accounts.buyer.key(),
@@ -159,87 +880,231 @@ pub mod pump_clone {
             ],
         )?;
 
-        // Transfer creator fee
-        if creator_fee > 0 {
-            let creator_transfer = anchor_lang::solana_program::system_instruction::transfer(
-                &ctx.accounts.buyer.key(),
-                &bonding_curve.creator,
-                creator_fee,
-            );
-            anchor_lang::solana_program::program::invoke(
-                &creator_transfer,
-                &[
-                    ctx.accounts.buyer.to_account_info(),
-                    ctx.accounts.buyer.to_account_info(), // This should be creator account
-                ],
-            )?;
-        }
+        // Creator fee to the launch's fee destination, less any community share
+        buy::pay_creator_fee(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.buyer.to_account_info(),
+            &ctx.accounts.token_launch,
+            &ctx.accounts.creator.to_account_info(),
+            ctx.accounts
+                .community_wallet
+                .as_ref()
+                .map(|community_wallet| community_wallet.to_account_info())
+                .as_ref(),
+            creator_fee,
+        )?;
 
-        // Transfer platform fee
-        if platform_fee > 0 {
-            let platform_transfer = anchor_lang::solana_program::system_instruction::transfer(
-                &ctx.accounts.buyer.key(),
-                &ctx.accounts.global_state.fee_recipient,
-                platform_fee,
-            );
-            anchor_lang::solana_program::program::invoke(
-                &platform_transfer,
-                &[
-                    ctx.accounts.buyer.to_account_info(),
-                    ctx.accounts.buyer.to_account_info(), // This should be fee recipient account
-                ],
+        // Route a slice of the platform fee into the holder rewards pool
+        let holder_rewards_share = match ctx.accounts.holder_rewards.as_mut() {
+            Some(holder_rewards) => {
+                let share = utils::calculate_fee(
+                    platform_fee,
+                    ctx.accounts.global_state.holder_rewards_bps,
+                )?;
+                if share > 0 {
+                    anchor_lang::system_program::transfer(
+                        CpiContext::new(
+                            ctx.accounts.system_program.to_account_info(),
+                            anchor_lang::system_program::Transfer {
+                                from: ctx.accounts.buyer.to_account_info(),
+                                to: holder_rewards.to_account_info(),
+                            },
+                        ),
+                        share,
+                    )?;
+                    holder_rewards.pending_rewards = holder_rewards.pending_rewards
+                        .checked_add(share)
+                        .ok_or(PumpError::Overflow)?;
+                }
+                share
+            }
+            None => 0,
+        };
+
+        // A referred buy pays its referrer a cut of the platform fee, parked
+        // on their referral account until claimed
+        let referral_share = match ctx.accounts.referrer_referral.as_mut() {
+            Some(referrer_referral) => {
+                let share = utils::calculate_fee(
+                    platform_fee,
+                    ctx.accounts.global_state.referral_fee_bps,
+                )?;
+                referrer_referral.accrue_reward(&ctx.accounts.buyer.key(), share)?;
+                if share > 0 {
+                    anchor_lang::system_program::transfer(
+                        CpiContext::new(
+                            ctx.accounts.system_program.to_account_info(),
+                            anchor_lang::system_program::Transfer {
+                                from: ctx.accounts.buyer.to_account_info(),
+                                to: referrer_referral.to_account_info(),
+                            },
+                        ),
+                        share,
+                    )?;
+                }
+                share
+            }
+            None => 0,
+        };
+        let platform_fee_to_recipient = platform_fee
+            .checked_sub(holder_rewards_share)
+            .and_then(|fee| fee.checked_sub(referral_share))
+            .ok_or(PumpError::Overflow)?;
+
+        // Crossing a trade-count milestone pays the creator a bonus out of
+        // this trade's platform fee
+        let milestone_bonus = if bonding_curve
+            .claim_trade_milestone(ctx.accounts.global_state.trade_milestone_interval)
+        {
+            let bonus = ctx
+                .accounts
+                .global_state
+                .trade_milestone_bonus
+                .min(platform_fee_to_recipient);
+            if bonus > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: ctx.accounts.creator.to_account_info(),
+                        },
+                    ),
+                    bonus,
+                )?;
+                emit!(TradeMilestoneReached {
+                    mint: ctx.accounts.mint.key(),
+                    creator: ctx.accounts.creator.key(),
+                    total_trades: bonding_curve.total_trades,
+                    bonus,
+                    timestamp: Clock::get()?.unix_timestamp,
+                });
+            }
+            bonus
+        } else {
+            0
+        };
+        let platform_fee_to_recipient = platform_fee_to_recipient
+            .checked_sub(milestone_bonus)
+            .ok_or(PumpError::Overflow)?;
+
+        // Platform fee accrues in the fee vault until the authority withdraws it
+        if platform_fee_to_recipient > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: ctx.accounts.fee_vault.to_account_info(),
+                    },
+                ),
+                platform_fee_to_recipient,
             )?;
+            ctx.accounts.global_state.record_fees_collected(platform_fee_to_recipient)?;
         }
 
-        // Transfer tokens from vault to buyer
+        let buyer_balance_before =
+            token::accessor::amount(&ctx.accounts.associated_user.to_account_info())?;
+
+        // Transfer tokens from the curve's token account to the buyer
         let mint_key = ctx.accounts.mint.key();
         let seeds = &[
             BONDING_CURVE_SEED,
             mint_key.as_ref(),
-            &[ctx.bumps.bonding_curve],
+            &[bonding_curve.bump],
         ];
         let signer = &[&seeds[..]];
 
         let cpi_accounts = Transfer {
             from: ctx.accounts.associated_bonding_curve.to_account_info(),
             to: ctx.accounts.associated_user.to_account_info(),
-            authority: ctx.accounts.bonding_curve.to_account_info(),
+            authority: bonding_curve.to_account_info(),
         };
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
         token::transfer(cpi_ctx, token_amount)?;
 
-        // Update bonding curve state
-        bonding_curve.virtual_sol_reserves = bonding_curve.virtual_sol_reserves
-            .checked_add(net_sol_amount)
-            .ok_or(PumpError::Overflow)?;
-        
-        bonding_curve.virtual_token_reserves = bonding_curve.virtual_token_reserves
-            .checked_sub(token_amount)
-            .ok_or(PumpError::Overflow)?;
-        
-        bonding_curve.real_sol_reserves = bonding_curve.real_sol_reserves
-            .checked_add(net_sol_amount)
-            .ok_or(PumpError::Overflow)?;
-        
-        bonding_curve.real_token_reserves = bonding_curve.real_token_reserves
-            .checked_sub(token_amount)
-            .ok_or(PumpError::Overflow)?;
+        let holder_threshold = utils::holder_balance_threshold(
+            ctx.accounts.global_state.min_holder_balance,
+            ctx.accounts.mint.decimals,
+        );
+        bonding_curve.holders_count = utils::apply_holder_delta(
+            bonding_curve.holders_count,
+            buyer_balance_before,
+            buyer_balance_before.saturating_add(token_amount),
+            holder_threshold,
+        );
 
-        // Check if funding goal reached
-        if bonding_curve.real_sol_reserves >= FUNDING_GOAL {
-            bonding_curve.complete = true;
+        if let (Some(user_position), Some(position_info)) =
+            (user_position.as_mut(), ctx.accounts.user_position.as_ref())
+        {
+            user_position.open(
+                ctx.accounts.buyer.key(),
+                ctx.accounts.token_launch.key(),
+                ctx.accounts.mint.key(),
+                bonding_curve.creator,
+                ctx.bumps.user_position.unwrap_or_default(),
+            );
+            let price = utils::canonical_price(
+                bonding_curve.virtual_sol_reserves,
+                bonding_curve.virtual_token_reserves,
+            )?;
+            user_position.update_after_buy(net_sol_amount, token_amount, price)?;
+            buy::store_position(
+                ctx.program_id,
+                &position_info.to_account_info(),
+                &ctx.accounts.buyer.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                user_position,
+            )?;
+        }
+
+        crate::profile_marker!("buy_tokens:transferred");
+
+        let price_before = utils::canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?;
+
+        if let Some(completed) = bonding_curve.settle_buy(
+            net_sol_amount,
+            token_amount,
+            sol_amount,
+            FUNDING_GOAL,
+            ctx.accounts.global_state.migration_threshold,
+            now,
+        )? {
+            emit!(completed);
             emit!(BondingCurveComplete {
                 mint: ctx.accounts.mint.key(),
                 final_sol_reserves: bonding_curve.real_sol_reserves,
-                timestamp: Clock::get()?.unix_timestamp,
+                timestamp: now,
             });
         }
 
-        let global_state = &mut ctx.accounts.global_state;
-        global_state.total_volume = global_state.total_volume
-            .checked_add(sol_amount)
-            .ok_or(PumpError::Overflow)?;
+        ctx.accounts.global_state.record_volume(sol_amount)?;
+
+        let price = utils::canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?;
+        emit!(Trade {
+            side: TradeDirection::Buy,
+            wallet: ctx.accounts.buyer.key(),
+            mint: ctx.accounts.mint.key(),
+            sol_amount,
+            token_amount,
+            creator_fee,
+            platform_fee,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+            price,
+            price_impact_bps: utils::price_impact_bps(price_before, price),
+            timestamp: Clock::get()?.unix_timestamp,
+            schema_version: Trade::SCHEMA_VERSION,
+        });
 
         emit!(TokensPurchased {
             buyer: ctx.accounts.buyer.key(),
@@ -250,9 +1115,14 @@ pub mod pump_clone {
             platform_fee,
             virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
             virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            price_before,
+            price_after: price,
+            // The buyer paid fees on top of what reached the curve
+            effective_price: utils::effective_price(sol_amount, token_amount)?,
             timestamp: Clock::get()?.unix_timestamp,
         });
 
+        crate::profile_marker!("buy_tokens:end");
         Ok(())
     }
 
@@ -260,38 +1130,132 @@ pub mod pump_clone {
         ctx: Context<SellTokens>,
         token_amount: u64,
         min_sol_output: u64,
+        deadline: i64,
     ) -> Result<()> {
-        require!(!ctx.accounts.global_state.is_paused, PumpError::ProgramPaused);
+        crate::profile_marker!("sell_tokens:start");
+        ctx.accounts.global_state.check_sells_allowed()?;
+        Blacklist::check_not_blacklisted(&ctx.accounts.seller_blacklist.to_account_info())?;
         require!(token_amount > 0, PumpError::InvalidAmount);
         require!(!ctx.accounts.bonding_curve.complete, PumpError::BondingCurveComplete);
+        ctx.accounts.bonding_curve.check_not_in_refund_mode()?;
+        let now = Clock::get()?.unix_timestamp;
+        require!(!utils::is_deadline_expired(now, deadline), PumpError::OperationExpired);
+        ctx.accounts.token_launch.check_trading_hours(now)?;
 
+        let SellQuote {
+            sol_amount,
+            creator_fee,
+            platform_fee,
+            sell_tax,
+            floor_share,
+            net_sol_amount,
+        } = ctx.accounts.quote(token_amount)?;
+
+        let max_trades_per_slot = ctx.accounts.global_state.max_trades_per_slot;
         let bonding_curve = &mut ctx.accounts.bonding_curve;
-        
-        let sol_amount = calculate_sell_amount(
+        bonding_curve.record_trade_in_slot(Clock::get()?.slot, max_trades_per_slot)?;
+        bonding_curve.update_twap(now)?;
+
+        let reference_price = bonding_curve.slippage_reference_price(now)?;
+        // Auto-slippage stands in for the stored default, sized to the sell
+        let (min_sol_output, default_slippage_bps) = match min_sol_output {
+            utils::AUTO_SLIPPAGE => (
+                utils::USE_DEFAULT_SLIPPAGE,
+                Some(utils::auto_slippage_bps(
+                    token_amount,
+                    bonding_curve.virtual_token_reserves,
+                    ctx.accounts.global_state.max_allowed_slippage_bps,
+                )),
+            ),
+            requested => (
+                requested,
+                ctx.accounts.user_settings.as_ref().map(|settings| settings.max_slippage_bps),
+            ),
+        };
+        let min_sol_output = utils::resolve_min_sol_output(
+            min_sol_output,
+            default_slippage_bps,
             token_amount,
-            bonding_curve.virtual_token_reserves,
-            bonding_curve.virtual_sol_reserves,
+            reference_price,
         )?;
-
         require!(sol_amount >= min_sol_output, PumpError::InsufficientOutput);
 
-        let creator_fee = sol_amount
-            .checked_mul(CREATOR_FEE_BASIS_POINTS as u64)
-            .ok_or(PumpError::Overflow)?
-            .checked_div(10000)
-            .ok_or(PumpError::Overflow)?;
+        let global_state = &ctx.accounts.global_state;
+        if global_state.wallet_throttle_applies(ctx.accounts.market_maker.as_deref())? {
+            ctx.accounts
+                .seller_position
+                .as_mut()
+                .ok_or(PumpError::AccountNotInitialized)?
+                .check_wallet_throttle(
+                    now,
+                    sol_amount,
+                    global_state.wallet_trade_cooldown_secs,
+                    global_state.wallet_daily_sol_limit,
+                )?;
+        }
+        if global_state.atomic_trade_guard {
+            let instructions_sysvar = ctx
+                .accounts
+                .instructions_sysvar
+                .as_ref()
+                .ok_or(PumpError::AccountNotInitialized)?;
+            let (instructions, current_index) =
+                utils::load_transaction_instructions(&instructions_sysvar.to_account_info())?;
+            require!(
+                !utils::is_atomic_round_trip(
+                    &instructions,
+                    current_index,
+                    ctx.program_id,
+                    &utils::BUY_DISCRIMINATORS,
+                    &ctx.accounts.seller.key(),
+                    &bonding_curve.key(),
+                ),
+                PumpError::MevProtectionActive
+            );
+            ctx.accounts
+                .seller_position
+                .as_ref()
+                .ok_or(PumpError::AccountNotInitialized)?
+                .check_same_slot_round_trip(Clock::get()?.slot)?;
+        }
 
-        let platform_fee = sol_amount
-            .checked_mul(PLATFORM_FEE_BASIS_POINTS as u64)
-            .ok_or(PumpError::Overflow)?
-            .checked_div(10000)
+        // The creator's sell tax stays with the curve, except the share that
+        // funds the price floor; what stays is still counted in its reserves
+        let sol_leaving_curve = sol_amount
+            .checked_sub(sell_tax)
+            .and_then(|amount| amount.checked_add(floor_share))
             .ok_or(PumpError::Overflow)?;
 
-        let net_sol_amount = sol_amount
-            .checked_sub(creator_fee)
-            .ok_or(PumpError::Overflow)?
-            .checked_sub(platform_fee)
-            .ok_or(PumpError::Overflow)?;
+        let vest_proceeds = requires_sell_vesting(net_sol_amount, bonding_curve.sell_vesting_threshold);
+        require!(
+            !vest_proceeds || ctx.accounts.sell_vesting_escrow.is_some(),
+            PumpError::SellVestingEscrowRequired
+        );
+
+        // Surface a frozen account up front instead of an opaque CPI failure
+        let seller_token_info = ctx.accounts.associated_user.to_account_info();
+        require!(
+            !utils::is_token_account_frozen(&seller_token_info.try_borrow_data()?),
+            PumpError::TokenAccountFrozen
+        );
+
+        let seller_balance_before =
+            token::accessor::amount(&ctx.accounts.associated_user.to_account_info())?;
+
+        bonding_curve.check_min_buys_before_sell(
+            ctx.accounts.seller_position.as_ref().map(|position| position.buy_count),
+        )?;
+
+        let max_extraction_multiple_bps = ctx.accounts.global_state.max_sell_extraction_multiple_bps;
+        if max_extraction_multiple_bps > 0 {
+            ctx.accounts
+                .seller_position
+                .as_ref()
+                .ok_or(PumpError::AccountNotInitialized)?
+                .check_sell_extraction(token_amount, net_sol_amount, max_extraction_multiple_bps)?;
+        }
+
+        crate::profile_marker!("sell_tokens:quoted");
 
         // Transfer tokens from seller to vault
         let cpi_accounts = Transfer {
@@ -303,20 +1267,142 @@ pub mod pump_clone {
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
         token::transfer(cpi_ctx, token_amount)?;
 
-        // Transfer SOL from vault to seller
+        let holder_threshold = utils::holder_balance_threshold(
+            ctx.accounts.global_state.min_holder_balance,
+            ctx.accounts.mint.decimals,
+        );
+        bonding_curve.holders_count = utils::apply_holder_delta(
+            bonding_curve.holders_count,
+            seller_balance_before,
+            seller_balance_before.saturating_sub(token_amount),
+            holder_threshold,
+        );
+
+        // Transfer SOL from vault to seller. The vault is a system account,
+        // so every debit goes through the system program under its seeds.
         let mint_key = ctx.accounts.mint.key();
         let sol_vault_seeds = &[
             SOL_VAULT_SEED,
             mint_key.as_ref(),
             &[ctx.bumps.sol_vault],
         ];
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let sol_vault = ctx.accounts.sol_vault.to_account_info();
+        let pay_from_vault = |to, amount: u64| -> Result<()> {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    system_program.clone(),
+                    anchor_lang::system_program::Transfer { from: sol_vault.clone(), to },
+                    &[&sol_vault_seeds[..]],
+                ),
+                amount,
+            )
+        };
 
-        **ctx.accounts.sol_vault.to_account_info().try_borrow_mut_lamports()? -= net_sol_amount;
-        **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += net_sol_amount;
+        if vest_proceeds {
+            // Large exits are routed into the seller's escrow and unlock linearly
+            let escrow_info = ctx
+                .accounts
+                .sell_vesting_escrow
+                .as_ref()
+                .ok_or(PumpError::SellVestingEscrowRequired)?
+                .to_account_info();
+            let escrow_bump = ctx.bumps.sell_vesting_escrow.unwrap_or_default();
+            if escrow_info.data_is_empty() {
+                let seller_key = ctx.accounts.seller.key();
+                utils::create_pda_account(
+                    &ctx.accounts.seller.to_account_info(),
+                    &escrow_info,
+                    &ctx.accounts.system_program.to_account_info(),
+                    SellVestingEscrow::LEN,
+                    ctx.program_id,
+                    &[b"sell_vesting", mint_key.as_ref(), seller_key.as_ref(), &[escrow_bump]],
+                )?;
+                SellVestingEscrow::default().try_serialize(&mut &mut escrow_info.try_borrow_mut_data()?[..])?;
+            }
+            let mut escrow: Account<SellVestingEscrow> = Account::try_from(&escrow_info)?;
+            let now = Clock::get()?.unix_timestamp;
+
+            // Settle whatever has already vested, then restart the window
+            // over the remaining locked balance plus the new proceeds.
+            let claimable = escrow.claimable_amount(now);
+            let locked = escrow
+                .total_amount
+                .checked_sub(escrow.claimed_amount)
+                .and_then(|x| x.checked_sub(claimable))
+                .ok_or(PumpError::Overflow)?;
+
+            if claimable > 0 {
+                **escrow.to_account_info().try_borrow_mut_lamports()? -= claimable;
+                **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += claimable;
+            }
+
+            escrow.seller = ctx.accounts.seller.key();
+            escrow.mint = mint_key;
+            escrow.total_amount = locked
+                .checked_add(net_sol_amount)
+                .ok_or(PumpError::Overflow)?;
+            escrow.claimed_amount = 0;
+            escrow.start_timestamp = now;
+            escrow.end_timestamp = now
+                .checked_add(bonding_curve.sell_vesting_duration)
+                .ok_or(PumpError::Overflow)?;
+            escrow.bump = escrow_bump;
+            escrow.exit(ctx.program_id)?;
+
+            pay_from_vault(escrow_info, net_sol_amount)?;
+        } else {
+            pay_from_vault(ctx.accounts.seller.to_account_info(), net_sol_amount)?;
+        }
+
+        // The platform fee has left the curve's reserves; park it in the
+        // platform fee vault alongside buy-side fees until it is withdrawn
+        if platform_fee > 0 {
+            pay_from_vault(ctx.accounts.fee_vault.to_account_info(), platform_fee)?;
+            ctx.accounts.global_state.record_fees_collected(platform_fee)?;
+        }
+
+        // The creator's cut of a sell stays in the vault until claimed; the
+        // launch's community share of it is paid out now, as on buys
+        let (creator_share, community_share) =
+            utils::split_creator_fee(creator_fee, ctx.accounts.token_launch.community_fee_bps)?;
+        if community_share > 0 {
+            let community_wallet = ctx
+                .accounts
+                .community_wallet
+                .as_ref()
+                .ok_or(PumpError::InvalidFeeRecipient)?;
+            pay_from_vault(community_wallet.to_account_info(), community_share)?;
+        }
+        ctx.accounts.token_launch.accrue_creator_fee(creator_share)?;
+
+        if floor_share > 0 {
+            let price_floor_vault = ctx
+                .accounts
+                .price_floor_vault
+                .as_ref()
+                .ok_or(PumpError::AccountNotInitialized)?;
+            pay_from_vault(price_floor_vault.to_account_info(), floor_share)?;
+        }
+
+        if let Some(seller_position) = ctx.accounts.seller_position.as_mut() {
+            let price = utils::canonical_price(
+                bonding_curve.virtual_sol_reserves,
+                bonding_curve.virtual_token_reserves,
+            )?;
+            seller_position.update_after_sell(net_sol_amount, token_amount, price)?;
+        }
+
+        crate::profile_marker!("sell_tokens:transferred");
+
+        let price_before = utils::canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?;
 
         // Update bonding curve state
         bonding_curve.virtual_sol_reserves = bonding_curve.virtual_sol_reserves
-            .checked_sub(sol_amount)
+            .checked_sub(sol_leaving_curve)
             .ok_or(PumpError::Overflow)?;
         
         bonding_curve.virtual_token_reserves = bonding_curve.virtual_token_reserves
@@ -324,23 +1410,73 @@ pub mod pump_clone {
             .ok_or(PumpError::Overflow)?;
         
         bonding_curve.real_sol_reserves = bonding_curve.real_sol_reserves
-            .checked_sub(sol_amount)
+            .checked_sub(sol_leaving_curve)
             .ok_or(PumpError::Overflow)?;
         
         bonding_curve.real_token_reserves = bonding_curve.real_token_reserves
             .checked_add(token_amount)
             .ok_or(PumpError::Overflow)?;
+        bonding_curve.check_reserve_ratio()?;
+        bonding_curve.record_price_high(utils::canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?);
+        bonding_curve.check_circuit_breaker(
+            Clock::get()?.slot,
+            price_before,
+            utils::canonical_price(
+                bonding_curve.virtual_sol_reserves,
+                bonding_curve.virtual_token_reserves,
+            )?,
+            ctx.accounts.global_state.max_price_drop_bps_per_slot,
+        )?;
 
-        let global_state = &mut ctx.accounts.global_state;
-        global_state.total_volume = global_state.total_volume
+        bonding_curve.total_volume = bonding_curve.total_volume
             .checked_add(sol_amount)
             .ok_or(PumpError::Overflow)?;
+        bonding_curve.record_rolling_volume(now, sol_amount)?;
+
+        ctx.accounts.global_state.record_volume(sol_amount)?;
+
+        let price = utils::canonical_price(
+            bonding_curve.virtual_sol_reserves,
+            bonding_curve.virtual_token_reserves,
+        )?;
+        emit!(Trade {
+            side: TradeDirection::Sell,
+            wallet: ctx.accounts.seller.key(),
+            mint: ctx.accounts.mint.key(),
+            sol_amount,
+            token_amount,
+            creator_fee,
+            platform_fee,
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            real_sol_reserves: bonding_curve.real_sol_reserves,
+            real_token_reserves: bonding_curve.real_token_reserves,
+            price,
+            price_impact_bps: utils::price_impact_bps(price_before, price),
+            timestamp: Clock::get()?.unix_timestamp,
+            schema_version: Trade::SCHEMA_VERSION,
+        });
 
         emit!(TokensSold {
+            price_before,
+            price_after: price,
+            // Only what the seller received after fees and sell tax
+            effective_price: utils::effective_price(net_sol_amount, token_amount)?,
             seller: ctx.accounts.seller.key(),
             mint: ctx.accounts.mint.key(),
             token_amount,
             sol_amount,
             creator_fee,
             platform_fee,
-            virtual_sol_reserves: bonding_curve.virtual_sol_
\ No newline at end of file
+            virtual_sol_reserves: bonding_curve.virtual_sol_reserves,
+            virtual_token_reserves: bonding_curve.virtual_token_reserves,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        crate::profile_marker!("sell_tokens:end");
+        Ok(())
+    }
+}
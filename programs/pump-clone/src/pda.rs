@@ -0,0 +1,36 @@
+//! Seeds and address derivations for the program's PDAs. Account constraints
+//! use the seed constants, and clients can call the helpers instead of
+//! rebuilding seeds by hand. Nothing here touches accounts, so the module
+//! builds unchanged on-chain and in client crates.
+
+use anchor_spl::associated_token::get_associated_token_address;
+use solana_program::pubkey::Pubkey;
+
+pub const BONDING_CURVE_SEED: &[u8] = b"bonding_curve";
+pub const SOL_VAULT_SEED: &[u8] = b"sol_vault";
+pub const GLOBAL_STATE_SEED: &[u8] = b"global_state";
+
+/// Curve seed prefixes used by earlier program versions. `rescue_curve_tokens`
+/// only signs for these, never for a prefix of a live PDA such as
+/// `b"vesting"`.
+pub const RETIRED_CURVE_SEEDS: &[&[u8]] = &[b"bonding-curve"];
+
+/// The curve PDA for `mint`; also the authority over the curve's tokens.
+pub fn bonding_curve(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BONDING_CURVE_SEED, mint.as_ref()], &crate::ID)
+}
+
+/// System account holding the SOL paid into `mint`'s curve.
+pub fn sol_vault(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[SOL_VAULT_SEED, mint.as_ref()], &crate::ID)
+}
+
+/// The curve's associated token account, which `create_token` mints the
+/// whole supply into and trades and migration draw on.
+pub fn curve_token_account(mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(&bonding_curve(mint).0, mint)
+}
+
+pub fn global_state() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_STATE_SEED], &crate::ID)
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 
 #[account]
+#[derive(Default)]
 pub struct GlobalState {
     pub authority: Pubkey,
     pub fee_recipient: Pubkey,
@@ -12,6 +13,83 @@ pub struct GlobalState {
     pub total_tokens_created: u64,
     pub total_volume: u64,
     pub is_paused: bool,
+    pub last_graduation_slot: u64,
+    pub graduation_cooldown_slots: u64,
+    pub min_migration_market_cap: u64,
+    pub holder_rewards_bps: u16,
+    pub min_holder_balance: u64,
+    pub creator_liquidity_enabled: bool,
+    pub max_trades_per_slot: u16,
+    pub waive_creator_self_fee: bool,
+    /// Quote mints new curves may launch against. Unused slots are the
+    /// default pubkey; an all-empty list allows any quote mint.
+    pub quote_mint_allowlist: [Pubkey; GlobalState::MAX_QUOTE_MINTS],
+    pub trade_milestone_interval: u64,
+    pub trade_milestone_bonus: u64,
+    /// Max deviation of the migration seed price from the TWAP; zero disables.
+    pub migration_price_band_bps: u16,
+    pub allow_creator_initial_buy: bool,
+    pub renounce_authorities_on_migration: bool,
+    pub min_initial_virtual_sol_reserves: u64,
+    /// Per-wallet buy cap in lamports; zero leaves buys uncapped.
+    pub max_buy_base: u64,
+    /// Extra cap granted per successful referral, for up to
+    /// `utils::MAX_BONUS_REFERRALS` referrals.
+    pub referral_buy_bonus: u64,
+    /// Emergency mode: buys are blocked while sells stay open.
+    pub sells_only: bool,
+    /// Minimum seconds between two trades from the same wallet on a curve;
+    /// zero disables the cooldown.
+    pub wallet_trade_cooldown_secs: i64,
+    /// Max lamports a wallet may trade on a curve per UTC day; zero disables.
+    pub wallet_daily_sol_limit: u64,
+    /// Rejects a buy and sell of the same curve by one wallet in a single
+    /// transaction, or a sell in the same slot as that wallet's last buy.
+    pub atomic_trade_guard: bool,
+    /// Cap on a wallet's cumulative sell proceeds from a curve, in bps of the
+    /// SOL it invested (30_000 = 3x); zero disables it.
+    pub max_sell_extraction_multiple_bps: u32,
+    /// `PAUSE_*` bits for instructions disabled independently of `is_paused`
+    pub paused_instructions: u8,
+    /// Platform token whose holders get `governance_fee_discount_bps` off the
+    /// platform fee; the default pubkey disables the discount.
+    pub governance_mint: Pubkey,
+    pub governance_min_balance: u64,
+    pub governance_fee_discount_bps: u16,
+    /// Platform fee by trade size; see `utils::fee_tier_bps` for selection.
+    pub platform_fee_tiers: [FeeTier; GlobalState::MAX_FEE_TIERS],
+    /// Send a migrated curve's sub-rent-exempt vault dust to the platform fee
+    /// vault on close instead of the closer's rent recipient.
+    pub vault_dust_to_fee_recipient: bool,
+    /// Halts every state-mutating instruction, migrations included, until
+    /// the authority lifts it. Stricter than `is_paused`.
+    pub global_kill_switch: bool,
+    /// When a creator changes fee destination, send fees accrued before the
+    /// change to the new destination instead of the old one.
+    pub accrued_creator_fees_follow_destination: bool,
+    /// Cap on the tolerance derived for trades sent with `utils::AUTO_SLIPPAGE`
+    pub max_allowed_slippage_bps: u16,
+    /// Lamports paid into the platform fee vault
+    pub total_fees_collected: u64,
+    /// Lamports withdrawn from the platform fee vault to `fee_recipient`
+    pub total_fees_withdrawn: u64,
+    /// Authority proposed by the current one; takes over once it accepts
+    pub pending_authority: Option<Pubkey>,
+    /// Cap on the creator and platform fee a launch may choose
+    pub max_fee_bps: u16,
+    /// Most tokens one buy may take, in bps of the curve's remaining real
+    /// token reserves; zero disables the cap
+    pub max_tokens_per_buy_bps: u16,
+    /// Seconds a migrated pool's LP tokens stay locked before the creator
+    /// can withdraw them
+    pub lp_lock_duration: i64,
+    /// AMM that graduated curves migrate into
+    pub migration_target: MigrationTarget,
+    /// Share of a referred buy's platform fee paid to the referrer, in bps
+    pub referral_fee_bps: u16,
+    /// Most a curve's price may fall within one slot through sells, in bps
+    /// of its price when the slot's first sell arrived; zero disables
+    pub max_price_drop_bps_per_slot: u16,
     pub bump: u8,
 }
 
@@ -27,10 +105,265 @@ impl GlobalState {
         8 + // total_tokens_created
         8 + // total_volume
         1 + // is_paused
+        8 + // last_graduation_slot
+        8 + // graduation_cooldown_slots
+        8 + // min_migration_market_cap
+        2 + // holder_rewards_bps
+        8 + // min_holder_balance
+        1 + // creator_liquidity_enabled
+        2 + // max_trades_per_slot
+        1 + // waive_creator_self_fee
+        32 * GlobalState::MAX_QUOTE_MINTS + // quote_mint_allowlist
+        8 + // trade_milestone_interval
+        8 + // trade_milestone_bonus
+        2 + // migration_price_band_bps
+        1 + // allow_creator_initial_buy
+        1 + // renounce_authorities_on_migration
+        8 + // min_initial_virtual_sol_reserves
+        8 + // max_buy_base
+        8 + // referral_buy_bonus
+        1 + // sells_only
+        8 + // wallet_trade_cooldown_secs
+        8 + // wallet_daily_sol_limit
+        1 + // atomic_trade_guard
+        4 + // max_sell_extraction_multiple_bps
+        1 + // paused_instructions
+        32 + // governance_mint
+        8 + // governance_min_balance
+        2 + // governance_fee_discount_bps
+        FeeTier::LEN * GlobalState::MAX_FEE_TIERS + // platform_fee_tiers
+        1 + // vault_dust_to_fee_recipient
+        1 + // global_kill_switch
+        1 + // accrued_creator_fees_follow_destination
+        2 + // max_allowed_slippage_bps
+        8 + // total_fees_collected
+        8 + // total_fees_withdrawn
+        1 + 32 + // pending_authority (Option<Pubkey>)
+        2 + // max_fee_bps
+        2 + // max_tokens_per_buy_bps
+        8 + // lp_lock_duration
+        1 + // migration_target
+        2 + // referral_fee_bps
+        2 + // max_price_drop_bps_per_slot
         1; // bump
+
+    pub const MAX_QUOTE_MINTS: usize = 4;
+    pub const MAX_FEE_TIERS: usize = 4;
+    pub const DEFAULT_MAX_ALLOWED_SLIPPAGE_BPS: u16 = 1_000;
+    pub const DEFAULT_MAX_FEE_BPS: u16 = 500;
+    pub const DEFAULT_LP_LOCK_DURATION: i64 = 30 * 86_400;
+
+    pub const PAUSE_CREATE: u8 = 1 << 0;
+    pub const PAUSE_BUY: u8 = 1 << 1;
+    pub const PAUSE_SELL: u8 = 1 << 2;
+    pub const PAUSE_MIGRATE: u8 = 1 << 3;
+
+    /// One-time setup of a fresh deployment. Refuses to run once an
+    /// authority has been recorded, so it can't be used to take over.
+    pub fn bootstrap(&mut self, authority: Pubkey, config: &BootstrapConfig, bump: u8) -> Result<()> {
+        require!(
+            self.authority == Pubkey::default(),
+            crate::error::PumpCloneError::AccountAlreadyInitialized
+        );
+        require!(
+            config.platform_fee_bps <= 10_000 && config.creator_fee_bps <= 10_000,
+            crate::error::PumpCloneError::InvalidFeePercentage
+        );
+        require!(
+            config.min_sol_threshold <= config.max_sol_threshold,
+            crate::error::PumpCloneError::InvalidConfiguration
+        );
+
+        *self = GlobalState {
+            authority,
+            fee_recipient: config.fee_recipient,
+            platform_fee_bps: config.platform_fee_bps,
+            creator_fee_bps: config.creator_fee_bps,
+            migration_threshold: config.migration_threshold,
+            min_sol_threshold: config.min_sol_threshold,
+            max_sol_threshold: config.max_sol_threshold,
+            allow_creator_initial_buy: true,
+            renounce_authorities_on_migration: true,
+            max_allowed_slippage_bps: GlobalState::DEFAULT_MAX_ALLOWED_SLIPPAGE_BPS,
+            max_fee_bps: GlobalState::DEFAULT_MAX_FEE_BPS,
+            lp_lock_duration: GlobalState::DEFAULT_LP_LOCK_DURATION,
+            bump,
+            ..GlobalState::default()
+        };
+        Ok(())
+    }
+
+    pub fn check_authority(&self, signer: &Pubkey) -> Result<()> {
+        require_keys_eq!(*signer, self.authority, crate::error::PumpCloneError::Unauthorized);
+        Ok(())
+    }
+
+    /// Resolves a fee chosen at launch. `None` keeps `default_bps`, lowered to
+    /// the cap if needed; an explicit value above `max_fee_bps` is rejected.
+    pub fn launch_fee_bps(&self, requested: Option<u16>, default_bps: u16) -> Result<u16> {
+        match requested {
+            None => Ok(default_bps.min(self.max_fee_bps)),
+            Some(bps) => {
+                require!(
+                    bps <= self.max_fee_bps,
+                    crate::error::PumpCloneError::InvalidFeePercentage
+                );
+                Ok(bps)
+            }
+        }
+    }
+
+    /// First step of an authority rotation. A new proposal replaces any
+    /// pending one.
+    pub fn propose_authority(&mut self, signer: &Pubkey, new_authority: Pubkey) -> Result<()> {
+        self.check_authority(signer)?;
+        self.pending_authority = Some(new_authority);
+        Ok(())
+    }
+
+    /// Second step: only the proposed key can take over, which proves the
+    /// new authority is a key someone actually controls.
+    pub fn accept_authority(&mut self, signer: &Pubkey) -> Result<()> {
+        require!(
+            self.pending_authority == Some(*signer),
+            crate::error::PumpCloneError::Unauthorized
+        );
+        self.authority = *signer;
+        self.pending_authority = None;
+        Ok(())
+    }
+
+    pub fn record_token_created(&mut self) -> Result<()> {
+        self.total_tokens_created = self.total_tokens_created
+            .checked_add(1)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_volume(&mut self, sol_amount: u64) -> Result<()> {
+        self.total_volume = self.total_volume
+            .checked_add(sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_fees_collected(&mut self, amount: u64) -> Result<()> {
+        self.total_fees_collected = self.total_fees_collected
+            .checked_add(amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_fees_withdrawn(&mut self, amount: u64) -> Result<()> {
+        self.total_fees_withdrawn = self.total_fees_withdrawn
+            .checked_add(amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Fails while the global kill switch is engaged. Only
+    /// `update_global_state`, which lifts it, is exempt.
+    pub fn check_not_killed(&self) -> Result<()> {
+        require!(!self.global_kill_switch, crate::error::PumpCloneError::KillSwitchEngaged);
+        Ok(())
+    }
+
+    /// Fails when `flag` is set in `paused_instructions` or the kill switch
+    /// is engaged.
+    pub fn check_instruction_enabled(&self, flag: u8) -> Result<()> {
+        self.check_not_killed()?;
+        require!(
+            self.paused_instructions & flag == 0,
+            crate::error::PumpCloneError::InstructionPaused
+        );
+        Ok(())
+    }
+
+    /// Caps a single buy at a share of the tokens still for sale, whatever its
+    /// SOL size, so one transaction can't corner a flat curve.
+    pub fn check_tokens_per_buy(&self, token_amount: u64, real_token_reserves: u64) -> Result<()> {
+        if self.max_tokens_per_buy_bps == 0 {
+            return Ok(());
+        }
+        let cap = crate::utils::safe_mul_div(
+            real_token_reserves,
+            self.max_tokens_per_buy_bps as u64,
+            10_000,
+        )?;
+        require!(
+            token_amount <= cap,
+            crate::error::PumpCloneError::MaxPurchaseAmountExceeded
+        );
+        Ok(())
+    }
+
+    pub fn check_not_paused(&self) -> Result<()> {
+        require!(!self.is_paused, crate::error::PumpCloneError::ProgramPaused);
+        Ok(())
+    }
+
+    pub fn check_creates_allowed(&self) -> Result<()> {
+        self.check_not_paused()?;
+        self.check_instruction_enabled(Self::PAUSE_CREATE)
+    }
+
+    pub fn check_buys_allowed(&self) -> Result<()> {
+        self.check_not_paused()?;
+        require!(!self.sells_only, crate::error::PumpCloneError::SellsOnlyMode);
+        self.check_instruction_enabled(Self::PAUSE_BUY)
+    }
+
+    pub fn check_sells_allowed(&self) -> Result<()> {
+        self.check_not_paused()?;
+        self.check_instruction_enabled(Self::PAUSE_SELL)
+    }
+
+    pub fn check_migrations_allowed(&self) -> Result<()> {
+        self.check_not_paused()?;
+        self.check_instruction_enabled(Self::PAUSE_MIGRATE)
+    }
+
+    /// Whether per-wallet cooldowns and daily limits apply to a trader.
+    /// Registered market makers are exempt, but only while active.
+    pub fn wallet_throttle_applies(&self, market_maker: Option<&MarketMaker>) -> Result<bool> {
+        if let Some(market_maker) = market_maker {
+            require!(market_maker.is_active, crate::error::PumpCloneError::MarketMakerNotActive);
+            return Ok(false);
+        }
+        Ok(self.wallet_trade_cooldown_secs > 0 || self.wallet_daily_sol_limit > 0)
+    }
+}
+
+/// Platform fee charged on trades of at least `min_trade_size` lamports. An
+/// all-zero entry is an unused slot.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct FeeTier {
+    pub min_trade_size: u64,
+    pub fee_bps: u16,
+}
+
+impl FeeTier {
+    pub const LEN: usize = 8 + // min_trade_size
+        2; // fee_bps
+
+    pub fn is_empty(&self) -> bool {
+        *self == FeeTier::default()
+    }
+}
+
+/// Fee tiers and thresholds a deployment needs before its first launch.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct BootstrapConfig {
+    pub fee_recipient: Pubkey,
+    pub platform_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub migration_threshold: u64,
+    pub min_sol_threshold: u64,
+    pub max_sol_threshold: u64,
 }
 
 #[account]
+#[derive(Default)]
 pub struct TokenLaunch {
     pub creator: Pubkey,
     pub mint: Pubkey,
@@ -50,6 +383,26 @@ pub struct TokenLaunch {
     pub raydium_pool: Option<Pubkey>,
     pub total_supply: u64,
     pub decimals: u8,
+    pub trading_window_start: u32,
+    pub trading_window_end: u32,
+    pub metadata_locked: bool,
+    /// Share of the creator fee, in bps, routed to `community_wallet`.
+    pub community_fee_bps: u16,
+    pub community_wallet: Pubkey,
+    /// Creator-set tax on sells, in bps of the gross SOL out
+    pub sell_tax_bps: u16,
+    /// Share of the sell tax, in bps, routed to the price-floor vault
+    pub sell_tax_floor_share_bps: u16,
+    /// Spot price below which the floor vault buys back and burns tokens
+    pub floor_price: u64,
+    /// Where claimed creator fees are paid; the default pubkey means `creator`
+    pub creator_fee_destination: Pubkey,
+    /// Sell-side creator fees held in the SOL vault until claimed
+    pub accrued_creator_fees: u64,
+    /// Fees accrued before a destination change that stay owed to the
+    /// previous destination
+    pub prior_accrued_creator_fees: u64,
+    pub prior_fee_destination: Pubkey,
     pub bump: u8,
 }
 
@@ -73,13 +426,182 @@ impl TokenLaunch {
         1 + 32 + // raydium_pool (Option<Pubkey>)
         8 + // total_supply
         1 + // decimals
+        4 + // trading_window_start
+        4 + // trading_window_end
+        1 + // metadata_locked
+        2 + // community_fee_bps
+        32 + // community_wallet
+        2 + // sell_tax_bps
+        2 + // sell_tax_floor_share_bps
+        8 + // floor_price
+        32 + // creator_fee_destination
+        8 + // accrued_creator_fees
+        8 + // prior_accrued_creator_fees
+        32 + // prior_fee_destination
         1; // bump
+
+    pub const MAX_URI_LEN: usize = 200;
+    pub const MAX_WEBSITE_LEN: usize = 100;
+    pub const MAX_SOCIAL_LEN: usize = 50;
+
+    pub fn fee_destination(&self) -> Pubkey {
+        if self.creator_fee_destination == Pubkey::default() {
+            self.creator
+        } else {
+            self.creator_fee_destination
+        }
+    }
+
+    /// Links the launch to the AMM pool its liquidity moved to.
+    pub fn record_migration(&mut self, raydium_pool: Pubkey, timestamp: i64) -> Result<()> {
+        require!(!self.is_migrated, crate::error::PumpCloneError::LiquidityAlreadyMigrated);
+        self.is_migrated = true;
+        self.migration_timestamp = Some(timestamp);
+        self.raydium_pool = Some(raydium_pool);
+        Ok(())
+    }
+
+    /// Overwrites each field `update` sets, leaving the rest alone. Nothing
+    /// can change once the metadata is locked. Every length is checked before
+    /// anything is written: links over their limit fail with
+    /// `InvalidMetadataUri`, social handles with `TokenDescriptionTooLong`.
+    pub fn apply_metadata_update(&mut self, update: MetadataUpdate) -> Result<()> {
+        require!(!self.metadata_locked, crate::error::PumpCloneError::MetadataLocked);
+        for (value, max_len) in [
+            (&update.uri, Self::MAX_URI_LEN),
+            (&update.image_uri, Self::MAX_URI_LEN),
+            (&update.website, Self::MAX_WEBSITE_LEN),
+        ] {
+            require!(
+                value.as_ref().map_or(0, String::len) <= max_len,
+                crate::error::PumpCloneError::InvalidMetadataUri
+            );
+        }
+        for value in [&update.twitter, &update.telegram, &update.discord] {
+            require!(
+                value.as_ref().map_or(0, String::len) <= Self::MAX_SOCIAL_LEN,
+                crate::error::PumpCloneError::TokenDescriptionTooLong
+            );
+        }
+
+        for (field, value) in [
+            (&mut self.metadata_uri, update.uri),
+            (&mut self.image_uri, update.image_uri),
+            (&mut self.website, update.website),
+            (&mut self.twitter, update.twitter),
+            (&mut self.telegram, update.telegram),
+            (&mut self.discord, update.discord),
+        ] {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the sell tax and price floor. Before the first trade the creator
+    /// may pick anything; after it, neither the tax nor the floor can go up,
+    /// so holders who bought under one setting are never taxed harder or
+    /// bought back at a floor they didn't see.
+    pub fn set_sell_tax(
+        &mut self,
+        sell_tax_bps: u16,
+        floor_share_bps: u16,
+        floor_price: u64,
+        trading_started: bool,
+    ) -> Result<()> {
+        require!(
+            !trading_started || (sell_tax_bps <= self.sell_tax_bps && floor_price <= self.floor_price),
+            crate::error::PumpCloneError::SellTaxLocked
+        );
+        self.sell_tax_bps = sell_tax_bps;
+        self.sell_tax_floor_share_bps = floor_share_bps;
+        self.floor_price = floor_price;
+        Ok(())
+    }
+
+    pub fn accrue_creator_fee(&mut self, amount: u64) -> Result<()> {
+        self.accrued_creator_fees = self.accrued_creator_fees
+            .checked_add(amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Points creator fees at `destination`. Fees already accrued follow it
+    /// when `accrued_follow` is set; otherwise they stay owed to the current
+    /// destination, which must not already hold fees from an earlier change.
+    pub fn change_fee_destination(&mut self, destination: Pubkey, accrued_follow: bool) -> Result<()> {
+        let previous = self.fee_destination();
+        self.creator_fee_destination = destination;
+        if accrued_follow || self.accrued_creator_fees == 0 || self.fee_destination() == previous {
+            return Ok(());
+        }
+
+        require!(
+            self.prior_accrued_creator_fees == 0 || self.prior_fee_destination == previous,
+            crate::error::PumpCloneError::UnclaimedCreatorFees
+        );
+        self.prior_fee_destination = previous;
+        self.prior_accrued_creator_fees = self.prior_accrued_creator_fees
+            .checked_add(self.accrued_creator_fees)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.accrued_creator_fees = 0;
+        Ok(())
+    }
+
+    /// Takes the fees owed to the current destination.
+    pub fn take_accrued_creator_fees(&mut self) -> u64 {
+        std::mem::take(&mut self.accrued_creator_fees)
+    }
+
+    /// Takes the fees still owed to the previous destination.
+    pub fn take_prior_creator_fees(&mut self) -> u64 {
+        std::mem::take(&mut self.prior_accrued_creator_fees)
+    }
+
+    /// Curve accounts can only be closed once liquidity has migrated, only
+    /// by the creator or the platform authority, and only after every accrued
+    /// creator fee has been claimed; closing drops the record of what is owed.
+    pub fn check_curve_closable(&self, closer: &Pubkey, platform_authority: &Pubkey) -> Result<()> {
+        require!(self.is_migrated, crate::error::PumpCloneError::CurveNotMigrated);
+        require!(
+            *closer == self.creator || closer == platform_authority,
+            crate::error::PumpCloneError::Unauthorized
+        );
+        require!(
+            self.accrued_creator_fees == 0 && self.prior_accrued_creator_fees == 0,
+            crate::error::PumpCloneError::UnclaimedCreatorFees
+        );
+        Ok(())
+    }
+
+    /// Rejects trades outside the creator's daily UTC window. Equal start
+    /// and end seconds mean the schedule is disabled and trading is 24/7.
+    pub fn check_trading_hours(&self, unix_timestamp: i64) -> Result<()> {
+        let second_of_day = crate::utils::utc_second_of_day(unix_timestamp);
+
+        if crate::utils::is_within_trading_window(
+            self.trading_window_start,
+            self.trading_window_end,
+            second_of_day,
+        ) {
+            return Ok(());
+        }
+
+        if second_of_day < self.trading_window_start {
+            return Err(crate::error::PumpCloneError::OperationTooEarly.into());
+        }
+
+        Err(crate::error::PumpCloneError::OperationExpired.into())
+    }
 }
 
 #[account]
+#[derive(Default)]
 pub struct BondingCurve {
     pub token_launch: Pubkey,
     pub mint: Pubkey,
+    pub creator: Pubkey,
     pub sol_reserves: u64,
     pub token_reserves: u64,
     pub virtual_sol_reserves: u64,
@@ -87,6 +609,8 @@ pub struct BondingCurve {
     pub real_sol_reserves: u64,
     pub real_token_reserves: u64,
     pub complete: bool,
+    /// Set once liquidity has moved to the AMM; never cleared
+    pub migrated: bool,
     pub total_supply: u64,
     pub initial_virtual_sol_reserves: u64,
     pub initial_virtual_token_reserves: u64,
@@ -98,6 +622,62 @@ pub struct BondingCurve {
     pub holders_count: u32,
     pub created_at: i64,
     pub updated_at: i64,
+    pub graduated_slot: u64,
+    pub sell_vesting_threshold: u64,
+    pub sell_vesting_duration: i64,
+    pub trade_slot: u64,
+    pub trades_in_slot: u16,
+    pub total_volume: u64,
+    pub fee_decay_start_bps: u16,
+    pub fee_decay_floor_bps: u16,
+    pub fee_decay_volume: u64,
+    pub min_buys_before_sell: u32,
+    pub total_trades: u64,
+    pub trade_milestones_paid: u64,
+    pub price_cumulative: u128,
+    pub price_cumulative_timestamp: i64,
+    pub twap_anchor_cumulative: u128,
+    pub twap_anchor_timestamp: i64,
+    pub twap_pending_cumulative: u128,
+    pub twap_pending_timestamp: i64,
+    pub max_holders_before_graduation: u32,
+    pub reserve_drift_tolerance_bps: u16,
+    pub max_sold_before_graduation_bps: u16,
+    /// Highest spot price seen after any trade, scaled like `last_price`
+    pub ath_price: u64,
+    /// Stalled or aborted curve: trading is closed and holders redeem their
+    /// tokens for a pro-rata share of the real SOL through `claim_refund`
+    pub refund_mode: bool,
+    /// Seconds after creation during which `max_buy_per_wallet` applies
+    pub anti_snipe_duration: i64,
+    /// Tokens one wallet may buy during the anti-snipe window; zero disables it
+    pub max_buy_per_wallet: u64,
+    /// Fees charged on this curve's trades, fixed at launch
+    pub creator_fee_bps: u16,
+    pub platform_fee_bps: u16,
+    /// Rejects `update_token_metadata` once the first buy has landed
+    pub lock_metadata_on_first_buy: bool,
+    /// Until then only wallets the creator whitelisted may buy; zero when
+    /// the launch has no whitelist phase
+    pub whitelist_end_ts: i64,
+    /// Snapshot taken when the curve completes
+    pub completed_at: i64,
+    pub completion_holders_count: u32,
+    pub time_to_completion: i64,
+    /// Slot of the latest sell and the price before that slot's first sell,
+    /// which the sell circuit breaker measures drops from
+    pub last_price_slot: u64,
+    pub slot_open_price: u64,
+    /// Start of the window `volume_24h` and `trades_24h` count over
+    pub volume_window_start: i64,
+    /// Tokens the curve bought back and burned through `floor_buyback`;
+    /// sold by the curve but no longer held by anyone
+    pub tokens_burned: u64,
+    /// Tokens holders returned through `claim_refund`
+    pub tokens_refunded: u64,
+    /// When the first buy settled; zero until then and never reset, so
+    /// selling back down to empty reserves doesn't undo it
+    pub first_buy_at: i64,
     pub bump: u8,
 }
 
@@ -105,6 +685,7 @@ impl BondingCurve {
     pub const LEN: usize = 8 + // discriminator
         32 + // token_launch
         32 + // mint
+        32 + // creator
         8 + // sol_reserves
         8 + // token_reserves
         8 + // virtual_sol_reserves
@@ -112,6 +693,7 @@ impl BondingCurve {
         8 + // real_sol_reserves
         8 + // real_token_reserves
         1 + // complete
+        1 + // migrated
         8 + // total_supply
         8 + // initial_virtual_sol_reserves
         8 + // initial_virtual_token_reserves
@@ -123,8 +705,589 @@ impl BondingCurve {
         4 + // holders_count
         8 + // created_at
         8 + // updated_at
+        8 + // graduated_slot
+        8 + // sell_vesting_threshold
+        8 + // sell_vesting_duration
+        8 + // trade_slot
+        2 + // trades_in_slot
+        8 + // total_volume
+        2 + // fee_decay_start_bps
+        2 + // fee_decay_floor_bps
+        8 + // fee_decay_volume
+        4 + // min_buys_before_sell
+        8 + // total_trades
+        8 + // trade_milestones_paid
+        16 + // price_cumulative
+        8 + // price_cumulative_timestamp
+        16 + // twap_anchor_cumulative
+        8 + // twap_anchor_timestamp
+        16 + // twap_pending_cumulative
+        8 + // twap_pending_timestamp
+        4 + // max_holders_before_graduation
+        2 + // reserve_drift_tolerance_bps
+        2 + // max_sold_before_graduation_bps
+        8 + // ath_price
+        1 + // refund_mode
+        8 + // anti_snipe_duration
+        8 + // max_buy_per_wallet
+        2 + // creator_fee_bps
+        2 + // platform_fee_bps
+        1 + // lock_metadata_on_first_buy
+        8 + // whitelist_end_ts
+        8 + // completed_at
+        4 + // completion_holders_count
+        8 + // time_to_completion
+        8 + // last_price_slot
+        8 + // slot_open_price
+        8 + // volume_window_start
+        8 + // tokens_burned
+        8 + // tokens_refunded
+        8 + // first_buy_at
         1; // bump
 
+    /// Span `volume_24h` and `trades_24h` cover
+    pub const VOLUME_WINDOW_SECS: i64 = 86_400;
+
+    /// Recomputes `k_constant` and `last_price` from the current virtual
+    /// reserves, discarding values written under older scaling rules.
+    pub fn normalize_pricing(&mut self) -> Result<()> {
+        self.k_constant = crate::utils::canonical_k_constant(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?;
+        self.last_price = crate::utils::canonical_price(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?;
+        Ok(())
+    }
+
+    /// The price both buys and sells measure slippage against: the TWAP, so
+    /// a price pushed just before the trade still trips the tolerance, or
+    /// the spot price on a curve that hasn't observed any time yet (such as
+    /// its first trade). Call after `update_twap`.
+    pub fn slippage_reference_price(&self, now: i64) -> Result<u64> {
+        match self.twap_price(now)? {
+            Some(twap) => Ok(twap),
+            None => crate::utils::canonical_price(
+                self.virtual_sol_reserves,
+                self.virtual_token_reserves,
+            ),
+        }
+    }
+
+    /// Real reserves at `slot`, hashed together with the curve address so the
+    /// snapshot can't be replayed for another curve or slot.
+    pub fn attest_reserves(&self, bonding_curve: Pubkey, slot: u64) -> ReserveAttestation {
+        let digest = anchor_lang::solana_program::hash::hashv(&[
+            bonding_curve.as_ref(),
+            &self.real_sol_reserves.to_le_bytes(),
+            &self.real_token_reserves.to_le_bytes(),
+            &slot.to_le_bytes(),
+        ]);
+        ReserveAttestation {
+            bonding_curve,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            slot,
+            digest: digest.to_bytes(),
+        }
+    }
+
+    /// Claims the curve for migration. The flag flips before any funds move,
+    /// so a second migrate of the same curve fails instead of seeding another
+    /// pool.
+    pub fn begin_migration(&mut self, migration_threshold: u64) -> Result<()> {
+        self.check_migration_ready(migration_threshold)?;
+        self.migrated = true;
+        Ok(())
+    }
+
+    /// What `begin_migration` requires of the curve, without claiming it.
+    /// `graduate` only stamps `graduated_slot` once the global cooldown has
+    /// elapsed, so a curve it deferred can't migrate either.
+    pub fn check_migration_ready(&self, migration_threshold: u64) -> Result<()> {
+        require!(!self.migrated, crate::error::PumpCloneError::AlreadyMigrated);
+        require!(self.complete, crate::error::PumpCloneError::BondingCurveNotCompleted);
+        require!(
+            self.real_sol_reserves >= migration_threshold,
+            crate::error::PumpCloneError::LiquidityMigrationNotReady
+        );
+        require!(self.graduated_slot != 0, crate::error::PumpCloneError::NotGraduated);
+        Ok(())
+    }
+
+    /// A launch can only be cancelled before any SOL has entered the curve.
+    pub fn check_cancellable(&self) -> Result<()> {
+        require!(!self.complete, crate::error::PumpCloneError::BondingCurveCompleted);
+        require!(self.real_sol_reserves == 0, crate::error::PumpCloneError::TradingAlreadyActive);
+        Ok(())
+    }
+
+    /// Metadata stays editable until the first buy settles, or for good when
+    /// the launch didn't opt into the first-buy lock.
+    pub fn check_metadata_updatable(&self) -> Result<()> {
+        require!(
+            !self.lock_metadata_on_first_buy || self.first_buy_at == 0,
+            crate::error::PumpCloneError::MetadataLocked
+        );
+        Ok(())
+    }
+
+    /// Closes the curve to trading, snapshots its final stats and returns
+    /// the event to emit. `total_volume` stops growing once trading ends,
+    /// so it needs no copy of its own.
+    pub fn mark_complete(&mut self, now: i64) -> crate::events::CurveCompleted {
+        self.complete = true;
+        self.completed_at = now;
+        self.completion_holders_count = self.holders_count;
+        self.time_to_completion = now.saturating_sub(self.created_at);
+
+        crate::events::CurveCompleted {
+            mint: self.mint,
+            virtual_sol_reserves: self.virtual_sol_reserves,
+            virtual_token_reserves: self.virtual_token_reserves,
+            real_sol_reserves: self.real_sol_reserves,
+            real_token_reserves: self.real_token_reserves,
+            total_volume: self.total_volume,
+            holders_count: self.holders_count,
+            time_to_completion: self.time_to_completion,
+            timestamp: now,
+        }
+    }
+
+    /// During the whitelist phase only the creator and whitelisted wallets
+    /// can buy.
+    pub fn check_whitelist_phase(&self, now: i64, buyer: &Pubkey, is_whitelisted: bool) -> Result<()> {
+        require!(
+            now >= self.whitelist_end_ts || *buyer == self.creator || is_whitelisted,
+            crate::error::PumpCloneError::UserNotWhitelisted
+        );
+        Ok(())
+    }
+
+    /// Lamport value of the tokens users hold, at the spot price. Tokens still
+    /// in the curve's real reserves are not circulating.
+    pub fn market_cap(&self) -> Result<u64> {
+        let circulating_supply = self.total_supply.saturating_sub(self.real_token_reserves);
+        crate::utils::spot_value(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+            circulating_supply,
+        )
+    }
+
+    /// Raises `ath_price` when `price` sets a new high.
+    pub fn record_price_high(&mut self, price: u64) {
+        self.ath_price = self.ath_price.max(price);
+    }
+
+    /// Counts a trade towards `volume_24h` and `trades_24h`. Once the window
+    /// is a day old the counters restart from this trade, so stale volume
+    /// drops off without a crank.
+    pub fn record_rolling_volume(&mut self, now: i64, sol_amount: u64) -> Result<()> {
+        if now.saturating_sub(self.volume_window_start) >= Self::VOLUME_WINDOW_SECS {
+            self.volume_window_start = now;
+            self.volume_24h = 0;
+            self.trades_24h = 0;
+        }
+        self.volume_24h = self
+            .volume_24h
+            .checked_add(sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.trades_24h = self.trades_24h.saturating_add(1);
+        Ok(())
+    }
+
+    /// Books a priced buy against the curve: moves both reserve pairs, checks
+    /// they still agree, records the new price high and volume, and completes
+    /// the curve once a graduation target is hit. Returns the completion
+    /// event when this buy completed it.
+    pub fn settle_buy(
+        &mut self,
+        net_sol_amount: u64,
+        token_amount: u64,
+        sol_amount: u64,
+        funding_goal: u64,
+        migration_threshold: u64,
+        now: i64,
+    ) -> Result<Option<crate::events::CurveCompleted>> {
+        self.virtual_sol_reserves = self
+            .virtual_sol_reserves
+            .checked_add(net_sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.virtual_token_reserves = self
+            .virtual_token_reserves
+            .checked_sub(token_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.real_sol_reserves = self
+            .real_sol_reserves
+            .checked_add(net_sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.real_token_reserves = self
+            .real_token_reserves
+            .checked_sub(token_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        if self.first_buy_at == 0 {
+            self.first_buy_at = now;
+        }
+        self.check_reserve_ratio()?;
+        self.record_price_high(crate::utils::canonical_price(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?);
+
+        // Counted before the completion check so the snapshot includes the
+        // completing buy
+        self.total_volume = self
+            .total_volume
+            .checked_add(sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.record_rolling_volume(now, sol_amount)?;
+
+        // Distribution targets only complete a curve that can also migrate;
+        // completing below `migration_threshold` would leave `begin_migration`
+        // failing forever with the SOL stuck in the vault
+        let distribution_target = self.reached_holder_target() || self.reached_sold_target();
+        if self.real_sol_reserves >= funding_goal
+            || (distribution_target && self.real_sol_reserves >= migration_threshold)
+        {
+            return Ok(Some(self.mark_complete(now)));
+        }
+        Ok(None)
+    }
+
+    /// Records a sell's resulting price and rejects it when the price has
+    /// fallen more than `max_drop_bps` since the slot's first sell. Several
+    /// small sells in one slot add up.
+    pub fn check_circuit_breaker(
+        &mut self,
+        slot: u64,
+        price_before: u64,
+        price_after: u64,
+        max_drop_bps: u16,
+    ) -> Result<()> {
+        if slot != self.last_price_slot || self.slot_open_price == 0 {
+            self.last_price_slot = slot;
+            self.slot_open_price = price_before;
+        }
+        if max_drop_bps > 0 && price_after < self.slot_open_price {
+            let drop_bps = (self.slot_open_price - price_after) as u128 * 10_000
+                / self.slot_open_price as u128;
+            require!(
+                drop_bps <= max_drop_bps as u128,
+                crate::error::PumpCloneError::CircuitBreakerTriggered
+            );
+        }
+        self.last_price = price_after;
+        Ok(())
+    }
+
+    /// How far `price` sits below the all-time high, in bps; zero at or above it.
+    pub fn drawdown_from_ath_bps(&self, price: u64) -> u64 {
+        if self.ath_price == 0 || price >= self.ath_price {
+            return 0;
+        }
+        ((self.ath_price - price) as u128 * 10_000 / self.ath_price as u128) as u64
+    }
+
+    /// Counts a trade against the per-slot cap, resetting the counter when a
+    /// new slot begins. A cap of zero means unlimited. Accepted trades also
+    /// advance the cumulative `total_trades` counter.
+    pub fn record_trade_in_slot(&mut self, slot: u64, max_trades_per_slot: u16) -> Result<()> {
+        if slot != self.trade_slot {
+            self.trade_slot = slot;
+            self.trades_in_slot = 0;
+        }
+
+        require!(
+            max_trades_per_slot == 0 || self.trades_in_slot < max_trades_per_slot,
+            crate::error::PumpCloneError::RateLimitExceeded
+        );
+
+        self.trades_in_slot = self.trades_in_slot.saturating_add(1);
+        self.total_trades = self.total_trades.saturating_add(1);
+        Ok(())
+    }
+
+    /// Folds the price since the last update into the cumulative price and
+    /// rolls the TWAP checkpoints. Call before a trade moves the reserves.
+    /// The anchor checkpoint trails by one to two `TWAP_WINDOW_SECONDS`, so
+    /// the TWAP always spans at least a full window once history exists.
+    pub fn update_twap(&mut self, now: i64) -> Result<()> {
+        let price = crate::utils::canonical_price(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?;
+
+        if self.price_cumulative_timestamp == 0 {
+            self.price_cumulative_timestamp = now;
+            self.twap_anchor_timestamp = now;
+            self.twap_pending_timestamp = now;
+            return Ok(());
+        }
+
+        self.price_cumulative = crate::utils::accumulate_price(
+            self.price_cumulative,
+            price,
+            self.price_cumulative_timestamp,
+            now,
+        )?;
+        self.price_cumulative_timestamp = now;
+
+        if now - self.twap_pending_timestamp >= crate::utils::TWAP_WINDOW_SECONDS {
+            self.twap_anchor_cumulative = self.twap_pending_cumulative;
+            self.twap_anchor_timestamp = self.twap_pending_timestamp;
+            self.twap_pending_cumulative = self.price_cumulative;
+            self.twap_pending_timestamp = now;
+        }
+
+        Ok(())
+    }
+
+    /// Time-weighted average price from the anchor checkpoint to `now`.
+    /// `None` until any time has been observed.
+    pub fn twap_price(&self, now: i64) -> Result<Option<u64>> {
+        let price = crate::utils::canonical_price(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?;
+        let cumulative = crate::utils::accumulate_price(
+            self.price_cumulative,
+            price,
+            self.price_cumulative_timestamp,
+            now,
+        )?;
+
+        Ok(crate::utils::average_price(
+            cumulative,
+            self.twap_anchor_cumulative,
+            now,
+            self.twap_anchor_timestamp,
+        ))
+    }
+
+    /// Marks the latest trade-count milestone as paid, returning whether a
+    /// new one was reached since the last payout. Milestones skipped in
+    /// between are paid once, not per milestone.
+    pub fn claim_trade_milestone(&mut self, interval: u64) -> bool {
+        if interval == 0 {
+            return false;
+        }
+
+        let reached = self.total_trades / interval;
+        if reached <= self.trade_milestones_paid {
+            return false;
+        }
+
+        self.trade_milestones_paid = reached;
+        true
+    }
+
+    /// Platform fee for the next trade. Curves launched with a decaying fee
+    /// start at `fee_decay_start_bps` and slide linearly to the floor as
+    /// cumulative volume approaches `fee_decay_volume`.
+    pub fn trading_fee_bps(&self, default_bps: u16) -> u16 {
+        if self.fee_decay_start_bps == 0 {
+            return default_bps;
+        }
+
+        crate::utils::decayed_fee_bps(
+            self.fee_decay_start_bps,
+            self.fee_decay_floor_bps,
+            self.fee_decay_volume,
+            self.total_volume,
+        )
+    }
+
+    /// Whether the curve has reached its holder target. Completes the curve
+    /// only once it also holds the migration threshold. Disabled when
+    /// `max_holders_before_graduation` is zero.
+    pub fn reached_holder_target(&self) -> bool {
+        self.max_holders_before_graduation > 0
+            && self.holders_count >= self.max_holders_before_graduation
+    }
+
+    /// Caps what a wallet can accumulate while the anti-snipe window is open.
+    /// `tokens_bought` is `None` when no position was supplied, which the
+    /// window rejects since the cap can't be tracked without it.
+    pub fn check_anti_snipe_cap(&self, now: i64, tokens_bought: Option<u64>, token_amount: u64) -> Result<()> {
+        if self.anti_snipe_duration <= 0
+            || self.max_buy_per_wallet == 0
+            || now >= self.created_at.saturating_add(self.anti_snipe_duration)
+        {
+            return Ok(());
+        }
+
+        let total = tokens_bought
+            .ok_or(crate::error::PumpCloneError::AccountNotInitialized)?
+            .checked_add(token_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        require!(
+            total <= self.max_buy_per_wallet,
+            crate::error::PumpCloneError::MaxPurchaseAmountExceeded
+        );
+        Ok(())
+    }
+
+    /// Normal trading is closed in refund mode; the error points sellers at
+    /// `claim_refund`.
+    pub fn check_not_in_refund_mode(&self) -> Result<()> {
+        require!(!self.refund_mode, crate::error::PumpCloneError::RefundModeActive);
+        Ok(())
+    }
+
+    /// Tokens sold by the curve that holders still hold: neither burned by
+    /// `floor_buyback` nor already refunded.
+    pub fn outstanding_tokens(&self) -> u64 {
+        self.initial_real_token_reserves
+            .saturating_sub(self.real_token_reserves)
+            .saturating_sub(self.tokens_burned)
+            .saturating_sub(self.tokens_refunded)
+    }
+
+    /// SOL owed for returning `token_amount` in refund mode: the same share
+    /// of the real SOL reserves as of the outstanding tokens.
+    pub fn refund_amount(&self, token_amount: u64) -> Result<u64> {
+        require!(self.refund_mode, crate::error::PumpCloneError::RefundModeNotActive);
+        let outstanding = self.outstanding_tokens();
+        require!(token_amount > 0, crate::error::PumpCloneError::SaleAmountTooSmall);
+        require!(token_amount <= outstanding, crate::error::PumpCloneError::MaxSaleAmountExceeded);
+        crate::utils::safe_mul_div(self.real_sol_reserves, token_amount, outstanding)
+    }
+
+    /// Books a refund: the SOL leaves the curve and the returned tokens stop
+    /// counting as outstanding, so later refunds keep the same rate. They
+    /// were burned, so they never return to the curve's unsold reserves.
+    pub fn settle_refund(&mut self, token_amount: u64, sol_amount: u64) -> Result<()> {
+        self.real_sol_reserves = self.real_sol_reserves
+            .checked_sub(sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.tokens_refunded = self.tokens_refunded
+            .checked_add(token_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Whether enough of the tradeable float has been sold. Completes the
+    /// curve only once it also holds the migration threshold. Disabled when
+    /// `max_sold_before_graduation_bps` is zero.
+    pub fn reached_sold_target(&self) -> bool {
+        if self.max_sold_before_graduation_bps == 0 || self.initial_real_token_reserves == 0 {
+            return false;
+        }
+        let sold = self.initial_real_token_reserves.saturating_sub(self.real_token_reserves);
+        (sold as u128) * 10_000
+            >= (self.initial_real_token_reserves as u128) * self.max_sold_before_graduation_bps as u128
+    }
+
+    /// Wallets must have bought at least `min_buys_before_sell` times before
+    /// they can sell. `buy_count` is `None` when no position was supplied.
+    pub fn check_min_buys_before_sell(&self, buy_count: Option<u32>) -> Result<()> {
+        if self.min_buys_before_sell == 0 {
+            return Ok(());
+        }
+
+        require!(
+            buy_count.unwrap_or(0) >= self.min_buys_before_sell,
+            crate::error::PumpCloneError::InsufficientBuysBeforeSell
+        );
+        Ok(())
+    }
+
+    pub fn add_liquidity(&mut self, sol_amount: u64) -> Result<()> {
+        let (virtual_sol_reserves, virtual_token_reserves) =
+            crate::utils::scale_reserves_for_liquidity(
+                self.virtual_sol_reserves,
+                self.virtual_token_reserves,
+                sol_amount,
+            )?;
+
+        self.virtual_sol_reserves = virtual_sol_reserves;
+        self.virtual_token_reserves = virtual_token_reserves;
+        self.real_sol_reserves = self.real_sol_reserves
+            .checked_add(sol_amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+
+        // Scaling widens both real/virtual gaps on purpose; make the new gaps
+        // the baseline `check_reserve_ratio` measures drift against
+        if self.initial_virtual_sol_reserves != 0 {
+            self.initial_virtual_sol_reserves = self.virtual_sol_reserves
+                .checked_sub(self.real_sol_reserves)
+                .ok_or(crate::error::PumpCloneError::ReserveCalculationOverflow)?;
+            self.initial_virtual_token_reserves = self.virtual_token_reserves
+                .checked_sub(self.real_token_reserves)
+                .and_then(|gap| gap.checked_add(self.initial_real_token_reserves))
+                .ok_or(crate::error::PumpCloneError::ReserveCalculationOverflow)?;
+        }
+
+        self.normalize_pricing()
+    }
+
+    pub fn verify_invariants(&self) -> Result<()> {
+        require!(
+            self.virtual_token_reserves > 0 && self.virtual_sol_reserves > 0,
+            crate::error::PumpCloneError::InvalidBondingCurveParams
+        );
+
+        let expected_k = crate::utils::canonical_k_constant(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?;
+        require!(
+            self.k_constant == expected_k,
+            crate::error::PumpCloneError::InvalidBondingCurveParams
+        );
+
+        let expected_price = crate::utils::canonical_price(
+            self.virtual_sol_reserves,
+            self.virtual_token_reserves,
+        )?;
+        require!(
+            self.last_price == expected_price,
+            crate::error::PumpCloneError::InvalidBondingCurveParams
+        );
+
+        Ok(())
+    }
+
+    /// Buys and sells move real and virtual reserves by the same amounts, so
+    /// the gap between them must stay at its launch value, re-baselined by
+    /// `add_liquidity`. Drift beyond
+    /// `reserve_drift_tolerance_bps` of that gap means some code path updated
+    /// one side without the other. Curves launched before the initial
+    /// reserves were recorded only get the real <= virtual bounds.
+    pub fn check_reserve_ratio(&self) -> Result<()> {
+        require!(
+            self.real_sol_reserves <= self.virtual_sol_reserves
+                && self.real_token_reserves <= self.virtual_token_reserves,
+            crate::error::PumpCloneError::ReserveCalculationOverflow
+        );
+
+        if self.initial_virtual_sol_reserves == 0 {
+            return Ok(());
+        }
+
+        let sol_gap = self.virtual_sol_reserves - self.real_sol_reserves;
+        let token_gap = self.virtual_token_reserves - self.real_token_reserves;
+        let launch_token_gap = self.initial_virtual_token_reserves
+            .saturating_sub(self.initial_real_token_reserves);
+        require!(
+            crate::utils::within_price_band(
+                sol_gap,
+                self.initial_virtual_sol_reserves,
+                self.reserve_drift_tolerance_bps,
+            ) && crate::utils::within_price_band(
+                token_gap,
+                launch_token_gap,
+                self.reserve_drift_tolerance_bps,
+            ),
+            crate::error::PumpCloneError::ReserveCalculationOverflow
+        );
+
+        Ok(())
+    }
+
     pub fn calculate_buy_price(&self, sol_amount: u64) -> Result<u64> {
         require!(!self.complete, crate::error::ErrorCode::BondingCurveComplete);
         require!(sol_amount > 0, crate::error::ErrorCode::InvalidAmount);
@@ -242,7 +1405,495 @@ impl BondingCurve {
     }
 }
 
+/// Per-token settings chosen by the creator at launch. Zero values leave the
+/// corresponding feature disabled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct LaunchConfig {
+    pub sell_vesting_threshold: u64,
+    pub sell_vesting_duration: i64,
+    /// Platform fee at launch; zero keeps the global default.
+    pub fee_decay_start_bps: u16,
+    pub fee_decay_floor_bps: u16,
+    /// Cumulative SOL volume at which the fee reaches the floor.
+    pub fee_decay_volume: u64,
+    pub min_buys_before_sell: u32,
+    /// Starting virtual SOL reserves; zero keeps the platform default.
+    pub initial_virtual_sol_reserves: u64,
+    /// Holder count that completes the curve early; zero disables it.
+    pub max_holders_before_graduation: u32,
+    /// Allowed drift of the real/virtual reserve gap, in bps of its launch value.
+    pub reserve_drift_tolerance_bps: u16,
+    /// Share of the tradeable float, in bps, whose sale completes the curve
+    /// early; zero disables it.
+    pub max_sold_before_graduation_bps: u16,
+    /// Seconds after launch during which each wallet's buys are capped at
+    /// `max_buy_per_wallet` tokens; zero disables the window.
+    pub anti_snipe_duration: i64,
+    pub max_buy_per_wallet: u64,
+    /// Token supply, capped at `MAX_TOKEN_SUPPLY`; zero keeps the maximum.
+    pub total_supply: u64,
+    /// Trading fees, capped at `GlobalState::max_fee_bps`; `None` keeps the
+    /// platform default.
+    pub creator_fee_bps: Option<u16>,
+    pub platform_fee_bps: Option<u16>,
+    /// Freeze metadata once the first buy lands
+    pub lock_metadata_on_first_buy: bool,
+    /// The creator's `initial_buy` tokens unlock linearly over
+    /// `creator_vesting_duration` seconds from launch, none before the cliff
+    pub creator_vesting_cliff: i64,
+    pub creator_vesting_duration: i64,
+    /// Seconds after launch during which only whitelisted wallets can buy
+    pub whitelist_duration: i64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct SellVestingEscrow {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub bump: u8,
+}
+
+impl SellVestingEscrow {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // seller
+        32 + // mint
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // start_timestamp
+        8 + // end_timestamp
+        1; // bump
+
+    pub fn claimable_amount(&self, now: i64) -> u64 {
+        crate::utils::calculate_vested_amount(
+            self.total_amount,
+            self.start_timestamp,
+            self.end_timestamp,
+            now,
+        )
+        .saturating_sub(self.claimed_amount)
+    }
+}
+
+/// Fee-funded pool paid out to holders in rounds. Each round snapshots the
+/// balance-seconds every position checkpointed since the previous round
+/// (paginated), then holders claim their pro-rata share once the authority
+/// finalizes the round.
+#[account]
+pub struct HolderRewards {
+    pub mint: Pubkey,
+    pub pending_rewards: u64,
+    pub round: u64,
+    pub round_open: bool,
+    pub snapshot_timestamp: i64,
+    pub total_weight: u128,
+    pub round_rewards: u64,
+    pub round_claimed: u64,
+    pub bump: u8,
+}
+
+impl HolderRewards {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        8 + // pending_rewards
+        8 + // round
+        1 + // round_open
+        8 + // snapshot_timestamp
+        16 + // total_weight
+        8 + // round_rewards
+        8 + // round_claimed
+        1; // bump
+
+    pub const MAX_POSITIONS_PER_BATCH: usize = 20;
+}
+
+/// Trading defaults a user sets once instead of on every trade.
 #[account]
+#[derive(Default)]
+pub struct UserSettings {
+    pub user: Pubkey,
+    pub max_slippage_bps: u16,
+    /// Claim holder rewards as tokens bought from the curve instead of SOL
+    pub compound_holder_rewards: bool,
+    pub bump: u8,
+}
+
+impl UserSettings {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // user
+        2 + // max_slippage_bps
+        1 + // compound_holder_rewards
+        1; // bump
+}
+
+/// Tracks how many wallets registered under a user as their referrer, and
+/// the platform-fee cut their referred buys have earned. Earned lamports sit
+/// on this account until claimed.
+#[account]
+#[derive(Default)]
+pub struct Referral {
+    pub owner: Pubkey,
+    pub successful_referrals: u32,
+    /// Shareable handle chosen by the owner; empty until registered
+    pub code: String,
+    pub pending_rewards: u64,
+    pub total_rewards: u64,
+    /// Wallet that referred the owner, fixed at registration; the default
+    /// pubkey when none was named
+    pub referred_by: Pubkey,
+    pub bump: u8,
+}
+
+impl Referral {
+    pub const MAX_CODE_LEN: usize = 16;
+    pub const MIN_CODE_LEN: usize = 3;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // owner
+        4 + // successful_referrals
+        4 + Self::MAX_CODE_LEN + // code
+        8 + // pending_rewards
+        8 + // total_rewards
+        32 + // referred_by
+        1; // bump
+
+    /// Codes are 3-16 ASCII letters or digits.
+    pub fn validate_code(code: &str) -> Result<()> {
+        require!(
+            (Self::MIN_CODE_LEN..=Self::MAX_CODE_LEN).contains(&code.len())
+                && code.bytes().all(|b| b.is_ascii_alphanumeric()),
+            crate::error::PumpCloneError::InvalidReferralCode
+        );
+        Ok(())
+    }
+
+    /// Records `referrer` as the wallet that referred the owner and counts
+    /// the referral on it. Each wallet is referred at most once, and never
+    /// by itself.
+    pub fn record_referrer(&mut self, referrer: &mut Referral) -> Result<()> {
+        require_keys_neq!(referrer.owner, self.owner, crate::error::PumpCloneError::SelfReferralNotAllowed);
+        require_keys_eq!(
+            self.referred_by,
+            Pubkey::default(),
+            crate::error::PumpCloneError::MaximumReferralsExceeded
+        );
+        self.referred_by = referrer.owner;
+        referrer.successful_referrals = referrer
+            .successful_referrals
+            .checked_add(1)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+
+    /// Credits a referred buy's reward. A wallet can't refer itself.
+    pub fn accrue_reward(&mut self, buyer: &Pubkey, amount: u64) -> Result<()> {
+        require_keys_neq!(self.owner, *buyer, crate::error::PumpCloneError::SelfReferralNotAllowed);
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.total_rewards = self
+            .total_rewards
+            .checked_add(amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+}
+
+/// Approved liquidity provider that trades free of per-wallet cooldowns and
+/// daily limits. Fees are charged as for any other wallet.
+#[account]
+#[derive(Default)]
+pub struct MarketMaker {
+    pub wallet: Pubkey,
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl MarketMaker {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        1 + // is_active
+        1; // bump
+}
+
+/// A wallet the creator admitted to a launch's whitelist phase.
+#[account]
+#[derive(Default)]
+pub struct Whitelist {
+    pub mint: Pubkey,
+    pub wallet: Pubkey,
+    pub is_whitelisted: bool,
+    pub bump: u8,
+}
+
+impl Whitelist {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // wallet
+        1 + // is_whitelisted
+        1; // bump
+
+    /// Reads a (mint, wallet) whitelist PDA; an empty account means the
+    /// wallet was never added.
+    pub fn is_whitelisted(whitelist: &AccountInfo) -> Result<bool> {
+        if whitelist.data_is_empty() {
+            return Ok(false);
+        }
+        let entry = Whitelist::try_deserialize(&mut &whitelist.data.borrow()[..])?;
+        Ok(entry.is_whitelisted)
+    }
+}
+
+/// Per-wallet trading ban set by the authority. Wallets that were never
+/// listed have no account at their PDA.
+#[account]
+#[derive(Default)]
+pub struct Blacklist {
+    pub wallet: Pubkey,
+    pub is_blacklisted: bool,
+    pub bump: u8,
+}
+
+impl Blacklist {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // wallet
+        1 + // is_blacklisted
+        1; // bump
+
+    /// Rejects a trade when the wallet's blacklist PDA holds an active entry.
+    pub fn check_not_blacklisted(blacklist: &AccountInfo) -> Result<()> {
+        if blacklist.data_is_empty() {
+            return Ok(());
+        }
+        let entry = Blacklist::try_deserialize(&mut &blacklist.data.borrow()[..])?;
+        require!(!entry.is_blacklisted, crate::error::PumpCloneError::UserBlacklisted);
+        Ok(())
+    }
+}
+
+/// Escrow for the tokens a creator buys at launch. They unlock linearly from
+/// `start_ts` over `duration`, but nothing is claimable before `cliff_ts`.
+#[account]
+#[derive(Default)]
+pub struct CreatorVesting {
+    pub creator: Pubkey,
+    pub mint: Pubkey,
+    pub total_amount: u64,
+    pub start_ts: i64,
+    pub cliff_ts: i64,
+    pub duration: i64,
+    pub claimed: u64,
+    pub bump: u8,
+}
+
+impl CreatorVesting {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // creator
+        32 + // mint
+        8 + // total_amount
+        8 + // start_ts
+        8 + // cliff_ts
+        8 + // duration
+        8 + // claimed
+        1; // bump
+
+    pub fn claimable_amount(&self, now: i64) -> Result<u64> {
+        require!(now >= self.cliff_ts, crate::error::PumpCloneError::VestingNotStarted);
+        let end_ts = self.start_ts.saturating_add(self.duration);
+        Ok(crate::utils::calculate_vested_amount(self.total_amount, self.start_ts, end_ts, now)
+            .saturating_sub(self.claimed))
+    }
+
+    /// Marks everything vested so far as claimed and returns the amount.
+    pub fn claim(&mut self, now: i64) -> Result<u64> {
+        let amount = self.claimable_amount(now)?;
+        require!(amount > 0, crate::error::PumpCloneError::InsufficientVestedAmount);
+        self.claimed = self
+            .claimed
+            .checked_add(amount)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(amount)
+    }
+}
+
+/// Owns the LP tokens minted when a curve migrates until `unlock_ts`, after
+/// which only the creator can withdraw them.
+#[account]
+#[derive(Default)]
+pub struct LpLock {
+    pub mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub creator: Pubkey,
+    pub unlock_ts: i64,
+    pub locked_at: i64,
+    /// LP tokens moved into the lock at migration
+    pub lp_amount: u64,
+    /// The pool's wSOL and token vaults, read by `lp_yield`
+    pub pool_sol_vault: Pubkey,
+    pub pool_token_vault: Pubkey,
+    /// What the pool was seeded with, and the LP supply that minted
+    pub seed_sol_amount: u64,
+    pub seed_token_amount: u64,
+    pub seed_lp_supply: u64,
+    pub bump: u8,
+}
+
+impl LpLock {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        32 + // lp_mint
+        32 + // creator
+        8 + // unlock_ts
+        8 + // locked_at
+        8 + // lp_amount
+        32 + // pool_sol_vault
+        32 + // pool_token_vault
+        8 + // seed_sol_amount
+        8 + // seed_token_amount
+        8 + // seed_lp_supply
+        1; // bump
+
+    pub fn check_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.unlock_ts, crate::error::PumpCloneError::TokensStillLocked);
+        Ok(())
+    }
+}
+
+/// AMM a graduated curve migrates into. Only Raydium has a pool-init CPI;
+/// another AMM joins with its own variant and `DexAdapter`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MigrationTarget {
+    #[default]
+    Raydium,
+}
+
+impl MigrationTarget {
+    pub fn program_id(self) -> Pubkey {
+        match self {
+            MigrationTarget::Raydium => crate::dex::RAYDIUM_AMM_V4_PROGRAM_ID,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TradeDirection {
+    Buy,
+    Sell,
+}
+
+/// A settled trade as recorded off-chain from its event, with the curve's
+/// virtual reserves just before it executed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeRecord {
+    pub direction: TradeDirection,
+    pub pre_virtual_sol_reserves: u64,
+    pub pre_virtual_token_reserves: u64,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+/// Expected result of a trade, as returned by `get_quote`. Buys take SOL in
+/// and return tokens; sells the reverse.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct TradeQuote {
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub creator_fee: u64,
+    pub platform_fee: u64,
+    pub sell_tax: u64,
+    /// Spot price once the trade has settled, for showing price impact
+    pub price_after: u64,
+}
+
+/// Fields `update_token_metadata` may change; `None` leaves a field as is.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq)]
+pub struct MetadataUpdate {
+    /// New Metaplex metadata URI
+    pub uri: Option<String>,
+    pub image_uri: Option<String>,
+    pub website: Option<String>,
+    pub twitter: Option<String>,
+    pub telegram: Option<String>,
+    pub discord: Option<String>,
+}
+
+/// What a migration would seed the pool with, as returned by
+/// `preview_migration`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct MigrationPreview {
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+/// One leg of `buy_tokens_batch`: spend `sol_amount` lamports on a curve and
+/// revert the whole batch if it yields fewer than `min_tokens_out`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct BuyLeg {
+    pub sol_amount: u64,
+    pub min_tokens_out: u64,
+}
+
+impl BuyLeg {
+    /// Remaining accounts each leg takes: bonding curve, mint, curve token
+    /// account, SOL vault, buyer token account, token launch, creator fee
+    /// destination, community wallet, buyer position, buyer whitelist PDA.
+    /// The community wallet is only checked when the launch routes part of
+    /// the creator fee to it.
+    pub const ACCOUNTS_PER_LEG: usize = 10;
+}
+
+/// A curve's real reserves as of `slot`, with a digest over all fields.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReserveAttestation {
+    pub bonding_curve: Pubkey,
+    pub real_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub slot: u64,
+    pub digest: [u8; 32],
+}
+
+/// Running totals for a wallet's overview across several positions.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PortfolioTotals {
+    pub positions: u32,
+    pub sol_invested: u64,
+    pub realized_profit: i64,
+    pub unrealized_profit: i64,
+}
+
+impl PortfolioTotals {
+    /// Adds one position, marking its open balance at `current_price`
+    /// (lamports per token, scaled by `PRICE_SCALE`).
+    pub fn add_position(&mut self, position: &UserPosition, current_price: u64) -> Result<()> {
+        let unrealized = crate::utils::unrealized_pnl(
+            position.token_balance,
+            position.average_buy_price,
+            current_price,
+        )?;
+
+        self.positions = self.positions
+            .checked_add(1)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.sol_invested = self.sol_invested
+            .checked_add(position.sol_invested)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.realized_profit = self.realized_profit
+            .checked_add(position.realized_profit)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        self.unrealized_profit = self.unrealized_profit
+            .checked_add(unrealized)
+            .ok_or(crate::error::PumpCloneError::NumericalOverflow)?;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
 pub struct UserPosition {
     pub user: Pubkey,
     pub token_launch: Pubkey,
@@ -257,7 +1908,21 @@ pub struct UserPosition {
     pub first_buy_timestamp: i64,
     pub last_trade_timestamp: i64,
     pub trade_count: u32,
+    pub buy_count: u32,
     pub is_creator: bool,
+    pub reward_round: u64,
+    pub reward_weight: u128,
+    pub rewards_claimed_round: u64,
+    /// Lamports traded on `daily_volume_day` (unix days)
+    pub daily_volume: u64,
+    pub daily_volume_day: i64,
+    /// Cumulative net SOL received from sells
+    pub sol_extracted: u64,
+    pub last_buy_slot: u64,
+    /// Balance-seconds held since the last holder-reward snapshot, folded in
+    /// whenever the balance changes
+    pub reward_balance_seconds: u128,
+    pub reward_checkpoint_ts: i64,
     pub bump: u8,
 }
 
@@ -276,15 +1941,235 @@ impl UserPosition {
         8 + // first_buy_timestamp
         8 + // last_trade_timestamp
         4 + // trade_count
+        4 + // buy_count
         1 + // is_creator
+        8 + // reward_round
+        16 + // reward_weight
+        8 + // rewards_claimed_round
+        8 + // daily_volume
+        8 + // daily_volume_day
+        8 + // sol_extracted
+        8 + // last_buy_slot
+        16 + // reward_balance_seconds
+        8 + // reward_checkpoint_ts
         1; // bump
 
+    pub const SECONDS_PER_DAY: i64 = 86_400;
+
+    /// A position can be closed once it holds no tokens, by its owner or by
+    /// anyone after the launch has migrated; rent always goes to the owner.
+    pub fn check_closable(&self, closer: &Pubkey, launch_migrated: bool) -> Result<()> {
+        require!(self.token_balance == 0, crate::error::PumpCloneError::PositionNotEmpty);
+        require!(
+            launch_migrated || *closer == self.user,
+            crate::error::PumpCloneError::Unauthorized
+        );
+        Ok(())
+    }
+
+    /// Folds a duplicate position for the same user and mint into this one.
+    /// The average buy price is re-derived from the combined cost basis.
+    pub fn merge(&mut self, other: &UserPosition) -> Result<()> {
+        self.token_balance = self.token_balance
+            .checked_add(other.token_balance)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.sol_invested = self.sol_invested
+            .checked_add(other.sol_invested)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.tokens_bought = self.tokens_bought
+            .checked_add(other.tokens_bought)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.tokens_sold = self.tokens_sold
+            .checked_add(other.tokens_sold)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.realized_profit = self.realized_profit
+            .checked_add(other.realized_profit)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.unrealized_profit = self.unrealized_profit
+            .checked_add(other.unrealized_profit)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.trade_count = self.trade_count
+            .checked_add(other.trade_count)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.buy_count = self.buy_count
+            .checked_add(other.buy_count)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.sol_extracted = self.sol_extracted
+            .checked_add(other.sol_extracted)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+
+        self.average_buy_price = if self.tokens_bought == 0 {
+            0
+        } else {
+            u64::try_from(
+                (self.sol_invested as u128) * 1_000_000_000 / self.tokens_bought as u128,
+            )
+            .map_err(|_| crate::error::ErrorCode::MathOverflow)?
+        };
+
+        if self.first_buy_timestamp == 0
+            || (other.first_buy_timestamp != 0 && other.first_buy_timestamp < self.first_buy_timestamp)
+        {
+            self.first_buy_timestamp = other.first_buy_timestamp;
+        }
+        self.last_trade_timestamp = self.last_trade_timestamp.max(other.last_trade_timestamp);
+        self.last_buy_slot = self.last_buy_slot.max(other.last_buy_slot);
+        self.is_creator |= other.is_creator;
+
+        // Weights snapshotted in the same round belong to the same holder;
+        // a claim recorded on either side covers the merged position.
+        if other.reward_round > self.reward_round {
+            self.reward_round = other.reward_round;
+            self.reward_weight = other.reward_weight;
+        } else if other.reward_round == self.reward_round {
+            self.reward_weight = self.reward_weight
+                .checked_add(other.reward_weight)
+                .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        }
+        self.rewards_claimed_round = self.rewards_claimed_round.max(other.rewards_claimed_round);
+        self.reward_balance_seconds = self.reward_balance_seconds
+            .checked_add(other.reward_balance_seconds)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.reward_checkpoint_ts = self.reward_checkpoint_ts.max(other.reward_checkpoint_ts);
+
+        if other.daily_volume_day > self.daily_volume_day {
+            self.daily_volume_day = other.daily_volume_day;
+            self.daily_volume = other.daily_volume;
+        } else if other.daily_volume_day == self.daily_volume_day {
+            self.daily_volume = self.daily_volume
+                .checked_add(other.daily_volume)
+                .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Enforces the per-wallet cooldown and daily volume limit, then books
+    /// `sol_amount` against today's volume. A zero setting disables its check.
+    pub fn check_wallet_throttle(
+        &mut self,
+        now: i64,
+        sol_amount: u64,
+        cooldown_secs: i64,
+        daily_limit: u64,
+    ) -> Result<()> {
+        if cooldown_secs > 0 && self.last_trade_timestamp > 0 {
+            require!(
+                now.saturating_sub(self.last_trade_timestamp) >= cooldown_secs,
+                crate::error::PumpCloneError::OperationTooEarly
+            );
+        }
+
+        let today = now.div_euclid(Self::SECONDS_PER_DAY);
+        if today != self.daily_volume_day {
+            self.daily_volume_day = today;
+            self.daily_volume = 0;
+        }
+        let volume = self.daily_volume
+            .checked_add(sol_amount)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        if daily_limit > 0 {
+            require!(volume <= daily_limit, crate::error::PumpCloneError::DailyLimitExceeded);
+        }
+        self.daily_volume = volume;
+
+        Ok(())
+    }
+
+    /// Books tokens bought with a compounded reward claim. The reward SOL
+    /// counts towards the cost basis like any other buy.
+    pub fn credit_compounded_reward(&mut self, token_amount: u64, sol_amount: u64) -> Result<()> {
+        self.token_balance = self.token_balance
+            .checked_add(token_amount)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.tokens_bought = self.tokens_bought
+            .checked_add(token_amount)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.sol_invested = self.sol_invested
+            .checked_add(sol_amount)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.average_buy_price = u64::try_from(
+            (self.sol_invested as u128) * 1_000_000_000 / self.tokens_bought as u128,
+        )
+        .map_err(|_| crate::error::ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Folds the time the current balance has been held into
+    /// `reward_balance_seconds`. Call before the balance changes, so a top-up
+    /// only earns weight for the time it is actually held.
+    pub fn checkpoint_reward_weight(&mut self, now: i64) {
+        if self.reward_checkpoint_ts > 0 {
+            self.reward_balance_seconds = self.reward_balance_seconds.saturating_add(
+                crate::utils::holder_reward_weight(self.token_balance, self.reward_checkpoint_ts, now),
+            );
+        }
+        self.reward_checkpoint_ts = self.reward_checkpoint_ts.max(now);
+    }
+
+    /// Closes this position's weight for a reward round at
+    /// `snapshot_timestamp`; the next round accrues from there.
+    pub fn take_reward_weight(&mut self, snapshot_timestamp: i64) -> u128 {
+        self.checkpoint_reward_weight(snapshot_timestamp);
+        std::mem::take(&mut self.reward_balance_seconds)
+    }
+
+    /// Rejects a sell landing in the same slot as this wallet's last buy, the
+    /// shape of an intra-block round trip.
+    pub fn check_same_slot_round_trip(&self, slot: u64) -> Result<()> {
+        require!(
+            self.buy_count == 0 || self.last_buy_slot != slot,
+            crate::error::PumpCloneError::FrontRunningDetected
+        );
+        Ok(())
+    }
+
+    /// The part of a sell's `sol_amount` paid for tokens this position holds.
+    /// Tokens the wallet got some other way (a transfer, an airdrop) were
+    /// never bought through the curve, so they have no cost basis here.
+    pub fn tracked_proceeds(&self, token_amount: u64, sol_amount: u64) -> u64 {
+        if token_amount == 0 {
+            return 0;
+        }
+        let tracked = token_amount.min(self.token_balance);
+        ((sol_amount as u128) * tracked as u128 / token_amount as u128) as u64
+    }
+
+    /// Rejects a sell that would take the position's cumulative proceeds past
+    /// `max_multiple_bps` of what it invested. Only proceeds from tokens the
+    /// position holds count, so a wallet selling tokens it never bought from
+    /// the curve isn't capped at zero. Zero disables the cap.
+    pub fn check_sell_extraction(&self, token_amount: u64, sol_amount: u64, max_multiple_bps: u32) -> Result<()> {
+        let tracked = self.tracked_proceeds(token_amount, sol_amount);
+        if max_multiple_bps == 0 || tracked == 0 {
+            return Ok(());
+        }
+        let cap = (self.sol_invested as u128) * max_multiple_bps as u128 / 10_000;
+        let extracted = (self.sol_extracted as u128) + tracked as u128;
+        require!(extracted <= cap, crate::error::PumpCloneError::SellExtractionCapExceeded);
+        Ok(())
+    }
+
+    /// Fills in a freshly created position on its owner's first buy; a
+    /// position already in use is left as it is.
+    pub fn open(&mut self, user: Pubkey, token_launch: Pubkey, mint: Pubkey, creator: Pubkey, bump: u8) {
+        if self.user != Pubkey::default() {
+            return;
+        }
+        self.user = user;
+        self.token_launch = token_launch;
+        self.mint = mint;
+        self.is_creator = user == creator;
+        self.bump = bump;
+    }
+
     pub fn update_after_buy(&mut self, sol_amount: u64, token_amount: u64, price: u64) -> Result<()> {
         let current_timestamp = Clock::get()?.unix_timestamp;
         
         if self.first_buy_timestamp == 0 {
             self.first_buy_timestamp = current_timestamp;
         }
+        self.checkpoint_reward_weight(current_timestamp);
 
         let total_sol_invested = self.sol_invested
             .checked_add(sol_amount)
@@ -309,20 +2194,29 @@ impl UserPosition {
         self.trade_count = self.trade_count
             .checked_add(1)
             .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.buy_count = self.buy_count
+            .checked_add(1)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.last_buy_slot = Clock::get()?.slot;
 
         Ok(())
     }
 
+    /// Books a sell against the position. Only the tokens it holds, and
+    /// their share of `sol_amount`, are booked; anything beyond that came
+    /// into the wallet outside the curve.
     pub fn update_after_sell(&mut self, sol_amount: u64, token_amount: u64, price: u64) -> Result<()> {
         let current_timestamp = Clock::get()?.unix_timestamp;
+        self.checkpoint_reward_weight(current_timestamp);
+
+        let sol_amount = self.tracked_proceeds(token_amount, sol_amount);
+        let token_amount = token_amount.min(self.token_balance);
 
         self.tokens_sold = self.tokens_sold
             .checked_add(token_amount)
             .ok_or(crate::error::ErrorCode::MathOverflow)?;
 
-        self.token_balance = self.token_balance
-            .checked_sub(token_amount)
-            .ok_or(crate::error::ErrorCode::InsufficientBalance)?;
+        self.token_balance -= token_amount;
 
         let cost_basis = self.average_buy_price
             .checked_mul(token_amount)
@@ -336,6 +2230,9 @@ impl UserPosition {
         self.realized_profit = self.realized_profit
             .checked_add(profit)
             .ok_or(crate::error::ErrorCode::MathOverflow)?;
+        self.sol_extracted = self.sol_extracted
+            .checked_add(sol_amount)
+            .ok_or(crate::error::ErrorCode::MathOverflow)?;
 
         self.last_trade_timestamp = current_timestamp;
         self.trade_count = self.trade_count
@@ -1,5 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
 
 #[error_code]
 pub enum UtilsError {
@@ -21,9 +26,20 @@ pub enum UtilsError {
     SlippageExceeded,
 }
 
-pub const BONDING_CURVE_SEED: &[u8] = b"bonding_curve";
-pub const TOKEN_VAULT_SEED: &[u8] = b"token_vault";
-pub const SOL_VAULT_SEED: &[u8] = b"sol_vault";
+/// Logs remaining compute units next to a label when built with the
+/// `profile` feature; expands to nothing otherwise.
+#[macro_export]
+macro_rules! profile_marker {
+    ($label:expr) => {
+        #[cfg(feature = "profile")]
+        {
+            anchor_lang::prelude::msg!("profile: {}", $label);
+            anchor_lang::solana_program::log::sol_log_compute_units();
+        }
+    };
+}
+
+pub use crate::pda::{BONDING_CURVE_SEED, SOL_VAULT_SEED};
 pub const METADATA_SEED: &[u8] = b"metadata";
 
 // Bonding curve constants
@@ -32,9 +48,11 @@ pub const VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000 * 1_000_000; // 1.073B tok
 pub const REAL_SOL_RESERVES: u64 = 85 * LAMPORTS_PER_SOL; // 85 SOL for migration
 pub const REAL_TOKEN_RESERVES: u64 = 793_100_000 * 1_000_000; // 793.1M tokens for migration
 pub const MAX_TOKEN_SUPPLY: u64 = 1_000_000_000 * 1_000_000; // 1B tokens with 6 decimals
+pub const MIGRATION_TOKEN_PERCENTAGE: u64 = 20; // share of total supply seeded into the pool
 pub const CREATOR_FEE_BASIS_POINTS: u16 = 100; // 1%
 pub const PLATFORM_FEE_BASIS_POINTS: u16 = 100; // 1%
 pub const BASIS_POINTS_DENOMINATOR: u16 = 10_000;
+pub const PRICE_SCALE: u64 = 1_000_000_000; // lamports per token, scaled by 1e9
 
 #[derive(Clone, Copy, Debug)]
 pub struct BondingCurveState {
@@ -60,6 +78,10 @@ impl BondingCurveState {
         Self::default()
     }
 
+    pub fn k(&self) -> Result<u128> {
+        canonical_k_constant(self.virtual_sol_reserves, self.virtual_token_reserves)
+    }
+
     pub fn calculate_buy_price(&self, token_amount: u64) -> Result<u64> {
         if token_amount == 0 {
             return Err(UtilsError::InvalidTokenAmount.into());
@@ -71,9 +93,7 @@ impl BondingCurveState {
 
         // Using constant product formula: x * y = k
         // Where x = SOL reserves, y = token reserves
-        let k = self.virtual_sol_reserves
-            .checked_mul(self.virtual_token_reserves)
-            .ok_or(UtilsError::MathOverflow)?;
+        let k = self.k()?;
 
         let new_token_reserves = self.virtual_token_reserves
             .checked_sub(token_amount)
@@ -83,15 +103,14 @@ impl BondingCurveState {
             return Err(UtilsError::DivisionByZero.into());
         }
 
-        let new_sol_reserves = k
-            .checked_div(new_token_reserves)
-            .ok_or(UtilsError::DivisionByZero)?;
+        // Round the new SOL reserve up so the buyer pays for any rounding
+        let new_sol_reserves = ceil_div(k, new_token_reserves as u128)?;
 
         let sol_amount = new_sol_reserves
-            .checked_sub(self.virtual_sol_reserves)
+            .checked_sub(self.virtual_sol_reserves as u128)
             .ok_or(UtilsError::MathOverflow)?;
 
-        Ok(sol_amount)
+        u64::try_from(sol_amount).map_err(|_| UtilsError::MathOverflow.into())
     }
 
     pub fn calculate_sell_price(&self, token_amount: u64) -> Result<u64> {
@@ -100,23 +119,20 @@ impl BondingCurveState {
         }
 
         // Using constant product formula: x * y = k
-        let k = self.virtual_sol_reserves
-            .checked_mul(self.virtual_token_reserves)
-            .ok_or(UtilsError::MathOverflow)?;
+        let k = self.k()?;
 
         let new_token_reserves = self.virtual_token_reserves
             .checked_add(token_amount)
             .ok_or(UtilsError::MathOverflow)?;
 
-        let new_sol_reserves = k
-            .checked_div(new_token_reserves)
-            .ok_or(UtilsError::DivisionByZero)?;
+        // Round the remaining SOL reserve up so the seller absorbs rounding
+        let new_sol_reserves = ceil_div(k, new_token_reserves as u128)?;
 
-        let sol_amount = self.virtual_sol_reserves
+        let sol_amount = (self.virtual_sol_reserves as u128)
             .checked_sub(new_sol_reserves)
             .ok_or(UtilsError::MathOverflow)?;
 
-        Ok(sol_amount)
+        Ok(sol_amount as u64)
     }
 
     pub fn calculate_tokens_for_sol(&self, sol_amount: u64) -> Result<u64> {
@@ -125,23 +141,32 @@ impl BondingCurveState {
         }
 
         // Using constant product formula: x * y = k
-        let k = self.virtual_sol_reserves
-            .checked_mul(self.virtual_token_reserves)
-            .ok_or(UtilsError::MathOverflow)?;
+        let k = self.k()?;
 
         let new_sol_reserves = self.virtual_sol_reserves
             .checked_add(sol_amount)
             .ok_or(UtilsError::MathOverflow)?;
 
-        let new_token_reserves = k
-            .checked_div(new_sol_reserves)
-            .ok_or(UtilsError::DivisionByZero)?;
+        // Round the remaining token reserve up so the buyer absorbs rounding
+        let new_token_reserves = ceil_div(k, new_sol_reserves as u128)?;
 
-        let token_amount = self.virtual_token_reserves
+        let token_amount = (self.virtual_token_reserves as u128)
             .checked_sub(new_token_reserves)
             .ok_or(UtilsError::MathOverflow)?;
 
-        Ok(token_amount)
+        Ok(token_amount as u64)
+    }
+
+    /// Quotes an exact-token-out buy. The cost is rounded up in the curve's
+    /// favour. The "refund" is only the part of `max_sol_in` the cost leaves
+    /// uncharged: nothing is paid back, since a buy never debits more than
+    /// it costs. Returns `(cost, refund)`.
+    pub fn quote_exact_tokens_out(&self, token_amount: u64, max_sol_in: u64) -> Result<(u64, u64)> {
+        let cost = self.calculate_buy_price(token_amount)?;
+        let refund = max_sol_in
+            .checked_sub(cost)
+            .ok_or(UtilsError::SlippageExceeded)?;
+        Ok((cost, refund))
     }
 
     pub fn update_after_buy(&mut self, sol_amount: u64, token_amount: u64) -> Result<()> {
@@ -196,18 +221,25 @@ impl BondingCurveState {
     }
 
     pub fn get_market_cap(&self) -> Result<u64> {
-        let price = self.get_current_price()?;
-        let circulating_supply = REAL_TOKEN_RESERVES
-            .checked_sub(self.virtual_token_reserves)
-            .ok_or(UtilsError::MathOverflow)?;
+        if self.virtual_token_reserves == 0 {
+            return Err(UtilsError::DivisionByZero.into());
+        }
 
-        let market_cap = price
-            .checked_mul(circulating_supply)
-            .ok_or(UtilsError::MathOverflow)?
-            .checked_div(1_000_000) // Remove price scaling
-            .ok_or(UtilsError::DivisionByZero)?;
+        self.get_market_cap_for_supply(MAX_TOKEN_SUPPLY)
+    }
+
+    pub fn get_market_cap_for_supply(&self, total_supply: u64) -> Result<u64> {
+        if self.virtual_token_reserves == 0 {
+            return Err(UtilsError::DivisionByZero.into());
+        }
 
-        Ok(market_cap)
+        // Market cap = spot price * total supply, computed without the
+        // intermediate price rounding: SOL reserves * supply / token reserves
+        safe_mul_div(
+            self.virtual_sol_reserves,
+            total_supply,
+            self.virtual_token_reserves,
+        )
     }
 }
 
@@ -252,6 +284,14 @@ pub fn calculate_sqrt(value: u64) -> u64 {
     x
 }
 
+pub fn ceil_div(numerator: u128, denominator: u128) -> Result<u128> {
+    if denominator == 0 {
+        return Err(UtilsError::DivisionByZero.into());
+    }
+
+    Ok(numerator / denominator + u128::from(numerator % denominator != 0))
+}
+
 pub fn safe_mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
     if c == 0 {
         return Err(UtilsError::DivisionByZero.into());
@@ -292,6 +332,23 @@ pub fn validate_token_amount(amount: u64) -> Result<()> {
     Ok(())
 }
 
+/// Scales a token reserve defined for a `MAX_TOKEN_SUPPLY` launch down to
+/// `total_supply`, so smaller supplies keep the same SOL-denominated curve and
+/// only the per-token price changes.
+pub fn scale_token_reserve(base_reserve: u64, total_supply: u64) -> Result<u64> {
+    validate_token_amount(total_supply)?;
+    let reserve = safe_mul_div(base_reserve, total_supply, MAX_TOKEN_SUPPLY)?;
+    if reserve == 0 {
+        return Err(UtilsError::InvalidTokenAmount.into());
+    }
+    Ok(reserve)
+}
+
+/// Tokens set aside from `total_supply` to seed the pool at migration.
+pub fn migration_token_amount(total_supply: u64) -> Result<u64> {
+    safe_mul_div(total_supply, MIGRATION_TOKEN_PERCENTAGE, 100)
+}
+
 pub fn validate_sol_amount(amount: u64) -> Result<()> {
     if amount == 0 {
         return Err(UtilsError::InvalidTokenAmount.into());
@@ -323,52 +380,3615 @@ pub fn calculate_progress_percentage(current_sol: u64, target_sol: u64) -> u16 {
     std::cmp::min(percentage as u16, 10000)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+pub fn is_graduation_cooldown_elapsed(
+    last_graduation_slot: u64,
+    cooldown_slots: u64,
+    current_slot: u64,
+) -> bool {
+    if last_graduation_slot == 0 || cooldown_slots == 0 {
+        return true;
+    }
 
-    #[test]
-    fn test_bonding_curve_buy_calculation() {
-        let curve = BondingCurveState::new();
-        let token_amount = 1_000_000; // 1 token
-        let sol_cost = curve.calculate_buy_price(token_amount).unwrap();
-        assert!(sol_cost > 0);
+    current_slot >= last_graduation_slot.saturating_add(cooldown_slots)
+}
+
+pub fn requires_sell_vesting(sol_amount: u64, vesting_threshold: u64) -> bool {
+    vesting_threshold > 0 && sol_amount >= vesting_threshold
+}
+
+pub fn calculate_vested_amount(total_amount: u64, start: i64, end: i64, now: i64) -> u64 {
+    if now <= start {
+        return 0;
     }
 
-    #[test]
-    fn test_bonding_curve_sell_calculation() {
-        let curve = BondingCurveState::new();
-        let token_amount = 1_000_000; // 1 token
-        let sol_received = curve.calculate_sell_price(token_amount).unwrap();
-        assert!(sol_received > 0);
+    if now >= end || end <= start {
+        return total_amount;
     }
 
-    #[test]
-    fn test_fee_calculation() {
-        let amount = 1_000_000;
-        let fee = calculate_fee(amount, 100).unwrap(); // 1%
-        assert_eq!(fee, 10_000);
+    let elapsed = (now - start) as u128;
+    let duration = (end - start) as u128;
+
+    ((total_amount as u128) * elapsed / duration) as u64
+}
+
+pub fn canonical_k_constant(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> Result<u128> {
+    (virtual_sol_reserves as u128)
+        .checked_mul(virtual_token_reserves as u128)
+        .ok_or(UtilsError::MathOverflow.into())
+}
+
+pub fn canonical_price(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> Result<u64> {
+    if virtual_token_reserves == 0 {
+        return Err(UtilsError::DivisionByZero.into());
     }
 
-    #[test]
-    fn test_slippage_validation() {
-        let expected = 1_000_000;
-        let actual = 990_000;
-        let slippage = 200; // 2%
-        
-        assert!(validate_slippage(expected, actual, slippage).is_ok());
-        
-        let actual_too_low = 970_000;
-        assert!(validate_slippage(expected, actual_too_low, slippage).is_err());
+    safe_mul_div(virtual_sol_reserves, PRICE_SCALE, virtual_token_reserves)
+}
+
+/// How far a buy's execution price, `sol_amount` paid for `token_amount`,
+/// lands above `reference_price`, in bps. Filling at or below the reference
+/// is no slippage at all.
+pub fn calculate_slippage(sol_amount: u64, token_amount: u64, reference_price: u64) -> Result<u64> {
+    if reference_price == 0 {
+        return Err(UtilsError::PriceCalculationFailed.into());
     }
 
-    #[test]
-    fn test_sqrt_calculation() {
-        assert_eq!(calculate_sqrt(0), 0);
-        assert_eq!(calculate_sqrt(1), 1);
-        assert_eq!(calculate_sqrt(4), 2);
-        assert_eq!(calculate_sqrt(9), 3);
-        assert_eq!(calculate_sqrt(16), 4);
+    let execution_price = safe_mul_div(sol_amount, PRICE_SCALE, token_amount)?;
+    safe_mul_div(
+        execution_price.saturating_sub(reference_price),
+        BASIS_POINTS_DENOMINATOR as u64,
+        reference_price,
+    )
+}
+
+/// Rejects a sell whose net proceeds, after fees and sell tax, land more
+/// than `max_slippage_bps` below the spot value of its tokens before the
+/// trade. The tolerance covers everything the seller gives up, not just the
+/// curve's price impact.
+pub fn check_sell_slippage(
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    token_amount: u64,
+    net_sol_out: u64,
+    max_slippage_bps: u16,
+) -> Result<()> {
+    let expected = spot_value(virtual_sol_reserves, virtual_token_reserves, token_amount)?;
+    validate_slippage(expected, net_sol_out, max_slippage_bps)
+}
+
+pub fn unwrapped_payment_amount(sol_amount: u64, wsol_balance: u64) -> Result<u64> {
+    if wsol_balance < sol_amount {
+        return Err(UtilsError::InsufficientLiquidity.into());
+    }
+
+    Ok(sol_amount)
+}
+
+pub fn meets_min_market_cap(market_cap: u64, min_market_cap: u64) -> bool {
+    min_market_cap == 0 || market_cap >= min_market_cap
+}
+
+/// Lamport value of `token_amount` base units at the curve's spot price.
+/// Price and amount are both in base units, so mint decimals cancel out.
+pub fn spot_value(virtual_sol_reserves: u64, virtual_token_reserves: u64, token_amount: u64) -> Result<u64> {
+    if virtual_token_reserves == 0 {
+        return Err(UtilsError::DivisionByZero.into());
+    }
+    safe_mul_div(virtual_sol_reserves, token_amount, virtual_token_reserves)
+}
+
+pub const SOL_DECIMALS: u8 = 9;
+const QUOTE_MICRO_UNIT_DECIMALS: u32 = 6;
+
+/// Price of one whole token in micro-units of the quote currency (1e-6 of a
+/// whole SOL, USDC, ...), scaling for both the mint's and the quote's
+/// decimals so UIs can print it directly.
+pub fn price_in_quote_micro_units(
+    virtual_quote_reserves: u64,
+    virtual_token_reserves: u64,
+    mint_decimals: u8,
+    quote_decimals: u8,
+) -> Result<u64> {
+    if virtual_token_reserves == 0 {
+        return Err(UtilsError::DivisionByZero.into());
+    }
+
+    let numerator = (virtual_quote_reserves as u128)
+        .checked_mul(
+            10u128
+                .checked_pow(mint_decimals as u32 + QUOTE_MICRO_UNIT_DECIMALS)
+                .ok_or(UtilsError::MathOverflow)?,
+        )
+        .ok_or(UtilsError::MathOverflow)?;
+    let denominator = (virtual_token_reserves as u128)
+        .checked_mul(
+            10u128
+                .checked_pow(quote_decimals as u32)
+                .ok_or(UtilsError::MathOverflow)?,
+        )
+        .ok_or(UtilsError::MathOverflow)?;
+
+    u64::try_from(numerator / denominator).map_err(|_| UtilsError::MathOverflow.into())
+}
+
+pub fn meets_min_initial_reserves(virtual_sol_reserves: u64, min_virtual_sol_reserves: u64) -> bool {
+    virtual_sol_reserves > 0 && virtual_sol_reserves >= min_virtual_sol_reserves
+}
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const TWAP_WINDOW_SECONDS: i64 = 600;
+
+pub fn utc_second_of_day(unix_timestamp: i64) -> u32 {
+    unix_timestamp.rem_euclid(SECONDS_PER_DAY) as u32
+}
+
+pub fn is_within_trading_window(start: u32, end: u32, second_of_day: u32) -> bool {
+    if start == end {
+        return true;
+    }
+
+    if start < end {
+        second_of_day >= start && second_of_day < end
+    } else {
+        // Window wraps past midnight UTC
+        second_of_day >= start || second_of_day < end
+    }
+}
+
+pub fn holder_reward_weight(balance: u64, first_buy_timestamp: i64, snapshot_timestamp: i64) -> u128 {
+    if first_buy_timestamp <= 0 || snapshot_timestamp <= first_buy_timestamp {
+        return 0;
+    }
+
+    let held_seconds = (snapshot_timestamp - first_buy_timestamp) as u128;
+    (balance as u128).saturating_mul(held_seconds)
+}
+
+pub fn holder_reward_share(round_rewards: u64, weight: u128, total_weight: u128) -> Result<u64> {
+    if total_weight == 0 || weight == 0 {
+        return Ok(0);
+    }
+
+    let share = (round_rewards as u128)
+        .checked_mul(weight)
+        .ok_or(UtilsError::MathOverflow)?
+        .checked_div(total_weight)
+        .ok_or(UtilsError::DivisionByZero)?;
+
+    Ok(share as u64)
+}
+
+/// Converts a whole-token holder threshold into base units for a mint.
+pub fn holder_balance_threshold(min_holder_balance: u64, decimals: u8) -> u64 {
+    10u64
+        .checked_pow(decimals as u32)
+        .and_then(|scale| min_holder_balance.checked_mul(scale))
+        .unwrap_or(u64::MAX)
+}
+
+pub fn accumulate_price(cumulative: u128, price: u64, last_timestamp: i64, now: i64) -> Result<u128> {
+    let elapsed = now.saturating_sub(last_timestamp).max(0) as u128;
+    cumulative
+        .checked_add(
+            (price as u128)
+                .checked_mul(elapsed)
+                .ok_or(UtilsError::MathOverflow)?,
+        )
+        .ok_or(UtilsError::MathOverflow.into())
+}
+
+pub fn average_price(cumulative_now: u128, cumulative_then: u128, now: i64, then: i64) -> Option<u64> {
+    let elapsed = now.checked_sub(then).filter(|elapsed| *elapsed > 0)?;
+    let average = cumulative_now.checked_sub(cumulative_then)? / elapsed as u128;
+    u64::try_from(average).ok()
+}
+
+/// Whether `price` sits within `band_bps` of `reference` on either side.
+pub fn within_price_band(price: u64, reference: u64, band_bps: u16) -> bool {
+    let deviation = (price as u128).abs_diff(reference as u128);
+    deviation * BASIS_POINTS_DENOMINATOR as u128 <= reference as u128 * band_bps as u128
+}
+
+/// Mint authorities the curve should give up after migration: only those it
+/// still holds, since setting an already-empty authority fails.
+pub fn authorities_to_renounce(
+    enabled: bool,
+    curve: &Pubkey,
+    mint_authority: Option<Pubkey>,
+    freeze_authority: Option<Pubkey>,
+) -> Vec<AuthorityType> {
+    if !enabled {
+        return Vec::new();
+    }
+
+    let mut authorities = Vec::new();
+    if mint_authority.as_ref() == Some(curve) {
+        authorities.push(AuthorityType::MintTokens);
+    }
+    if freeze_authority.as_ref() == Some(curve) {
+        authorities.push(AuthorityType::FreezeAccount);
+    }
+    authorities
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationShortfall {
+    Tokens,
+    Sol,
+}
+
+/// Checks the curve can seed both sides of the pool in full. The SOL vault
+/// must either be drained exactly or stay rent exempt afterwards, otherwise
+/// the wrap transfer would fail halfway through the migration.
+pub fn migration_shortfall(
+    token_balance: u64,
+    sol_balance: u64,
+    token_amount: u64,
+    sol_amount: u64,
+    vault_rent_minimum: u64,
+) -> Option<MigrationShortfall> {
+    if token_amount == 0 || token_balance < token_amount {
+        return Some(MigrationShortfall::Tokens);
+    }
+
+    if sol_amount == 0 {
+        return Some(MigrationShortfall::Sol);
+    }
+
+    match sol_balance.checked_sub(sol_amount) {
+        Some(left) if left == 0 || left >= vault_rent_minimum => None,
+        _ => Some(MigrationShortfall::Sol),
+    }
+}
+
+/// Splits a graduated curve into the pool's seed amounts: `MIGRATION_TOKEN_PERCENTAGE`
+/// of `total_supply` against `sol_amount`, provided the vaults can fund both.
+pub fn plan_migration(
+    total_supply: u64,
+    sol_amount: u64,
+    token_balance: u64,
+    sol_balance: u64,
+    vault_rent_minimum: u64,
+) -> Result<core::result::Result<crate::state::MigrationPreview, MigrationShortfall>> {
+    let token_amount = migration_token_amount(total_supply)?;
+    Ok(
+        match migration_shortfall(token_balance, sol_balance, token_amount, sol_amount, vault_rent_minimum) {
+            Some(shortfall) => Err(shortfall),
+            None => Ok(crate::state::MigrationPreview {
+                token_amount,
+                sol_amount,
+            }),
+        },
+    )
+}
+
+/// Mark-to-market PnL of an open balance bought at `average_buy_price`.
+/// Both prices are lamports per token scaled by `PRICE_SCALE`.
+pub fn unrealized_pnl(token_balance: u64, average_buy_price: u64, current_price: u64) -> Result<i64> {
+    let value = (current_price as i128 - average_buy_price as i128)
+        .checked_mul(token_balance as i128)
+        .ok_or(UtilsError::MathOverflow)?
+        / PRICE_SCALE as i128;
+    i64::try_from(value).map_err(|_| UtilsError::MathOverflow.into())
+}
+
+/// Spot price at which selling recovers what a position paid. Positions book
+/// the SOL that reached the curve, so the average buy price is grossed up by
+/// the buy fee paid on top of it and then by the sell fee taken from the
+/// proceeds. Rounds up; zero for an empty position.
+pub fn break_even_price(
+    token_balance: u64,
+    average_buy_price: u64,
+    buy_fee_bps: u16,
+    sell_fee_bps: u16,
+) -> Result<u64> {
+    if token_balance == 0 {
+        return Ok(0);
+    }
+
+    let denominator = BASIS_POINTS_DENOMINATOR as u128;
+    let numerator = (average_buy_price as u128) * denominator * denominator;
+    let kept = (denominator.saturating_sub(buy_fee_bps as u128))
+        * (denominator.saturating_sub(sell_fee_bps as u128));
+    u64::try_from(ceil_div(numerator, kept)?).map_err(|_| UtilsError::MathOverflow.into())
+}
+
+/// How far a trade moved the spot price, in bps of the price before it.
+pub fn price_impact_bps(price_before: u64, price_after: u64) -> u64 {
+    if price_before == 0 {
+        return 0;
+    }
+    let impact = price_before.abs_diff(price_after) as u128 * BASIS_POINTS_DENOMINATOR as u128
+        / price_before as u128;
+    impact.min(u64::MAX as u128) as u64
+}
+
+/// Average execution price of a trade, scaled by `PRICE_SCALE`. Pass the SOL
+/// that actually left or reached the trader's wallet so fees are included.
+pub fn effective_price(sol_amount: u64, token_amount: u64) -> Result<u64> {
+    if token_amount == 0 {
+        return Ok(0);
+    }
+    safe_mul_div(sol_amount, PRICE_SCALE, token_amount)
+}
+
+pub fn is_quote_mint_allowed(allowlist: &[Pubkey], quote_mint: &Pubkey) -> bool {
+    let mut configured = allowlist
+        .iter()
+        .filter(|mint| **mint != Pubkey::default())
+        .peekable();
+    configured.peek().is_none() || configured.any(|mint| mint == quote_mint)
+}
+
+/// Offset of the `state` byte in an SPL token account: mint (32), owner
+/// (32), amount (8) and the `COption<Pubkey>` delegate (36).
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+const TOKEN_ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// Reads the freeze state straight from token account data, the same way
+/// `token::accessor` reads balances.
+pub fn is_token_account_frozen(data: &[u8]) -> bool {
+    data.get(TOKEN_ACCOUNT_STATE_OFFSET) == Some(&TOKEN_ACCOUNT_STATE_FROZEN)
+}
+
+/// Gross SOL a sell of `token_amount` fetches from the given virtual
+/// reserves, before fees and sell tax.
+pub fn calculate_sell_amount(
+    token_amount: u64,
+    virtual_token_reserves: u64,
+    virtual_sol_reserves: u64,
+) -> Result<u64> {
+    BondingCurveState {
+        virtual_sol_reserves,
+        virtual_token_reserves,
+        real_sol_reserves: 0,
+        real_token_reserves: 0,
+    }
+    .calculate_sell_price(token_amount)
+}
+
+/// Tokens a buy of `sol_amount` fills against the given virtual reserves.
+/// The buy handler and every quote that must agree with it price through
+/// here, with the same rounding as `BondingCurveState::calculate_buy_price`.
+pub fn calculate_buy_amount(
+    sol_amount: u64,
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+) -> Result<u64> {
+    BondingCurveState {
+        virtual_sol_reserves,
+        virtual_token_reserves,
+        real_sol_reserves: 0,
+        real_token_reserves: 0,
+    }
+    .calculate_tokens_for_sol(sol_amount)
+}
+
+/// SOL to pay so at least `net_sol_amount` is left once `fee_bps` of it is
+/// taken in fees. Fees are rounded down per fee, so this can overshoot by a
+/// lamport or so but never leaves the curve short.
+pub fn gross_up_for_fees(net_sol_amount: u64, fee_bps: u16) -> Result<u64> {
+    if fee_bps >= BASIS_POINTS_DENOMINATOR {
+        return Err(crate::error::PumpCloneError::InvalidFeePercentage.into());
+    }
+    let gross = ceil_div(
+        net_sol_amount as u128 * BASIS_POINTS_DENOMINATOR as u128,
+        (BASIS_POINTS_DENOMINATOR - fee_bps) as u128,
+    )?;
+    u64::try_from(gross).map_err(|_| UtilsError::MathOverflow.into())
+}
+
+/// Passed as the per-trade slippage argument to fall back to the value
+/// stored in the trader's `UserSettings`.
+pub const USE_DEFAULT_SLIPPAGE: u64 = u64::MAX;
+
+/// Passed as the per-trade slippage argument to size the tolerance to the
+/// trade with `auto_slippage_bps`.
+pub const AUTO_SLIPPAGE: u64 = u64::MAX - 1;
+
+/// Auto-slippage granted to even the smallest trade.
+pub const AUTO_SLIPPAGE_FLOOR_BPS: u16 = 50;
+
+/// Auto-slippage for a trade of `trade_amount` against `reserve` of the same
+/// asset. A constant-product trade moves the price by about twice its share
+/// of the reserve, so larger trades get proportionally looser tolerance, up
+/// to `max_allowed_slippage_bps`.
+pub fn auto_slippage_bps(trade_amount: u64, reserve: u64, max_allowed_slippage_bps: u16) -> u16 {
+    if reserve == 0 {
+        return max_allowed_slippage_bps;
+    }
+
+    let impact_bps = trade_amount as u128 * 2 * BASIS_POINTS_DENOMINATOR as u128 / reserve as u128;
+    (AUTO_SLIPPAGE_FLOOR_BPS as u128 + impact_bps).min(max_allowed_slippage_bps as u128) as u16
+}
+
+/// Per-trade slippage in bps, or the stored default for the sentinel. The
+/// sentinel without a settings account is rejected rather than read as
+/// unbounded.
+pub fn resolve_max_slippage_bps(requested: u64, default_bps: Option<u16>) -> Result<u64> {
+    match (requested, default_bps) {
+        (USE_DEFAULT_SLIPPAGE, Some(default_bps)) => Ok(default_bps as u64),
+        (USE_DEFAULT_SLIPPAGE, None) => err!(crate::error::PumpCloneError::DefaultSlippageNotSet),
+        (requested, _) => Ok(requested),
+    }
+}
+
+/// Minimum sell output for the sentinel: the reference value of the tokens,
+/// less the stored slippage. The sentinel without a settings account is
+/// rejected rather than read as no floor.
+pub fn resolve_min_sol_output(
+    requested: u64,
+    default_bps: Option<u16>,
+    token_amount: u64,
+    reference_price: u64,
+) -> Result<u64> {
+    match (requested, default_bps) {
+        (USE_DEFAULT_SLIPPAGE, Some(default_bps)) => {
+            let expected = safe_mul_div(token_amount, reference_price, PRICE_SCALE)?;
+            let tolerance = BASIS_POINTS_DENOMINATOR.saturating_sub(default_bps) as u64;
+            safe_mul_div(expected, tolerance, BASIS_POINTS_DENOMINATOR as u64)
+        }
+        (USE_DEFAULT_SLIPPAGE, None) => err!(crate::error::PumpCloneError::DefaultSlippageNotSet),
+        (requested, _) => Ok(requested),
+    }
+}
+
+/// Referrals that count towards a wallet's buy-cap bonus. Referrals are
+/// cheap to farm with fresh wallets, so the bonus stops growing here.
+pub const MAX_BONUS_REFERRALS: u32 = 20;
+
+/// Largest single buy a wallet may make: the platform base plus a bonus for
+/// every successful referral, up to `MAX_BONUS_REFERRALS`. A zero base leaves
+/// buys uncapped.
+pub fn max_buy_allowance(base: u64, bonus_per_referral: u64, referrals: u32) -> u64 {
+    if base == 0 {
+        return u64::MAX;
+    }
+    let referrals = referrals.min(MAX_BONUS_REFERRALS);
+    base.saturating_add(bonus_per_referral.saturating_mul(referrals as u64))
+}
+
+/// Fair-launch platforms can forbid the creator's head-start buy at creation.
+pub fn is_initial_buy_permitted(initial_buy: u64, allow_creator_initial_buy: bool) -> bool {
+    initial_buy == 0 || allow_creator_initial_buy
+}
+
+/// A trade submitted with a deadline is rejected once the cluster clock has
+/// passed it. A deadline of 0 opts out of the check.
+pub fn is_deadline_expired(unix_timestamp: i64, deadline: i64) -> bool {
+    deadline != 0 && unix_timestamp > deadline
+}
+
+/// Address and bump of a curve PDA derived under a retired seed prefix.
+/// `None` when the prefix is not in `pda::RETIRED_CURVE_SEEDS` or reproduces
+/// the live curve, whose tokens are not orphaned.
+pub fn legacy_curve_address(
+    legacy_seed: &[u8],
+    mint: &Pubkey,
+    program_id: &Pubkey,
+    live_curve: &Pubkey,
+) -> Option<(Pubkey, u8)> {
+    if !crate::pda::RETIRED_CURVE_SEEDS.contains(&legacy_seed) {
+        return None;
+    }
+    let (address, bump) = Pubkey::find_program_address(&[legacy_seed, mint.as_ref()], program_id);
+    (address != *live_curve).then_some((address, bump))
+}
+
+/// Splits a migrated curve's vault balance on close into
+/// `(to_rent_recipient, to_treasury)`. The closer only gets back the
+/// rent-exempt minimum the vault was funded with; anything above it is
+/// leftover SOL that belongs to the platform. A balance below the minimum is
+/// dust nobody paid rent with, and goes to the treasury too when configured.
+/// Either way the whole balance is paid out so the vault closes.
+pub fn vault_close_payouts(balance: u64, rent_exempt_minimum: u64, dust_to_treasury: bool) -> (u64, u64) {
+    if dust_to_treasury && balance < rent_exempt_minimum {
+        (0, balance)
+    } else {
+        let to_rent_recipient = balance.min(rent_exempt_minimum);
+        (to_rent_recipient, balance - to_rent_recipient)
+    }
+}
+
+/// Creates the PDA `account` signed for by `seeds` (bump included), with
+/// `space` bytes owned by `owner` and rent paid by `payer`. Handlers call this
+/// once their guards have passed, so a rejected instruction creates nothing.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+    owner: &Pubkey,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    anchor_lang::system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::CreateAccount {
+                from: payer.clone(),
+                to: account.clone(),
+            },
+            &[seeds],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        owner,
+    )
+}
+
+/// Every top-level instruction of the running transaction, plus the index of
+/// the one currently executing.
+pub fn load_transaction_instructions(instructions_sysvar: &AccountInfo) -> Result<(Vec<Instruction>, usize)> {
+    let current_index = load_current_index_checked(instructions_sysvar)? as usize;
+    let mut instructions = Vec::new();
+    while let Ok(instruction) = load_instruction_at_checked(instructions.len(), instructions_sysvar) {
+        instructions.push(instruction);
+    }
+    Ok((instructions, current_index))
+}
+
+/// Every instruction that buys from a curve, for `is_atomic_round_trip`.
+pub const BUY_DISCRIMINATORS: [[u8; 8]; 3] = [
+    <crate::instruction::BuyTokens as anchor_lang::Discriminator>::DISCRIMINATOR,
+    <crate::instruction::BuyExactTokens as anchor_lang::Discriminator>::DISCRIMINATOR,
+    <crate::instruction::BuyTokensBatch as anchor_lang::Discriminator>::DISCRIMINATOR,
+];
+
+/// Every instruction that sells into a curve, for `is_atomic_round_trip`.
+pub const SELL_DISCRIMINATORS: [[u8; 8]; 2] = [
+    <crate::instruction::SellTokens as anchor_lang::Discriminator>::DISCRIMINATOR,
+    <crate::instruction::SellTokensWithSlippage as anchor_lang::Discriminator>::DISCRIMINATOR,
+];
+
+/// True when another instruction in the same transaction trades the opposite
+/// side of `curve` for `trader`, i.e. an atomic buy-then-sell or sell-then-buy.
+/// `opposite_discriminators` lists every instruction that trades that side.
+/// Only top-level instructions are visible; trades nested inside another
+/// program's CPI are not detected.
+pub fn is_atomic_round_trip(
+    instructions: &[Instruction],
+    current_index: usize,
+    program_id: &Pubkey,
+    opposite_discriminators: &[[u8; 8]],
+    trader: &Pubkey,
+    curve: &Pubkey,
+) -> bool {
+    instructions.iter().enumerate().any(|(index, instruction)| {
+        index != current_index
+            && instruction.program_id == *program_id
+            && opposite_discriminators
+                .iter()
+                .any(|discriminator| instruction.data.starts_with(discriminator))
+            && instruction.accounts.iter().any(|meta| meta.pubkey == *trader)
+            && instruction.accounts.iter().any(|meta| meta.pubkey == *curve)
+    })
+}
+
+/// Lamports `withdraw_fees` can take from the fee vault while leaving it
+/// rent-exempt.
+pub fn withdrawable_fees(vault_balance: u64, rent_exempt_minimum: u64) -> u64 {
+    vault_balance.saturating_sub(rent_exempt_minimum)
+}
+
+/// Shortfall, in bps, of a recorded trade's output against what
+/// `amount_in` was worth at the pre-trade spot price. Trades that filled at
+/// or better than spot report zero.
+pub fn realized_slippage_bps(record: &crate::state::TradeRecord) -> Result<u64> {
+    let (reserve_in, reserve_out) = match record.direction {
+        crate::state::TradeDirection::Buy => {
+            (record.pre_virtual_sol_reserves, record.pre_virtual_token_reserves)
+        }
+        crate::state::TradeDirection::Sell => {
+            (record.pre_virtual_token_reserves, record.pre_virtual_sol_reserves)
+        }
+    };
+    let expected_out = safe_mul_div(record.amount_in, reserve_out, reserve_in)?;
+    if expected_out == 0 || record.amount_out >= expected_out {
+        return Ok(0);
+    }
+
+    safe_mul_div(
+        expected_out - record.amount_out,
+        BASIS_POINTS_DENOMINATOR as u64,
+        expected_out,
+    )
+}
+
+/// Gross SOL side of a trade: the SOL paid in on a buy, or the SOL the curve
+/// releases for `amount_in` tokens on a sell.
+pub fn gross_trade_sol(
+    curve: &BondingCurveState,
+    direction: crate::state::TradeDirection,
+    amount_in: u64,
+) -> Result<u64> {
+    match direction {
+        crate::state::TradeDirection::Buy => Ok(amount_in),
+        crate::state::TradeDirection::Sell => curve.calculate_sell_price(amount_in),
+    }
+}
+
+/// Prices a trade the way the buy and sell handlers settle it: fees come off
+/// the gross SOL, buys are priced on and credit the curve with the net, and
+/// sells debit the gross.
+pub fn quote_trade(
+    curve: &BondingCurveState,
+    direction: crate::state::TradeDirection,
+    amount_in: u64,
+    creator_fee_bps: u16,
+    platform_fee_bps: u16,
+    sell_tax_bps: u16,
+) -> Result<crate::state::TradeQuote> {
+    let gross_sol = gross_trade_sol(curve, direction, amount_in)?;
+    let creator_fee = calculate_fee(gross_sol, creator_fee_bps)?;
+    let platform_fee = calculate_fee(gross_sol, platform_fee_bps)?;
+    let sell_tax = match direction {
+        crate::state::TradeDirection::Buy => 0,
+        crate::state::TradeDirection::Sell => calculate_fee(gross_sol, sell_tax_bps)?,
+    };
+    let net_sol = gross_sol
+        .checked_sub(creator_fee)
+        .and_then(|x| x.checked_sub(platform_fee))
+        .and_then(|x| x.checked_sub(sell_tax))
+        .ok_or(UtilsError::MathOverflow)?;
+
+    let (amount_out, sol_after, tokens_after) = match direction {
+        crate::state::TradeDirection::Buy => {
+            let tokens = calculate_buy_amount(net_sol, curve.virtual_sol_reserves, curve.virtual_token_reserves)?;
+            (
+                tokens,
+                curve.virtual_sol_reserves.checked_add(net_sol),
+                curve.virtual_token_reserves.checked_sub(tokens),
+            )
+        }
+        crate::state::TradeDirection::Sell => (
+            net_sol,
+            curve.virtual_sol_reserves.checked_sub(gross_sol),
+            curve.virtual_token_reserves.checked_add(amount_in),
+        ),
+    };
+    let price_after = canonical_price(
+        sol_after.ok_or(UtilsError::MathOverflow)?,
+        tokens_after.ok_or(UtilsError::MathOverflow)?,
+    )?;
+
+    Ok(crate::state::TradeQuote {
+        amount_in,
+        amount_out,
+        creator_fee,
+        platform_fee,
+        sell_tax,
+        price_after,
+    })
+}
+
+/// Quotes one leg of a batched buy with the single-buy math, failing with
+/// `SlippageExceeded` when it would return less than the leg's minimum.
+pub fn quote_buy_leg(
+    curve: &BondingCurveState,
+    leg: &crate::state::BuyLeg,
+    creator_fee_bps: u16,
+    platform_fee_bps: u16,
+) -> Result<crate::state::TradeQuote> {
+    let quote = quote_trade(
+        curve,
+        crate::state::TradeDirection::Buy,
+        leg.sol_amount,
+        creator_fee_bps,
+        platform_fee_bps,
+        0,
+    )?;
+    if quote.amount_out == 0 || quote.amount_out < leg.min_tokens_out {
+        return Err(UtilsError::SlippageExceeded.into());
+    }
+    Ok(quote)
+}
+
+/// Ceiling on the sell tax plus creator and platform fees, in bps.
+pub const MAX_COMBINED_SELL_FEE_BPS: u16 = 2_000;
+
+pub fn combined_sell_fees_within_cap(sell_tax_bps: u16, creator_fee_bps: u16, platform_fee_bps: u16) -> bool {
+    sell_tax_bps as u32 + creator_fee_bps as u32 + platform_fee_bps as u32
+        <= MAX_COMBINED_SELL_FEE_BPS as u32
+}
+
+/// Sell tax on `sol_amount` and the part of it routed to the price-floor
+/// vault. Returns `(sell_tax, floor_share)`.
+pub fn split_sell_tax(sol_amount: u64, sell_tax_bps: u16, floor_share_bps: u16) -> Result<(u64, u64)> {
+    let sell_tax = calculate_fee(sol_amount, sell_tax_bps)?;
+    let floor_share = calculate_fee(sell_tax, floor_share_bps)?;
+    Ok((sell_tax, floor_share))
+}
+
+/// Lamports the floor vault spends on a buyback: once the spot price has
+/// dropped below the floor, what it takes to buy the price back up to the
+/// floor, capped at the vault's balance; nothing otherwise.
+pub fn floor_buyback_amount(
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    floor_price: u64,
+    vault_balance: u64,
+) -> Result<u64> {
+    if floor_price == 0 || canonical_price(virtual_sol_reserves, virtual_token_reserves)? >= floor_price {
+        return Ok(0);
+    }
+
+    // Along x * y = k the price is x^2 / k, so the floor is reached at
+    // x = sqrt(floor * k), rounded down so the buyback never overshoots it
+    let target_sq = canonical_k_constant(virtual_sol_reserves, virtual_token_reserves)?
+        .checked_mul(floor_price as u128)
+        .ok_or(UtilsError::MathOverflow)?
+        / PRICE_SCALE as u128;
+    let shortfall = sqrt_u128(target_sq).saturating_sub(virtual_sol_reserves as u128);
+    Ok(shortfall.min(vault_balance as u128) as u64)
+}
+
+fn sqrt_u128(value: u128) -> u128 {
+    if value == 0 {
+        return 0;
+    }
+
+    let mut x = value;
+    let mut y = value / 2 + 1;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Splits the creator fee between the creator and the launch's community
+/// wallet. Returns `(creator_share, community_share)`, which always sum to
+/// `creator_fee`.
+pub fn split_creator_fee(creator_fee: u64, community_fee_bps: u16) -> Result<(u64, u64)> {
+    let community_share = calculate_fee(creator_fee, community_fee_bps)?;
+    let creator_share = creator_fee
+        .checked_sub(community_share)
+        .ok_or(UtilsError::MathOverflow)?;
+    Ok((creator_share, community_share))
+}
+
+pub const SECONDS_PER_YEAR: i64 = 365 * 86_400;
+
+/// Simple (non-compounded) annualized return in bps of `fees` earned on
+/// `principal` over `elapsed_seconds`. Zero for a fresh or empty position.
+pub fn annualized_yield_bps(fees: u64, principal: u64, elapsed_seconds: i64) -> u64 {
+    if principal == 0 || elapsed_seconds <= 0 {
+        return 0;
+    }
+    let annualized = (fees as u128)
+        * BASIS_POINTS_DENOMINATOR as u128
+        * SECONDS_PER_YEAR as u128
+        / ((principal as u128) * elapsed_seconds as u128);
+    u64::try_from(annualized).unwrap_or(u64::MAX)
+}
+
+/// Current SOL value of a locked LP position and the pool fees it has
+/// earned since seeding, as `(locked_value, fees_accrued)`. Fees grow the
+/// pool's invariant root per LP token while deposits and withdrawals leave
+/// it unchanged, so that growth, valued at the pool's current price, is the
+/// fee income. The position is valued as twice its share of the SOL side.
+pub fn lp_position_yield(
+    lock: &crate::state::LpLock,
+    pool_sol_reserve: u64,
+    pool_token_reserve: u64,
+    lp_supply: u64,
+) -> Result<(u64, u64)> {
+    if lp_supply == 0 || lock.seed_lp_supply == 0 {
+        return Ok((0, 0));
+    }
+
+    let locked_value = (pool_sol_reserve as u128)
+        .checked_mul(2 * lock.lp_amount as u128)
+        .ok_or(UtilsError::MathOverflow)?
+        / lp_supply as u128;
+    let root_now = sqrt_u128(pool_sol_reserve as u128 * pool_token_reserve as u128)
+        .checked_mul(lock.seed_lp_supply as u128)
+        .ok_or(UtilsError::MathOverflow)?;
+    let root_seed = sqrt_u128(lock.seed_sol_amount as u128 * lock.seed_token_amount as u128)
+        .checked_mul(lp_supply as u128)
+        .ok_or(UtilsError::MathOverflow)?;
+    let fees_accrued = match root_now.checked_sub(root_seed) {
+        Some(growth) if root_now > 0 => locked_value
+            .checked_mul(growth)
+            .ok_or(UtilsError::MathOverflow)?
+            / root_now,
+        _ => 0,
+    };
+
+    Ok((
+        u64::try_from(locked_value).map_err(|_| UtilsError::MathOverflow)?,
+        u64::try_from(fees_accrued).map_err(|_| UtilsError::MathOverflow)?,
+    ))
+}
+
+/// Fee for a trade of `trade_size` lamports: the tier with the highest
+/// `min_trade_size` not above it. Lower bounds are inclusive, so a trade sitting
+/// exactly on a boundary pays the upper tier's fee, and the choice does not
+/// depend on the order tiers are stored in. With no matching tier the
+/// default applies.
+pub fn fee_tier_bps(tiers: &[crate::state::FeeTier], trade_size: u64, default_bps: u16) -> u16 {
+    tiers
+        .iter()
+        .filter(|tier| !tier.is_empty() && tier.min_trade_size <= trade_size)
+        .max_by_key(|tier| tier.min_trade_size)
+        .map_or(default_bps, |tier| tier.fee_bps)
+}
+
+/// Tiers must have distinct lower bounds for the lookup to be unambiguous.
+pub fn validate_fee_tiers(tiers: &[crate::state::FeeTier]) -> bool {
+    let used: Vec<_> = tiers.iter().filter(|tier| !tier.is_empty()).collect();
+    used.iter().all(|tier| tier.fee_bps <= BASIS_POINTS_DENOMINATOR)
+        && used.iter().enumerate().all(|(i, tier)| {
+            used[i + 1..].iter().all(|other| other.min_trade_size != tier.min_trade_size)
+        })
+}
+
+/// Platform fee after the governance-holder discount. Only a balance of at
+/// least `min_balance` qualifies; without a token account the full fee applies.
+pub fn governance_discounted_fee(
+    fee: u64,
+    discount_bps: u16,
+    holder_balance: Option<u64>,
+    min_balance: u64,
+) -> Result<u64> {
+    match holder_balance {
+        Some(balance) if balance >= min_balance => {
+            let discount = calculate_fee(fee, discount_bps)?;
+            Ok(fee.checked_sub(discount).ok_or(UtilsError::MathOverflow)?)
+        }
+        _ => Ok(fee),
+    }
+}
+
+/// A creator buying their own token would only pay the creator fee back to
+/// themselves; when waived the buy skips that transfer entirely.
+pub fn creator_fee_for_buyer(creator_fee: u64, buyer: &Pubkey, creator: &Pubkey, waive_self_fee: bool) -> u64 {
+    if waive_self_fee && buyer == creator {
+        0
+    } else {
+        creator_fee
+    }
+}
+
+pub fn counts_as_holder(balance: u64, threshold: u64) -> bool {
+    balance > 0 && balance >= threshold
+}
+
+pub fn apply_holder_delta(holders_count: u32, balance_before: u64, balance_after: u64, threshold: u64) -> u32 {
+    match (
+        counts_as_holder(balance_before, threshold),
+        counts_as_holder(balance_after, threshold),
+    ) {
+        (false, true) => holders_count.saturating_add(1),
+        (true, false) => holders_count.saturating_sub(1),
+        _ => holders_count,
+    }
+}
+
+pub fn scale_reserves_for_liquidity(
+    virtual_sol_reserves: u64,
+    virtual_token_reserves: u64,
+    sol_amount: u64,
+) -> Result<(u64, u64)> {
+    if sol_amount == 0 {
+        return Err(UtilsError::InvalidTokenAmount.into());
+    }
+
+    let new_sol_reserves = virtual_sol_reserves
+        .checked_add(sol_amount)
+        .ok_or(UtilsError::MathOverflow)?;
+    let new_token_reserves = safe_mul_div(
+        virtual_token_reserves,
+        new_sol_reserves,
+        virtual_sol_reserves,
+    )?;
+
+    Ok((new_sol_reserves, new_token_reserves))
+}
+
+pub fn decayed_fee_bps(start_bps: u16, floor_bps: u16, decay_volume: u64, volume: u64) -> u16 {
+    if start_bps <= floor_bps || decay_volume == 0 || volume >= decay_volume {
+        return floor_bps.min(start_bps);
+    }
+
+    let span = (start_bps - floor_bps) as u128;
+    let decayed = span * volume as u128 / decay_volume as u128;
+
+    start_bps - decayed as u16
+}
+
+/// SOL credited to reserves after the creator and platform fees are taken,
+/// mirroring the per-fee rounding in the buy path.
+pub fn net_after_trade_fees(gross: u64, creator_fee_bps: u16, platform_fee_bps: u16) -> Result<u64> {
+    gross
+        .checked_sub(calculate_fee(gross, creator_fee_bps)?)
+        .and_then(|x| x.checked_sub(calculate_fee(gross, platform_fee_bps).ok()?))
+        .ok_or(UtilsError::MathOverflow.into())
+}
+
+/// Smallest gross buy whose net credit covers `net_needed`.
+pub fn gross_sol_for_net(net_needed: u64, creator_fee_bps: u16, platform_fee_bps: u16) -> Result<u64> {
+    if net_needed == 0 {
+        return Ok(0);
+    }
+
+    let total_bps = creator_fee_bps as u128 + platform_fee_bps as u128;
+    let denominator = (BASIS_POINTS_DENOMINATOR as u128)
+        .checked_sub(total_bps)
+        .filter(|d| *d > 0)
+        .ok_or(UtilsError::InvalidBondingCurveParams)?;
+
+    // Fees round down, so this estimate may overshoot by a lamport or two
+    let mut gross = u64::try_from(ceil_div(
+        net_needed as u128 * BASIS_POINTS_DENOMINATOR as u128,
+        denominator,
+    )?)
+    .map_err(|_| UtilsError::MathOverflow)?;
+
+    while gross > 0
+        && net_after_trade_fees(gross - 1, creator_fee_bps, platform_fee_bps)? >= net_needed
+    {
+        gross -= 1;
+    }
+
+    Ok(gross)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bonding_curve_buy_calculation() {
+        let curve = BondingCurveState::new();
+        let token_amount = 1_000_000; // 1 token
+        let sol_cost = curve.calculate_buy_price(token_amount).unwrap();
+        assert!(sol_cost > 0);
+    }
+
+    #[test]
+    fn test_bonding_curve_sell_calculation() {
+        let curve = BondingCurveState::new();
+        let token_amount = 1_000_000; // 1 token
+        let sol_received = curve.calculate_sell_price(token_amount).unwrap();
+        assert!(sol_received > 0);
+    }
+
+    #[test]
+    fn test_fee_calculation() {
+        let amount = 1_000_000;
+        let fee = calculate_fee(amount, 100).unwrap(); // 1%
+        assert_eq!(fee, 10_000);
+    }
+
+    #[test]
+    fn test_slippage_validation() {
+        let expected = 1_000_000;
+        let actual = 990_000;
+        let slippage = 200; // 2%
+        
+        assert!(validate_slippage(expected, actual, slippage).is_ok());
+        
+        let actual_too_low = 970_000;
+        assert!(validate_slippage(expected, actual_too_low, slippage).is_err());
+    }
+
+    #[test]
+    fn test_sqrt_calculation() {
+        assert_eq!(calculate_sqrt(0), 0);
+        assert_eq!(calculate_sqrt(1), 1);
+        assert_eq!(calculate_sqrt(4), 2);
+        assert_eq!(calculate_sqrt(9), 3);
+        assert_eq!(calculate_sqrt(16), 4);
+    }
+
+    #[test]
+    fn test_graduation_cooldown() {
+        // First graduation is never deferred
+        assert!(is_graduation_cooldown_elapsed(0, 150, 1_000));
+
+        // Second graduation inside the cooldown is deferred
+        assert!(!is_graduation_cooldown_elapsed(1_000, 150, 1_001));
+        assert!(!is_graduation_cooldown_elapsed(1_000, 150, 1_149));
+
+        // Allowed once the cooldown has elapsed
+        assert!(is_graduation_cooldown_elapsed(1_000, 150, 1_150));
+        assert!(is_graduation_cooldown_elapsed(1_000, 150, 2_000));
+
+        // A zero cooldown disables the gate
+        assert!(is_graduation_cooldown_elapsed(1_000, 0, 1_000));
+    }
+
+    #[test]
+    fn test_sell_vesting_threshold() {
+        let threshold = 5 * LAMPORTS_PER_SOL;
+
+        // Small sells pay out instantly
+        assert!(!requires_sell_vesting(LAMPORTS_PER_SOL, threshold));
+
+        // Large sells are escrowed
+        assert!(requires_sell_vesting(threshold, threshold));
+        assert!(requires_sell_vesting(10 * LAMPORTS_PER_SOL, threshold));
+
+        // Zero threshold disables vesting
+        assert!(!requires_sell_vesting(100 * LAMPORTS_PER_SOL, 0));
+    }
+
+    #[test]
+    fn test_vested_amount_over_time() {
+        let total = 10 * LAMPORTS_PER_SOL;
+        let start = 1_000;
+        let end = 1_000 + 3_600;
+
+        assert_eq!(calculate_vested_amount(total, start, end, start), 0);
+        assert_eq!(calculate_vested_amount(total, start, end, start + 900), total / 4);
+        assert_eq!(calculate_vested_amount(total, start, end, start + 1_800), total / 2);
+        assert_eq!(calculate_vested_amount(total, start, end, end), total);
+        assert_eq!(calculate_vested_amount(total, start, end, end + 10), total);
+    }
+
+    #[test]
+    fn test_normalize_curve_pricing() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            // Legacy scaling: k truncated to u64 and price scaled by 1e6
+            k_constant: (VIRTUAL_SOL_RESERVES as u128 * VIRTUAL_TOKEN_RESERVES as u128) as u64 as u128,
+            last_price: VIRTUAL_SOL_RESERVES * 1_000_000 / VIRTUAL_TOKEN_RESERVES,
+            ..Default::default()
+        };
+        assert!(curve.verify_invariants().is_err());
+
+        curve.normalize_pricing().unwrap();
+        assert!(curve.verify_invariants().is_ok());
+        assert_eq!(
+            curve.k_constant,
+            VIRTUAL_SOL_RESERVES as u128 * VIRTUAL_TOKEN_RESERVES as u128
+        );
+        assert_eq!(
+            curve.last_price,
+            canonical_price(VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_migration_market_cap_gate() {
+        // SOL threshold already met: 85 SOL raised on top of 30 virtual
+        let curve = BondingCurveState {
+            virtual_sol_reserves: 115 * LAMPORTS_PER_SOL,
+            virtual_token_reserves: 279_900_000 * 1_000_000,
+            real_sol_reserves: REAL_SOL_RESERVES,
+            real_token_reserves: 0,
+        };
+        assert!(curve.is_migration_ready());
+
+        let market_cap = curve.get_market_cap().unwrap();
+        assert!(market_cap > 400 * LAMPORTS_PER_SOL && market_cap < 420 * LAMPORTS_PER_SOL);
+
+        assert!(!meets_min_market_cap(market_cap, 500 * LAMPORTS_PER_SOL));
+        assert!(meets_min_market_cap(market_cap, 300 * LAMPORTS_PER_SOL));
+        assert!(meets_min_market_cap(market_cap, 0));
+    }
+
+    #[test]
+    fn test_profile_marker_is_transparent() {
+        crate::profile_marker!("test:start");
+        let fee = calculate_fee(1_000_000, 100).unwrap();
+        crate::profile_marker!("test:end");
+
+        assert_eq!(fee, 10_000);
+        assert_eq!(calculate_amount_after_fee(1_000_000, 100).unwrap(), 990_000);
+    }
+
+    #[test]
+    fn test_trading_window() {
+        let nine_am = 9 * 3_600;
+        let five_pm = 17 * 3_600;
+        let day_start = 19_700 * SECONDS_PER_DAY;
+
+        assert_eq!(utc_second_of_day(day_start + nine_am as i64), nine_am);
+
+        // Inside and outside a daytime window
+        assert!(is_within_trading_window(nine_am, five_pm, 12 * 3_600));
+        assert!(!is_within_trading_window(nine_am, five_pm, 8 * 3_600));
+        assert!(!is_within_trading_window(nine_am, five_pm, five_pm));
+
+        // Overnight window wrapping past midnight
+        assert!(is_within_trading_window(five_pm, nine_am, 23 * 3_600));
+        assert!(is_within_trading_window(five_pm, nine_am, 3 * 3_600));
+        assert!(!is_within_trading_window(five_pm, nine_am, 12 * 3_600));
+
+        // Disabled schedule trades around the clock
+        assert!(is_within_trading_window(0, 0, 12 * 3_600));
+    }
+
+    #[test]
+    fn test_holder_rewards_weighting() {
+        let snapshot = 1_000_000;
+        let day = SECONDS_PER_DAY;
+
+        // Same balance, held ten days vs one day
+        let long_holder = holder_reward_weight(1_000_000, snapshot - 10 * day, snapshot);
+        let short_holder = holder_reward_weight(1_000_000, snapshot - day, snapshot);
+        // Ten times the balance, same holding period
+        let large_holder = holder_reward_weight(10_000_000, snapshot - day, snapshot);
+
+        assert!(long_holder > short_holder);
+        assert_eq!(large_holder, long_holder);
+
+        let total = long_holder + short_holder + large_holder;
+        let rewards = 21 * LAMPORTS_PER_SOL;
+        let long_share = holder_reward_share(rewards, long_holder, total).unwrap();
+        let short_share = holder_reward_share(rewards, short_holder, total).unwrap();
+
+        assert_eq!(long_share, 10 * LAMPORTS_PER_SOL);
+        assert_eq!(short_share, LAMPORTS_PER_SOL);
+
+        // Never held through the snapshot, never rewarded
+        assert_eq!(holder_reward_weight(1_000_000, 0, snapshot), 0);
+        assert_eq!(holder_reward_share(rewards, 0, total).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_holder_reward_weight_ignores_last_minute_top_up() {
+        let start = 1_000_000;
+        let snapshot = start + 10 * SECONDS_PER_DAY;
+
+        let mut holder = UserPosition::default();
+        holder.checkpoint_reward_weight(start);
+        holder.token_balance = 1_000_000;
+
+        // Held a token for a minute, then topped up 100x right before the snapshot
+        let mut gamer = UserPosition::default();
+        gamer.checkpoint_reward_weight(start);
+        gamer.token_balance = 1;
+        gamer.checkpoint_reward_weight(snapshot - 60);
+        gamer.token_balance = 100_000_000;
+
+        let holder_weight = holder.take_reward_weight(snapshot);
+        let gamer_weight = gamer.take_reward_weight(snapshot);
+        assert_eq!(holder_weight, 1_000_000 * 10 * SECONDS_PER_DAY as u128);
+        assert!(gamer_weight < holder_weight / 100);
+
+        // The next round accrues from the snapshot, not from the first buy
+        assert_eq!(holder.reward_balance_seconds, 0);
+        assert_eq!(holder.take_reward_weight(snapshot + 100), 100_000_000);
+    }
+
+    #[test]
+    fn test_dust_holders_not_counted() {
+        // 10 whole tokens at 6 decimals
+        let threshold = holder_balance_threshold(10, 6);
+        assert_eq!(threshold, 10_000_000);
+
+        // Dust buyer does not count
+        assert_eq!(apply_holder_delta(5, 0, 1, threshold), 5);
+
+        // Meaningful buyer does
+        assert_eq!(apply_holder_delta(5, 0, 25_000_000, threshold), 6);
+
+        // Topping up dust past the threshold counts once
+        assert_eq!(apply_holder_delta(5, 1, 10_000_000, threshold), 6);
+        assert_eq!(apply_holder_delta(6, 10_000_000, 20_000_000, threshold), 6);
+
+        // Selling down to dust removes the holder
+        assert_eq!(apply_holder_delta(6, 25_000_000, 1, threshold), 5);
+
+        // A zero threshold counts any non-zero balance
+        assert_eq!(apply_holder_delta(0, 0, 1, 0), 1);
+    }
+
+    #[test]
+    fn test_add_liquidity_keeps_price_and_cuts_slippage() {
+        use crate::state::BondingCurve;
+
+        let shallow = BondingCurve {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            real_token_reserves: REAL_TOKEN_RESERVES,
+            initial_virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            initial_virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            initial_real_token_reserves: REAL_TOKEN_RESERVES,
+            ..BondingCurve::default()
+        };
+        let mut deep = shallow.clone();
+        deep.add_liquidity(3 * LAMPORTS_PER_SOL).unwrap();
+
+        assert_eq!(deep.real_sol_reserves, 3 * LAMPORTS_PER_SOL);
+        assert_eq!(
+            deep.last_price,
+            canonical_price(shallow.virtual_sol_reserves, shallow.virtual_token_reserves).unwrap()
+        );
+        deep.verify_invariants().unwrap();
+        // The widened gaps are the new baseline, not drift
+        deep.check_reserve_ratio().unwrap();
+
+        // The same buy gets more tokens from the deeper curve
+        let sol_amount = 5 * LAMPORTS_PER_SOL;
+        let buy = |curve: &BondingCurve| {
+            calculate_buy_amount(sol_amount, curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap()
+        };
+        assert!(buy(&deep) > buy(&shallow));
+    }
+
+    #[test]
+    fn test_max_trades_per_slot() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve::default();
+
+        // Three trades fit in slot 100, the fourth is rejected
+        for _ in 0..3 {
+            curve.record_trade_in_slot(100, 3).unwrap();
+        }
+        assert!(curve.record_trade_in_slot(100, 3).is_err());
+
+        // The counter resets in the next slot
+        curve.record_trade_in_slot(101, 3).unwrap();
+        assert_eq!(curve.trades_in_slot, 1);
+
+        // No cap configured
+        for _ in 0..10 {
+            curve.record_trade_in_slot(102, 0).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_migration_price_band_against_twap() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            virtual_sol_reserves: 30 * LAMPORTS_PER_SOL,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            ..BondingCurve::default()
+        };
+        let fair_price = canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+
+        // An hour of trading at the fair price
+        let mut now = 1_700_000_000;
+        for _ in 0..60 {
+            curve.update_twap(now).unwrap();
+            now += 60;
+        }
+
+        // Pump the price 50% right before graduation
+        curve.update_twap(now).unwrap();
+        curve.virtual_sol_reserves = 45 * LAMPORTS_PER_SOL;
+        let pumped_price = canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+
+        let twap = curve.twap_price(now + 1).unwrap().unwrap();
+        assert!(within_price_band(fair_price, twap, 100));
+        assert!(!within_price_band(pumped_price, twap, 1_000));
+
+        // Once the pumped price has held for long enough, the TWAP catches up
+        for _ in 0..30 {
+            now += 60;
+            curve.update_twap(now).unwrap();
+        }
+        let twap = curve.twap_price(now).unwrap().unwrap();
+        assert!(within_price_band(pumped_price, twap, 1_000));
+    }
+
+    #[test]
+    fn test_trade_milestone_paid_once() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve::default();
+        let mut payouts = 0;
+
+        for _ in 0..250 {
+            curve.record_trade_in_slot(1, 0).unwrap();
+            if curve.claim_trade_milestone(100) {
+                payouts += 1;
+            }
+        }
+
+        // Crossed 100 and 200, each paid exactly once
+        assert_eq!(curve.total_trades, 250);
+        assert_eq!(payouts, 2);
+        assert!(!curve.claim_trade_milestone(100));
+
+        // Disabled interval never pays
+        assert!(!curve.claim_trade_milestone(0));
+    }
+
+    #[test]
+    fn test_migration_funding_checked_up_front() {
+        let rent = 890_880;
+        let tokens = 200_000_000_000_000;
+        let sol = 85 * LAMPORTS_PER_SOL;
+
+        // Short on tokens: rejected before any SOL is touched
+        assert_eq!(
+            migration_shortfall(tokens - 1, sol * 2, tokens, sol, rent),
+            Some(MigrationShortfall::Tokens)
+        );
+        assert_eq!(
+            migration_shortfall(0, sol, 0, sol, rent),
+            Some(MigrationShortfall::Tokens)
+        );
+
+        // Short on SOL, or leaving the vault below rent exemption
+        assert_eq!(
+            migration_shortfall(tokens, sol - 1, tokens, sol, rent),
+            Some(MigrationShortfall::Sol)
+        );
+        assert_eq!(
+            migration_shortfall(tokens, sol + rent - 1, tokens, sol, rent),
+            Some(MigrationShortfall::Sol)
+        );
+
+        // Fully funded, draining the vault exactly or staying rent exempt
+        assert_eq!(migration_shortfall(tokens, sol, tokens, sol, rent), None);
+        assert_eq!(migration_shortfall(tokens, sol + rent, tokens, sol, rent), None);
+    }
+
+    #[test]
+    fn test_portfolio_totals() {
+        use crate::state::{PortfolioTotals, UserPosition};
+
+        // Bought 1,000 tokens at 1 SOL each, sold some for +0.5 SOL
+        let winner = UserPosition {
+            token_balance: 1_000,
+            sol_invested: 1_000 * LAMPORTS_PER_SOL,
+            average_buy_price: LAMPORTS_PER_SOL * PRICE_SCALE,
+            realized_profit: (LAMPORTS_PER_SOL / 2) as i64,
+            ..UserPosition::default()
+        };
+        // Bought 500 tokens at 2 SOL each, realized a 0.25 SOL loss
+        let loser = UserPosition {
+            token_balance: 500,
+            sol_invested: 1_000 * LAMPORTS_PER_SOL,
+            average_buy_price: 2 * LAMPORTS_PER_SOL * PRICE_SCALE,
+            realized_profit: -((LAMPORTS_PER_SOL / 4) as i64),
+            ..UserPosition::default()
+        };
+
+        let mut totals = PortfolioTotals::default();
+        // Winner marked at 1.5 SOL (+500 SOL), loser at 1 SOL (-500 SOL)
+        totals.add_position(&winner, 3 * LAMPORTS_PER_SOL / 2 * PRICE_SCALE).unwrap();
+        totals.add_position(&loser, LAMPORTS_PER_SOL * PRICE_SCALE).unwrap();
+
+        assert_eq!(totals.positions, 2);
+        assert_eq!(totals.sol_invested, 2_000 * LAMPORTS_PER_SOL);
+        assert_eq!(totals.realized_profit, (LAMPORTS_PER_SOL / 4) as i64);
+        assert_eq!(totals.unrealized_profit, 0);
+
+        assert_eq!(
+            unrealized_pnl(1_000, LAMPORTS_PER_SOL * PRICE_SCALE, 3 * LAMPORTS_PER_SOL / 2 * PRICE_SCALE).unwrap(),
+            500 * LAMPORTS_PER_SOL as i64
+        );
+    }
+
+    #[test]
+    fn test_quote_mint_allowlist() {
+        let sol = anchor_spl::token::spl_token::native_mint::ID;
+        let usdc = Pubkey::new_unique();
+        let unapproved = Pubkey::new_unique();
+        let allowlist = [sol, usdc, Pubkey::default(), Pubkey::default()];
+
+        assert!(is_quote_mint_allowed(&allowlist, &sol));
+        assert!(is_quote_mint_allowed(&allowlist, &usdc));
+        assert!(!is_quote_mint_allowed(&allowlist, &unapproved));
+
+        // Nothing configured yet: every quote mint is accepted
+        assert!(is_quote_mint_allowed(&[Pubkey::default(); 4], &unapproved));
+    }
+
+    #[test]
+    fn test_holder_target_completes_curve() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            max_holders_before_graduation: 3,
+            real_sol_reserves: LAMPORTS_PER_SOL,
+            ..BondingCurve::default()
+        };
+
+        let threshold = holder_balance_threshold(0, 6);
+        for _ in 0..2 {
+            curve.holders_count = apply_holder_delta(curve.holders_count, 0, 1_000_000, threshold);
+        }
+        assert!(!curve.reached_holder_target());
+
+        // Third holder crosses the target while far below the SOL threshold
+        curve.holders_count = apply_holder_delta(curve.holders_count, 0, 1_000_000, threshold);
+        assert!(curve.reached_holder_target());
+        assert!(curve.real_sol_reserves < REAL_SOL_RESERVES);
+
+        // Disabled by default
+        curve.max_holders_before_graduation = 0;
+        assert!(!curve.reached_holder_target());
+    }
+
+    #[test]
+    fn test_holder_target_waits_for_migration_threshold() {
+        use crate::state::BondingCurve;
+
+        let migration_threshold = 5 * LAMPORTS_PER_SOL;
+        let mut curve = BondingCurve {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            real_token_reserves: REAL_TOKEN_RESERVES,
+            max_holders_before_graduation: 1,
+            holders_count: 1,
+            ..BondingCurve::default()
+        };
+
+        // Holder target met, but completing now would strand the SOL since
+        // begin_migration needs the threshold
+        let completed = curve
+            .settle_buy(LAMPORTS_PER_SOL, 1_000, LAMPORTS_PER_SOL, REAL_SOL_RESERVES, migration_threshold, 0)
+            .unwrap();
+        assert!(completed.is_none());
+        assert!(!curve.complete);
+
+        let completed = curve
+            .settle_buy(4 * LAMPORTS_PER_SOL, 1_000, 4 * LAMPORTS_PER_SOL, REAL_SOL_RESERVES, migration_threshold, 0)
+            .unwrap();
+        assert!(completed.is_some());
+        curve.graduated_slot = 1;
+        assert!(curve.begin_migration(migration_threshold).is_ok());
+    }
+
+    #[test]
+    fn test_min_buys_before_sell() {
+        use crate::state::BondingCurve;
+
+        let curve = BondingCurve {
+            min_buys_before_sell: 3,
+            ..BondingCurve::default()
+        };
+
+        // Selling before meeting the minimum, or without a position, is rejected
+        assert!(curve.check_min_buys_before_sell(Some(2)).is_err());
+        assert!(curve.check_min_buys_before_sell(None).is_err());
+
+        // A wallet that has bought enough times can sell
+        assert!(curve.check_min_buys_before_sell(Some(3)).is_ok());
+
+        // Zero disables the requirement
+        assert!(BondingCurve::default().check_min_buys_before_sell(None).is_ok());
+    }
+
+    #[test]
+    fn test_merge_positions() {
+        use crate::state::UserPosition;
+
+        let mut canonical = UserPosition {
+            token_balance: 1_000,
+            sol_invested: 2 * LAMPORTS_PER_SOL,
+            tokens_bought: 1_000,
+            first_buy_timestamp: 200,
+            last_trade_timestamp: 300,
+            trade_count: 1,
+            ..UserPosition::default()
+        };
+        canonical.average_buy_price = 2 * LAMPORTS_PER_SOL * 1_000_000_000 / 1_000;
+
+        let duplicate = UserPosition {
+            token_balance: 3_000,
+            sol_invested: 2 * LAMPORTS_PER_SOL,
+            tokens_bought: 3_000,
+            first_buy_timestamp: 100,
+            last_trade_timestamp: 250,
+            trade_count: 2,
+            ..UserPosition::default()
+        };
+
+        canonical.merge(&duplicate).unwrap();
+
+        assert_eq!(canonical.token_balance, 4_000);
+        assert_eq!(canonical.sol_invested, 4 * LAMPORTS_PER_SOL);
+        assert_eq!(canonical.trade_count, 3);
+        assert_eq!(canonical.first_buy_timestamp, 100);
+        assert_eq!(canonical.last_trade_timestamp, 300);
+
+        // 4 SOL over 4,000 tokens, not the mean of the two averages
+        assert_eq!(canonical.average_buy_price, LAMPORTS_PER_SOL * 1_000_000_000 / 1_000);
+    }
+
+    #[test]
+    fn test_metadata_update_sets_socials_within_limits() {
+        use crate::error::PumpCloneError;
+        use crate::state::{MetadataUpdate, TokenLaunch};
+
+        let mut launch = TokenLaunch {
+            website: "https://example.com".to_string(),
+            ..TokenLaunch::default()
+        };
+        launch
+            .apply_metadata_update(MetadataUpdate {
+                twitter: Some("@launch".to_string()),
+                ..MetadataUpdate::default()
+            })
+            .unwrap();
+        assert_eq!(launch.twitter, "@launch");
+        // Fields left unset are untouched
+        assert_eq!(launch.website, "https://example.com");
+        assert_eq!(launch.metadata_uri, "");
+
+        // An over-long handle rejects the whole update, valid fields included
+        let rejected = launch.apply_metadata_update(MetadataUpdate {
+            twitter: Some("x".repeat(TokenLaunch::MAX_SOCIAL_LEN + 1)),
+            telegram: Some("t.me/launch".to_string()),
+            ..MetadataUpdate::default()
+        });
+        assert_eq!(rejected.unwrap_err(), PumpCloneError::TokenDescriptionTooLong.into());
+        assert_eq!(launch.twitter, "@launch");
+        assert_eq!(launch.telegram, "");
+
+        // Links have their own limits
+        let rejected = launch.apply_metadata_update(MetadataUpdate {
+            image_uri: Some("x".repeat(TokenLaunch::MAX_URI_LEN + 1)),
+            ..MetadataUpdate::default()
+        });
+        assert_eq!(rejected.unwrap_err(), PumpCloneError::InvalidMetadataUri.into());
+        launch
+            .apply_metadata_update(MetadataUpdate {
+                twitter: Some("x".repeat(TokenLaunch::MAX_SOCIAL_LEN)),
+                ..MetadataUpdate::default()
+            })
+            .unwrap();
+        assert_eq!(launch.twitter.len(), TokenLaunch::MAX_SOCIAL_LEN);
+
+        // A locked launch keeps every field, not just the URI
+        launch.metadata_locked = true;
+        for update in [
+            MetadataUpdate {
+                image_uri: Some("https://example.com/new.png".to_string()),
+                ..MetadataUpdate::default()
+            },
+            MetadataUpdate {
+                website: Some("https://example.org".to_string()),
+                ..MetadataUpdate::default()
+            },
+            MetadataUpdate {
+                discord: Some("discord.gg/launch".to_string()),
+                ..MetadataUpdate::default()
+            },
+        ] {
+            assert_eq!(
+                launch.apply_metadata_update(update).unwrap_err(),
+                PumpCloneError::MetadataLocked.into()
+            );
+        }
+        assert_eq!(launch.website, "https://example.com");
+        assert_eq!(launch.image_uri, "");
+    }
+
+    #[test]
+    fn test_rolling_volume_resets_after_a_day() {
+        use crate::state::BondingCurve;
+
+        let start = 1_700_000_000;
+        let mut curve = BondingCurve::default();
+        curve.record_rolling_volume(start, 2 * LAMPORTS_PER_SOL).unwrap();
+        curve.record_rolling_volume(start + 3_600, LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(curve.volume_window_start, start);
+        assert_eq!(curve.volume_24h, 3 * LAMPORTS_PER_SOL);
+        assert_eq!(curve.trades_24h, 2);
+
+        // 25 hours on, the first trade restarts the window from its own values
+        let later = start + 25 * 3_600;
+        curve.record_rolling_volume(later, LAMPORTS_PER_SOL / 2).unwrap();
+        assert_eq!(curve.volume_window_start, later);
+        assert_eq!(curve.volume_24h, LAMPORTS_PER_SOL / 2);
+        assert_eq!(curve.trades_24h, 1);
+
+        // Just short of a day later the window is still open
+        curve.record_rolling_volume(later + BondingCurve::VOLUME_WINDOW_SECS - 1, LAMPORTS_PER_SOL).unwrap();
+        assert_eq!(curve.volume_window_start, later);
+        assert_eq!(curve.trades_24h, 2);
+    }
+
+    #[test]
+    fn test_raydium_amm_id_follows_market() {
+        let program = crate::dex::RAYDIUM_AMM_V4_PROGRAM_ID;
+        let (market, other_market) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let amm_id = crate::dex::raydium_amm_id(&program, &market);
+        assert_eq!(
+            amm_id,
+            Pubkey::find_program_address(&[program.as_ref(), market.as_ref(), b"amm_associated_seed"], &program).0
+        );
+        // A caller can't pass a pool for some other market, or a keypair
+        assert_ne!(amm_id, crate::dex::raydium_amm_id(&program, &other_market));
+        assert!(!amm_id.is_on_curve());
+    }
+
+    #[test]
+    fn test_gross_up_for_fees_covers_the_net_amount() {
+        for net in [1u64, 999, LAMPORTS_PER_SOL, 12_345_678_901] {
+            let gross = gross_up_for_fees(net, 200).unwrap();
+            let fees = calculate_fee(gross, 100).unwrap() + calculate_fee(gross, 100).unwrap();
+            assert!(gross - fees >= net);
+            assert!(gross <= net * 10_000 / 9_800 + 1);
+        }
+        assert_eq!(gross_up_for_fees(LAMPORTS_PER_SOL, 0).unwrap(), LAMPORTS_PER_SOL);
+        assert!(gross_up_for_fees(1, BASIS_POINTS_DENOMINATOR).is_err());
+    }
+
+    #[test]
+    fn test_quote_buy_leg_enforces_min_tokens_out() {
+        use crate::state::BuyLeg;
+
+        let curve = BondingCurveState::new();
+        let sol = LAMPORTS_PER_SOL;
+        // Only the SOL left after both 1% fees buys tokens
+        let expected = curve.calculate_tokens_for_sol(sol - 2 * (sol / 100)).unwrap();
+        let quote = quote_buy_leg(&curve, &BuyLeg { sol_amount: sol, min_tokens_out: expected }, 100, 100).unwrap();
+        assert_eq!(quote.amount_out, expected);
+        assert_eq!(quote.creator_fee, sol / 100);
+        assert_eq!(quote.platform_fee, sol / 100);
+        assert!(quote_buy_leg(&curve, &BuyLeg { sol_amount: sol, min_tokens_out: expected + 1 }, 100, 100).is_err());
+    }
+
+    #[test]
+    fn test_sell_circuit_breaker() {
+        use crate::state::BondingCurve;
+
+        // Prices around a sell of `tokens` from a curve holding 20 SOL of buys
+        let mut state = BondingCurveState::new();
+        let bought = state.calculate_tokens_for_sol(20 * LAMPORTS_PER_SOL).unwrap();
+        state.update_after_buy(20 * LAMPORTS_PER_SOL, bought).unwrap();
+        let sell = |state: &mut BondingCurveState, tokens: u64| {
+            let before = canonical_price(state.virtual_sol_reserves, state.virtual_token_reserves).unwrap();
+            let sol = state.calculate_sell_price(tokens).unwrap();
+            state.update_after_sell(sol, tokens).unwrap();
+            let after = canonical_price(state.virtual_sol_reserves, state.virtual_token_reserves).unwrap();
+            (before, after)
+        };
+        let max_drop_bps = 1_000;
+        let slot = 500;
+
+        // Dumping the whole bag in one go breaches a 10% per-slot limit
+        let mut dumped = state;
+        let (before, after) = sell(&mut dumped, bought);
+        let mut curve = BondingCurve::default();
+        assert_eq!(
+            curve.check_circuit_breaker(slot, before, after, max_drop_bps).unwrap_err(),
+            crate::error::PumpCloneError::CircuitBreakerTriggered.into()
+        );
+
+        // A small sell passes, and is bypassed entirely when disabled
+        let mut curve = BondingCurve::default();
+        let (before, after) = sell(&mut state, bought / 50);
+        curve.check_circuit_breaker(slot, before, after, max_drop_bps).unwrap();
+        assert_eq!(curve.last_price, after);
+        assert_eq!(curve.slot_open_price, before);
+        BondingCurve::default().check_circuit_breaker(slot, 100, 1, 0).unwrap();
+
+        // Small sells in the same slot add up against the slot's opening price
+        let mut tripped = false;
+        for _ in 0..20 {
+            let (before, after) = sell(&mut state, bought / 50);
+            if curve.check_circuit_breaker(slot, before, after, max_drop_bps).is_err() {
+                tripped = true;
+                break;
+            }
+        }
+        assert!(tripped);
+
+        // A new slot measures from a fresh opening price
+        let (before, after) = sell(&mut state, bought / 50);
+        curve.check_circuit_breaker(slot + 1, before, after, max_drop_bps).unwrap();
+    }
+
+    #[test]
+    fn test_curve_completion_event_and_snapshot() {
+        use crate::state::BondingCurve;
+
+        let funding_goal = 85 * LAMPORTS_PER_SOL;
+        let created_at = 1_700_000_000;
+        let mut state = BondingCurveState::new();
+        let mut curve = BondingCurve {
+            mint: Pubkey::new_unique(),
+            created_at,
+            ..BondingCurve::default()
+        };
+
+        // Buy until the real SOL reserves reach the funding goal
+        let mut buys = 0;
+        while state.real_sol_reserves < funding_goal {
+            let tokens = state.calculate_tokens_for_sol(5 * LAMPORTS_PER_SOL).unwrap();
+            state.update_after_buy(5 * LAMPORTS_PER_SOL, tokens).unwrap();
+            curve.total_volume += 5 * LAMPORTS_PER_SOL;
+            buys += 1;
+        }
+        curve.virtual_sol_reserves = state.virtual_sol_reserves;
+        curve.virtual_token_reserves = state.virtual_token_reserves;
+        curve.real_sol_reserves = state.real_sol_reserves;
+        curve.real_token_reserves = state.real_token_reserves;
+        curve.holders_count = buys;
+
+        let now = created_at + 7_200;
+        let event = curve.mark_complete(now);
+
+        assert!(curve.complete);
+        assert_eq!(event.mint, curve.mint);
+        assert_eq!(event.real_sol_reserves, state.real_sol_reserves);
+        assert!(event.real_sol_reserves >= funding_goal);
+        assert_eq!(event.real_token_reserves, state.real_token_reserves);
+        assert_eq!(event.virtual_sol_reserves, state.virtual_sol_reserves);
+        assert_eq!(event.virtual_token_reserves, state.virtual_token_reserves);
+        assert_eq!(event.total_volume, buys as u64 * 5 * LAMPORTS_PER_SOL);
+        assert_eq!(event.holders_count, buys);
+        assert_eq!(event.time_to_completion, 7_200);
+        assert_eq!(event.timestamp, now);
+
+        // The same figures stay queryable on the curve
+        assert_eq!(curve.completed_at, now);
+        assert_eq!(curve.completion_holders_count, buys);
+        assert_eq!(curve.time_to_completion, 7_200);
+    }
+
+    #[test]
+    fn test_whitelist_phase_gates_buys() {
+        use crate::state::{BondingCurve, Whitelist};
+
+        let created_at = 1_700_000_000;
+        let curve = BondingCurve {
+            creator: Pubkey::new_unique(),
+            created_at,
+            whitelist_end_ts: created_at + 3_600,
+            ..BondingCurve::default()
+        };
+        let outsider = Pubkey::new_unique();
+        let (key, owner) = (Pubkey::new_unique(), crate::ID);
+        let (mut lamports, mut data) = (0u64, Vec::new());
+        let no_entry = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        let is_whitelisted = Whitelist::is_whitelisted(&no_entry).unwrap();
+        assert!(!is_whitelisted);
+
+        // During the phase a wallet without an entry is turned away
+        assert_eq!(
+            curve.check_whitelist_phase(created_at + 60, &outsider, is_whitelisted).unwrap_err(),
+            crate::error::PumpCloneError::UserNotWhitelisted.into()
+        );
+        curve.check_whitelist_phase(created_at + 60, &outsider, true).unwrap();
+        curve.check_whitelist_phase(created_at + 60, &curve.creator, false).unwrap();
+
+        // The same wallet can buy once the phase ends
+        curve.check_whitelist_phase(curve.whitelist_end_ts, &outsider, is_whitelisted).unwrap();
+
+        // Launches without a phase are open from the start
+        let open = BondingCurve { whitelist_end_ts: 0, ..curve };
+        open.check_whitelist_phase(created_at, &outsider, false).unwrap();
+    }
+
+    #[test]
+    fn test_blacklisted_wallet_cannot_trade_until_removed() {
+        use crate::state::Blacklist;
+
+        let wallet = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let owner = crate::ID;
+        let account_data = |is_blacklisted: bool| {
+            let mut data = Vec::new();
+            Blacklist { wallet, is_blacklisted, bump: 255 }.try_serialize(&mut data).unwrap();
+            data
+        };
+
+        // A wallet that was never listed has no account and trades freely
+        let (mut lamports, mut data) = (0u64, Vec::new());
+        let never_listed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        Blacklist::check_not_blacklisted(&never_listed).unwrap();
+
+        let (mut lamports, mut data) = (LAMPORTS_PER_SOL, account_data(true));
+        let listed = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        assert_eq!(
+            Blacklist::check_not_blacklisted(&listed).unwrap_err(),
+            crate::error::PumpCloneError::UserBlacklisted.into()
+        );
+
+        // Lifting the ban keeps the account but lets the wallet buy again
+        let (mut lamports, mut data) = (LAMPORTS_PER_SOL, account_data(false));
+        let lifted = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        Blacklist::check_not_blacklisted(&lifted).unwrap();
+    }
+
+    #[test]
+    fn test_referral_counted_once_per_referred_wallet() {
+        use crate::state::Referral;
+
+        let mut referrer = Referral { owner: Pubkey::new_unique(), ..Referral::default() };
+        let mut other = Referral { owner: Pubkey::new_unique(), ..Referral::default() };
+        let mut wallet = Referral { owner: Pubkey::new_unique(), ..Referral::default() };
+
+        wallet.record_referrer(&mut referrer).unwrap();
+        assert_eq!(wallet.referred_by, referrer.owner);
+        assert_eq!(referrer.successful_referrals, 1);
+
+        // Naming a referrer again, the same one or another, counts nothing
+        assert_eq!(
+            wallet.record_referrer(&mut referrer).unwrap_err(),
+            crate::error::PumpCloneError::MaximumReferralsExceeded.into()
+        );
+        assert_eq!(
+            wallet.record_referrer(&mut other).unwrap_err(),
+            crate::error::PumpCloneError::MaximumReferralsExceeded.into()
+        );
+        assert_eq!((referrer.successful_referrals, other.successful_referrals), (1, 0));
+
+        // Nor can a wallet refer itself
+        let mut own = Referral { owner: other.owner, ..Referral::default() };
+        assert_eq!(
+            own.record_referrer(&mut other).unwrap_err(),
+            crate::error::PumpCloneError::SelfReferralNotAllowed.into()
+        );
+        assert_eq!(other.successful_referrals, 0);
+    }
+
+    #[test]
+    fn test_referral_rewards_accrue_per_buy() {
+        use crate::state::Referral;
+
+        let referrer = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+        let mut referral = Referral { owner: referrer, ..Referral::default() };
+        let referral_fee_bps = 2_500;
+
+        // Two referred buys of different sizes each pay a quarter of their
+        // platform fee
+        let mut expected = 0;
+        for sol_amount in [LAMPORTS_PER_SOL, 3 * LAMPORTS_PER_SOL] {
+            let platform_fee = calculate_fee(sol_amount, PLATFORM_FEE_BASIS_POINTS).unwrap();
+            let share = calculate_fee(platform_fee, referral_fee_bps).unwrap();
+            referral.accrue_reward(&buyer, share).unwrap();
+            expected += share;
+        }
+        assert_eq!(expected, LAMPORTS_PER_SOL / 100);
+        assert_eq!(referral.pending_rewards, expected);
+        assert_eq!(referral.total_rewards, expected);
+
+        // Buying through your own referral is rejected and earns nothing
+        assert_eq!(
+            referral.accrue_reward(&referrer, 1).unwrap_err(),
+            crate::error::PumpCloneError::SelfReferralNotAllowed.into()
+        );
+        assert_eq!(referral.pending_rewards, expected);
+
+        Referral::validate_code("moon42").unwrap();
+        for code in ["ab", "this-has-dashes", "waytoolongforareferralcode"] {
+            assert_eq!(
+                Referral::validate_code(code).unwrap_err(),
+                crate::error::PumpCloneError::InvalidReferralCode.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_dex_adapter_targets_configured_program() {
+        use crate::dex::{adapter_for, RAYDIUM_AMM_V4_PROGRAM_ID};
+        use crate::state::{GlobalState, MigrationTarget};
+
+        assert_eq!(GlobalState::default().migration_target, MigrationTarget::Raydium);
+
+        let owner = Pubkey::default();
+        let key = RAYDIUM_AMM_V4_PROGRAM_ID;
+        let (mut lamports, mut data) = (0u64, [0u8; 0]);
+        let raydium = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, true, 0);
+        let adapter = adapter_for(MigrationTarget::Raydium, &raydium, Pubkey::new_unique(), &[], 0).unwrap();
+        assert_eq!(adapter.program_id(), RAYDIUM_AMM_V4_PROGRAM_ID);
+
+        // A program that doesn't belong to the configured target is rejected
+        let key = Pubkey::new_unique();
+        let (mut lamports, mut data) = (0u64, [0u8; 0]);
+        let other = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, true, 0);
+        assert!(adapter_for(MigrationTarget::Raydium, &other, Pubkey::new_unique(), &[], 0).is_err());
+    }
+
+    #[test]
+    fn test_lp_lock_releases_after_unlock_ts() {
+        use crate::state::{GlobalState, LpLock};
+
+        let migration_timestamp = 1_700_000_000;
+        let global_state = GlobalState {
+            lp_lock_duration: GlobalState::DEFAULT_LP_LOCK_DURATION,
+            ..GlobalState::default()
+        };
+        let lp_lock = LpLock {
+            unlock_ts: migration_timestamp + global_state.lp_lock_duration,
+            ..LpLock::default()
+        };
+
+        // Straight after migration, and a second before the unlock
+        for now in [migration_timestamp, lp_lock.unlock_ts - 1] {
+            assert_eq!(
+                lp_lock.check_unlocked(now).unwrap_err(),
+                crate::error::PumpCloneError::TokensStillLocked.into()
+            );
+        }
+
+        // Clock warped past the unlock
+        lp_lock.check_unlocked(lp_lock.unlock_ts).unwrap();
+        lp_lock.check_unlocked(lp_lock.unlock_ts + 86_400).unwrap();
+    }
+
+    #[test]
+    fn test_creator_vesting_claims() {
+        use crate::state::CreatorVesting;
+
+        let start = 1_700_000_000;
+        let total = 40_000_000 * 1_000_000;
+        let mut vesting = CreatorVesting {
+            total_amount: total,
+            start_ts: start,
+            cliff_ts: start + 1_000,
+            duration: 4_000,
+            ..CreatorVesting::default()
+        };
+
+        // Before the cliff nothing can be claimed
+        assert_eq!(
+            vesting.claim(start + 999).unwrap_err(),
+            crate::error::PumpCloneError::VestingNotStarted.into()
+        );
+        assert_eq!(vesting.claimed, 0);
+
+        // Mid-vest releases the linear share, and only once
+        assert_eq!(vesting.claim(start + 2_000).unwrap(), total / 2);
+        assert_eq!(
+            vesting.claim(start + 2_000).unwrap_err(),
+            crate::error::PumpCloneError::InsufficientVestedAmount.into()
+        );
+
+        // Past the duration the remainder is released in full
+        assert_eq!(vesting.claim(start + 10_000).unwrap(), total - total / 2);
+        assert_eq!(vesting.claimed, total);
+        assert_eq!(vesting.claimable_amount(start + 20_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_market_cap_counts_circulating_supply() {
+        use crate::state::BondingCurve;
+
+        let mut state = BondingCurveState::new();
+        let on_chain = |state: &BondingCurveState| BondingCurve {
+            total_supply: MAX_TOKEN_SUPPLY,
+            virtual_sol_reserves: state.virtual_sol_reserves,
+            virtual_token_reserves: state.virtual_token_reserves,
+            real_token_reserves: state.real_token_reserves,
+            ..BondingCurve::default()
+        };
+
+        // Only the supply outside the curve's real reserves circulates
+        let fresh = on_chain(&state);
+        assert_eq!(
+            fresh.market_cap().unwrap(),
+            spot_value(
+                state.virtual_sol_reserves,
+                state.virtual_token_reserves,
+                MAX_TOKEN_SUPPLY - REAL_TOKEN_RESERVES,
+            )
+            .unwrap()
+        );
+
+        let mut previous = fresh.market_cap().unwrap();
+        for _ in 0..20 {
+            let tokens = state.calculate_tokens_for_sol(4 * LAMPORTS_PER_SOL).unwrap();
+            state.update_after_buy(4 * LAMPORTS_PER_SOL, tokens).unwrap();
+            let market_cap = on_chain(&state).market_cap().unwrap();
+            assert!(market_cap > previous, "{} <= {}", market_cap, previous);
+            previous = market_cap;
+        }
+
+        // Reserves above the recorded supply saturate rather than underflow
+        let oversized = BondingCurve { total_supply: 0, ..on_chain(&state) };
+        assert_eq!(oversized.market_cap().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_close_position_requires_empty_balance() {
+        use crate::state::UserPosition;
+
+        let owner = Pubkey::new_unique();
+        let stranger = Pubkey::new_unique();
+        let mut position = UserPosition {
+            user: owner,
+            token_balance: 1_000_000,
+            tokens_bought: 1_000_000,
+            ..UserPosition::default()
+        };
+
+        assert_eq!(
+            position.check_closable(&owner, false).unwrap_err(),
+            crate::error::PumpCloneError::PositionNotEmpty.into()
+        );
+
+        // Sell everything
+        position.tokens_sold = position.token_balance;
+        position.token_balance = 0;
+        position.check_closable(&owner, false).unwrap();
+
+        // Only the owner may close before migration; anyone may after
+        assert_eq!(
+            position.check_closable(&stranger, false).unwrap_err(),
+            crate::error::PumpCloneError::Unauthorized.into()
+        );
+        position.check_closable(&stranger, true).unwrap();
+    }
+
+    #[test]
+    fn test_second_migration_rejected() {
+        use crate::state::BondingCurve;
+
+        let threshold = 85 * LAMPORTS_PER_SOL;
+        let mut curve = BondingCurve { real_sol_reserves: threshold - 1, ..BondingCurve::default() };
+
+        // Not complete, then complete but short of the threshold
+        assert_eq!(
+            curve.begin_migration(threshold).unwrap_err(),
+            crate::error::PumpCloneError::BondingCurveNotCompleted.into()
+        );
+        curve.complete = true;
+        assert_eq!(
+            curve.begin_migration(threshold).unwrap_err(),
+            crate::error::PumpCloneError::LiquidityMigrationNotReady.into()
+        );
+        assert!(!curve.migrated);
+
+        // Funded but not yet graduated
+        curve.real_sol_reserves = threshold;
+        assert_eq!(
+            curve.begin_migration(threshold).unwrap_err(),
+            crate::error::PumpCloneError::NotGraduated.into()
+        );
+        assert!(!curve.migrated);
+
+        curve.graduated_slot = 1;
+        curve.begin_migration(threshold).unwrap();
+        assert!(curve.migrated);
+        assert_eq!(
+            curve.begin_migration(threshold).unwrap_err(),
+            crate::error::PumpCloneError::AlreadyMigrated.into()
+        );
+    }
+
+    #[test]
+    fn test_migration_recorded_on_launch() {
+        use crate::state::TokenLaunch;
+
+        let mut launch = TokenLaunch::default();
+        let pool = Pubkey::new_unique();
+        launch.record_migration(pool, 1_700_000_000).unwrap();
+
+        assert!(launch.is_migrated);
+        assert_eq!(launch.raydium_pool, Some(pool));
+        assert_eq!(launch.migration_timestamp, Some(1_700_000_000));
+
+        // A second migration can't repoint the launch
+        assert_eq!(
+            launch.record_migration(Pubkey::new_unique(), 1_700_000_100).unwrap_err(),
+            crate::error::PumpCloneError::LiquidityAlreadyMigrated.into()
+        );
+        assert_eq!(launch.raydium_pool, Some(pool));
+    }
+
+    #[test]
+    fn test_per_buy_token_cap() {
+        use crate::state::GlobalState;
+
+        let curve = BondingCurveState::new();
+        // At most 1% of the remaining real reserves per buy
+        let global_state = GlobalState { max_tokens_per_buy_bps: 100, ..GlobalState::default() };
+        let cap = curve.real_token_reserves / 100;
+
+        let small = curve.calculate_tokens_for_sol(LAMPORTS_PER_SOL / 10).unwrap();
+        assert!(small <= cap);
+        assert!(global_state.check_tokens_per_buy(small, curve.real_token_reserves).is_ok());
+
+        let large = curve.calculate_tokens_for_sol(LAMPORTS_PER_SOL).unwrap();
+        assert!(large > cap);
+        assert_eq!(
+            global_state.check_tokens_per_buy(large, curve.real_token_reserves).unwrap_err(),
+            crate::error::PumpCloneError::MaxPurchaseAmountExceeded.into()
+        );
+        assert!(global_state.check_tokens_per_buy(cap, curve.real_token_reserves).is_ok());
+        assert!(global_state.check_tokens_per_buy(cap + 1, curve.real_token_reserves).is_err());
+
+        // Disabled by default
+        assert!(GlobalState::default().check_tokens_per_buy(large, curve.real_token_reserves).is_ok());
+    }
+
+    #[test]
+    fn test_break_even_price_covers_buy_and_sell_fees() {
+        use crate::state::UserPosition;
+
+        // 1 SOL reached the curve for 1M tokens after a 2% buy fee
+        let tokens = 1_000_000 * 1_000_000;
+        let position = UserPosition {
+            token_balance: tokens,
+            sol_invested: LAMPORTS_PER_SOL,
+            tokens_bought: tokens,
+            average_buy_price: safe_mul_div(LAMPORTS_PER_SOL, PRICE_SCALE, tokens).unwrap(),
+            ..UserPosition::default()
+        };
+        let (buy_fee_bps, sell_fee_bps) = (200, 300);
+
+        let price = break_even_price(position.token_balance, position.average_buy_price, buy_fee_bps, sell_fee_bps)
+            .unwrap();
+        assert!(price > position.average_buy_price);
+
+        // Selling everything at that price returns the gross SOL spent
+        let gross_paid = safe_mul_div(LAMPORTS_PER_SOL, 10_000, 10_000 - buy_fee_bps as u64).unwrap();
+        let proceeds = safe_mul_div(tokens, price, PRICE_SCALE).unwrap();
+        let net = calculate_amount_after_fee(proceeds, sell_fee_bps).unwrap();
+        assert!(net >= gross_paid);
+        // ... and one price tick lower falls short
+        let proceeds = safe_mul_div(tokens, price - 1, PRICE_SCALE).unwrap();
+        assert!(calculate_amount_after_fee(proceeds, sell_fee_bps).unwrap() <= gross_paid);
+
+        // Without fees break-even is the average buy price itself
+        assert_eq!(
+            break_even_price(position.token_balance, position.average_buy_price, 0, 0).unwrap(),
+            position.average_buy_price
+        );
+        // Nothing to sell, nothing to break even on
+        assert_eq!(break_even_price(0, position.average_buy_price, buy_fee_bps, sell_fee_bps).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_sell_slippage_in_bps() {
+        let curve = BondingCurveState::new();
+        // Selling 1/19 of the virtual token reserves lands 5% below spot
+        let token_amount = curve.virtual_token_reserves / 19;
+        let quoted_sol =
+            calculate_sell_amount(token_amount, curve.virtual_token_reserves, curve.virtual_sol_reserves).unwrap();
+        assert_eq!(quoted_sol, curve.calculate_sell_price(token_amount).unwrap());
+        let check = |max_bps| {
+            check_sell_slippage(
+                curve.virtual_sol_reserves,
+                curve.virtual_token_reserves,
+                token_amount,
+                quoted_sol,
+                max_bps,
+            )
+        };
+
+        assert_eq!(check(100).unwrap_err(), UtilsError::SlippageExceeded.into());
+        assert!(check(499).is_err());
+        assert!(check(501).is_ok());
+
+        // A small sell stays inside a 1% tolerance
+        let small = curve.virtual_token_reserves / 1_000;
+        let small_quote = curve.calculate_sell_price(small).unwrap();
+        assert!(check_sell_slippage(
+            curve.virtual_sol_reserves,
+            curve.virtual_token_reserves,
+            small,
+            small_quote,
+            100,
+        )
+        .is_ok());
+
+        // The same small sell after 1% creator and 1% platform fees gives up
+        // more than 1% of its spot value
+        let quote = quote_trade(&curve, crate::state::TradeDirection::Sell, small, 100, 100, 0).unwrap();
+        assert!(check_sell_slippage(
+            curve.virtual_sol_reserves,
+            curve.virtual_token_reserves,
+            small,
+            quote.amount_out,
+            100,
+        )
+        .is_err());
+        assert!(check_sell_slippage(
+            curve.virtual_sol_reserves,
+            curve.virtual_token_reserves,
+            small,
+            quote.amount_out,
+            250,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_metadata_lock_on_first_buy() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            lock_metadata_on_first_buy: true,
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            real_token_reserves: REAL_TOKEN_RESERVES,
+            ..BondingCurve::default()
+        };
+        // Creator liquidity isn't a buy
+        curve.real_sol_reserves = LAMPORTS_PER_SOL;
+        assert!(curve.check_metadata_updatable().is_ok());
+        curve.real_sol_reserves = 0;
+
+        curve
+            .settle_buy(LAMPORTS_PER_SOL / 100, 1_000, LAMPORTS_PER_SOL / 100, REAL_SOL_RESERVES, u64::MAX, 1_700_000_000)
+            .unwrap();
+        assert_eq!(curve.first_buy_at, 1_700_000_000);
+        assert_eq!(
+            curve.check_metadata_updatable().unwrap_err(),
+            crate::error::PumpCloneError::MetadataLocked.into()
+        );
+
+        // Selling the curve back down to no real SOL leaves it locked
+        curve.real_sol_reserves = 0;
+        assert!(curve.check_metadata_updatable().is_err());
+
+        // Without the lock, metadata stays editable during trading
+        curve.lock_metadata_on_first_buy = false;
+        assert!(curve.check_metadata_updatable().is_ok());
+    }
+
+    #[test]
+    fn test_pause_blocks_create_trade_and_migrate() {
+        use crate::state::GlobalState;
+
+        let mut global_state = GlobalState { is_paused: true, ..GlobalState::default() };
+        let checks: [fn(&GlobalState) -> Result<()>; 4] = [
+            GlobalState::check_creates_allowed,
+            GlobalState::check_buys_allowed,
+            GlobalState::check_sells_allowed,
+            GlobalState::check_migrations_allowed,
+        ];
+        for check in checks {
+            assert_eq!(
+                check(&global_state).unwrap_err(),
+                crate::error::PumpCloneError::ProgramPaused.into()
+            );
+        }
+
+        global_state.is_paused = false;
+        for check in checks {
+            assert!(check(&global_state).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_per_token_fee_bps_on_buy() {
+        use crate::state::{GlobalState, TradeDirection};
+
+        let global_state = GlobalState {
+            max_fee_bps: GlobalState::DEFAULT_MAX_FEE_BPS,
+            ..GlobalState::default()
+        };
+        // A creator launching with a 0.5% creator fee keeps the default platform fee
+        let creator_fee_bps = global_state.launch_fee_bps(Some(50), CREATOR_FEE_BASIS_POINTS).unwrap();
+        let platform_fee_bps = global_state.launch_fee_bps(None, PLATFORM_FEE_BASIS_POINTS).unwrap();
+        assert_eq!((creator_fee_bps, platform_fee_bps), (50, 100));
+
+        let curve = BondingCurveState::new();
+        let sol_in = LAMPORTS_PER_SOL;
+        let quote = quote_trade(&curve, TradeDirection::Buy, sol_in, creator_fee_bps, platform_fee_bps, 0).unwrap();
+        assert_eq!(quote.creator_fee, sol_in / 200);
+        assert_eq!(quote.platform_fee, sol_in / 100);
+        assert_eq!(quote.sell_tax, 0);
+
+        // Fees above the global cap are rejected
+        assert_eq!(
+            global_state.launch_fee_bps(Some(GlobalState::DEFAULT_MAX_FEE_BPS + 1), 0).unwrap_err(),
+            crate::error::PumpCloneError::InvalidFeePercentage.into()
+        );
+        // A default above a lowered cap is brought down to it
+        let capped = GlobalState { max_fee_bps: 25, ..GlobalState::default() };
+        assert_eq!(capped.launch_fee_bps(None, CREATOR_FEE_BASIS_POINTS).unwrap(), 25);
+    }
+
+    #[test]
+    fn test_configurable_total_supply_scales_curve() {
+        let full_supply = MAX_TOKEN_SUPPLY;
+        let half_supply = MAX_TOKEN_SUPPLY / 2;
+        let curve_for = |supply: u64| BondingCurveState {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: scale_token_reserve(VIRTUAL_TOKEN_RESERVES, supply).unwrap(),
+            real_sol_reserves: 0,
+            real_token_reserves: scale_token_reserve(REAL_TOKEN_RESERVES, supply).unwrap(),
+        };
+        let full = curve_for(full_supply);
+        let half = curve_for(half_supply);
+
+        // The maximum supply keeps the platform defaults
+        assert_eq!(full.virtual_token_reserves, VIRTUAL_TOKEN_RESERVES);
+        assert_eq!(full.real_token_reserves, REAL_TOKEN_RESERVES);
+        assert_eq!(half.virtual_token_reserves, VIRTUAL_TOKEN_RESERVES / 2);
+        assert_eq!(half.real_token_reserves, REAL_TOKEN_RESERVES / 2);
+
+        // Same SOL buys half as many tokens, at the same valuation
+        let sol_in = LAMPORTS_PER_SOL;
+        let full_tokens = full.calculate_tokens_for_sol(sol_in).unwrap();
+        let half_tokens = half.calculate_tokens_for_sol(sol_in).unwrap();
+        assert!(full_tokens / 2 - half_tokens <= 1);
+        assert_eq!(
+            full.get_market_cap_for_supply(full_supply).unwrap(),
+            half.get_market_cap_for_supply(half_supply).unwrap()
+        );
+
+        // Migration seeds 20% of whichever supply was chosen
+        assert_eq!(migration_token_amount(full_supply).unwrap(), full_supply / 5);
+        assert_eq!(migration_token_amount(half_supply).unwrap(), half_supply / 5);
+
+        // Supplies outside (0, MAX_TOKEN_SUPPLY] are rejected
+        assert!(scale_token_reserve(VIRTUAL_TOKEN_RESERVES, 0).is_err());
+        assert!(scale_token_reserve(VIRTUAL_TOKEN_RESERVES, MAX_TOKEN_SUPPLY + 1).is_err());
+        // As are supplies too small to leave any reserve
+        assert!(scale_token_reserve(REAL_TOKEN_RESERVES, 1).is_err());
+    }
+
+    #[test]
+    fn test_two_step_authority_transfer() {
+        use crate::state::GlobalState;
+
+        let authority = Pubkey::new_unique();
+        let typo = Pubkey::new_unique();
+        let successor = Pubkey::new_unique();
+        let mut global_state = GlobalState { authority, ..GlobalState::default() };
+
+        // Only the current authority can propose
+        assert!(global_state.propose_authority(&successor, successor).is_err());
+
+        // A second proposal replaces the first
+        global_state.propose_authority(&authority, typo).unwrap();
+        global_state.propose_authority(&authority, successor).unwrap();
+        assert_eq!(global_state.pending_authority, Some(successor));
+        assert!(global_state.accept_authority(&typo).is_err());
+
+        // A random signer can't accept
+        assert_eq!(
+            global_state.accept_authority(&Pubkey::new_unique()).unwrap_err(),
+            crate::error::PumpCloneError::Unauthorized.into()
+        );
+        assert_eq!(global_state.authority, authority);
+
+        global_state.accept_authority(&successor).unwrap();
+        assert_eq!(global_state.authority, successor);
+        assert_eq!(global_state.pending_authority, None);
+        assert!(global_state.accept_authority(&successor).is_err());
+    }
+
+    #[test]
+    fn test_realized_slippage_from_trade_record() {
+        use crate::state::{TradeDirection, TradeRecord};
+
+        // Replay a 1 SOL buy on the default curve as it would be recorded
+        let curve = BondingCurveState::new();
+        let sol_in = LAMPORTS_PER_SOL;
+        let tokens_out = curve.calculate_tokens_for_sol(sol_in).unwrap();
+        let record = TradeRecord {
+            direction: TradeDirection::Buy,
+            pre_virtual_sol_reserves: curve.virtual_sol_reserves,
+            pre_virtual_token_reserves: curve.virtual_token_reserves,
+            amount_in: sol_in,
+            amount_out: tokens_out,
+        };
+
+        // Independently: spot value of the input vs what was received
+        let at_spot = sol_in as f64 * curve.virtual_token_reserves as f64
+            / curve.virtual_sol_reserves as f64;
+        let expected_bps = (at_spot - tokens_out as f64) / at_spot * 10_000.0;
+        let slippage = realized_slippage_bps(&record).unwrap();
+        assert!((slippage as f64 - expected_bps).abs() <= 1.0, "{} vs {}", slippage, expected_bps);
+        // A 1 SOL buy into 30 SOL of reserves loses about 1/31 to impact
+        assert_eq!(slippage, 322);
+
+        // The same trade read as a sell of the tokens back at the old reserves
+        let sell = TradeRecord {
+            direction: TradeDirection::Sell,
+            amount_in: tokens_out,
+            amount_out: curve.calculate_sell_price(tokens_out).unwrap(),
+            ..record
+        };
+        assert!(realized_slippage_bps(&sell).unwrap() > 0);
+
+        // Filling at or above spot is no slippage
+        let filled_at_spot = TradeRecord { amount_out: at_spot as u64 + 1, ..record };
+        assert_eq!(realized_slippage_bps(&filled_at_spot).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_anti_snipe_wallet_cap() {
+        use crate::state::BondingCurve;
+
+        let curve = BondingCurve {
+            created_at: 1_000,
+            anti_snipe_duration: 30,
+            max_buy_per_wallet: 5_000,
+            ..BondingCurve::default()
+        };
+
+        // Inside the window the wallet's running total is capped
+        assert!(curve.check_anti_snipe_cap(1_010, Some(3_000), 2_000).is_ok());
+        assert_eq!(
+            curve.check_anti_snipe_cap(1_010, Some(3_000), 2_001).unwrap_err(),
+            crate::error::PumpCloneError::MaxPurchaseAmountExceeded.into()
+        );
+        assert!(curve.check_anti_snipe_cap(1_010, None, 1).is_err());
+
+        // The identical buy goes through once the window has passed
+        assert!(curve.check_anti_snipe_cap(1_030, Some(3_000), 2_001).is_ok());
+        assert!(BondingCurve::default().check_anti_snipe_cap(0, None, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_refund_mode_replaces_sells() {
+        use crate::state::BondingCurve;
+
+        // 100 tokens sold for 10 SOL before the curve stalled
+        let mut curve = BondingCurve {
+            initial_real_token_reserves: 1_000,
+            real_token_reserves: 900,
+            real_sol_reserves: 10 * LAMPORTS_PER_SOL,
+            ..BondingCurve::default()
+        };
+        assert!(curve.check_not_in_refund_mode().is_ok());
+        assert!(curve.refund_amount(10).is_err());
+
+        curve.refund_mode = true;
+        assert_eq!(
+            curve.check_not_in_refund_mode().unwrap_err(),
+            crate::error::PumpCloneError::RefundModeActive.into()
+        );
+
+        // Each holder gets the same rate regardless of claim order
+        let first = curve.refund_amount(40).unwrap();
+        assert_eq!(first, 4 * LAMPORTS_PER_SOL);
+        curve.settle_refund(40, first).unwrap();
+        let rest = curve.refund_amount(60).unwrap();
+        assert_eq!(rest, 6 * LAMPORTS_PER_SOL);
+        curve.settle_refund(60, rest).unwrap();
+        assert_eq!(curve.real_sol_reserves, 0);
+        // Refunded tokens were burned, not returned to the curve
+        assert_eq!(curve.real_token_reserves, 900);
+
+        // Nothing left outstanding to redeem
+        assert!(curve.refund_amount(1).is_err());
+    }
+
+    #[test]
+    fn test_refund_rate_excludes_floor_buyback_burns() {
+        use crate::state::BondingCurve;
+
+        // 100 tokens left the curve, but the floor vault bought back and
+        // burned 50 of them, so holders only have 50 to refund
+        let curve = BondingCurve {
+            initial_real_token_reserves: 1_000,
+            real_token_reserves: 900,
+            tokens_burned: 50,
+            real_sol_reserves: 10 * LAMPORTS_PER_SOL,
+            refund_mode: true,
+            ..BondingCurve::default()
+        };
+        assert_eq!(curve.outstanding_tokens(), 50);
+        assert_eq!(curve.refund_amount(50).unwrap(), 10 * LAMPORTS_PER_SOL);
+        assert!(curve.refund_amount(51).is_err());
+    }
+
+    #[test]
+    fn test_withdraw_fees() {
+        use crate::state::GlobalState;
+
+        let authority = Pubkey::new_unique();
+        let mut global_state = GlobalState { authority, ..GlobalState::default() };
+
+        assert_eq!(
+            global_state.check_authority(&Pubkey::new_unique()).unwrap_err(),
+            crate::error::PumpCloneError::Unauthorized.into()
+        );
+        assert!(global_state.check_authority(&authority).is_ok());
+
+        // Two buys' platform fees land in the vault on top of its rent
+        let rent_exempt_minimum = 890_880;
+        let mut vault = rent_exempt_minimum;
+        for fee in [LAMPORTS_PER_SOL / 100, LAMPORTS_PER_SOL / 50] {
+            vault += fee;
+            global_state.record_fees_collected(fee).unwrap();
+        }
+
+        // Withdrawal drains everything above the rent-exempt minimum
+        let amount = withdrawable_fees(vault, rent_exempt_minimum);
+        vault -= amount;
+        global_state.record_fees_withdrawn(amount).unwrap();
+        assert_eq!(vault, rent_exempt_minimum);
+        assert_eq!(global_state.total_fees_withdrawn, global_state.total_fees_collected);
+        assert_eq!(withdrawable_fees(vault, rent_exempt_minimum), 0);
+    }
+
+    #[test]
+    fn test_auto_slippage_scales_with_trade_size() {
+        let reserve = VIRTUAL_SOL_RESERVES;
+        let cap = 1_000;
+
+        let small = auto_slippage_bps(LAMPORTS_PER_SOL / 10, reserve, cap);
+        let large = auto_slippage_bps(3 * LAMPORTS_PER_SOL, reserve, cap);
+        assert!(small >= AUTO_SLIPPAGE_FLOOR_BPS);
+        assert!(large > small);
+        assert!(large <= cap);
+
+        // Trades big enough to move the curve past the cap are held to it
+        assert_eq!(auto_slippage_bps(reserve, reserve, cap), cap);
+        assert_eq!(auto_slippage_bps(1, 0, cap), cap);
+    }
+
+    #[test]
+    fn test_accrued_creator_fees_on_destination_change() {
+        use crate::state::TokenLaunch;
+
+        let creator = Pubkey::new_unique();
+        let new_destination = Pubkey::new_unique();
+        let launch = TokenLaunch { creator, ..TokenLaunch::default() };
+
+        // Accrued fees stay with the old destination under the default policy
+        let mut stays = launch.clone();
+        stays.accrue_creator_fee(100).unwrap();
+        stays.change_fee_destination(new_destination, false).unwrap();
+        stays.accrue_creator_fee(50).unwrap();
+        assert_eq!(stays.fee_destination(), new_destination);
+        assert_eq!((stays.prior_fee_destination, stays.take_prior_creator_fees()), (creator, 100));
+        assert_eq!(stays.take_accrued_creator_fees(), 50);
+
+        // ... or follow the new one when configured
+        let mut follows = launch.clone();
+        follows.accrue_creator_fee(100).unwrap();
+        follows.change_fee_destination(new_destination, true).unwrap();
+        follows.accrue_creator_fee(50).unwrap();
+        assert_eq!(follows.take_prior_creator_fees(), 0);
+        assert_eq!(follows.take_accrued_creator_fees(), 150);
+
+        // A second change can't strand fees still owed to the first destination
+        let mut chained = launch;
+        chained.accrue_creator_fee(100).unwrap();
+        chained.change_fee_destination(new_destination, false).unwrap();
+        chained.accrue_creator_fee(50).unwrap();
+        assert!(chained.change_fee_destination(Pubkey::new_unique(), false).is_err());
+    }
+
+    #[test]
+    fn test_sell_price_uses_full_width_k() {
+        use crate::instructions::sell_tokens::calculate_sell_price;
+
+        let k = VIRTUAL_SOL_RESERVES as u128 * VIRTUAL_TOKEN_RESERVES as u128;
+        assert!(k > u64::MAX as u128);
+
+        // Buy with 1 SOL, then sell the tokens straight back
+        let mut curve = BondingCurveState::new();
+        let sol_in = LAMPORTS_PER_SOL;
+        let tokens = curve.calculate_tokens_for_sol(sol_in).unwrap();
+        curve.virtual_sol_reserves += sol_in;
+        curve.virtual_token_reserves -= tokens;
+
+        let sol_out = calculate_sell_price(
+            curve.virtual_token_reserves,
+            curve.virtual_sol_reserves,
+            tokens,
+            k,
+        )
+        .unwrap();
+
+        // The round trip returns the input less the 1% sell fee, give or
+        // take rounding
+        let expected = sol_in - calculate_fee(sol_in, 100).unwrap();
+        assert!(sol_out <= expected);
+        assert!(expected - sol_out <= 2, "sol_out {} vs {}", sol_out, expected);
+    }
+
+    #[test]
+    fn test_global_kill_switch() {
+        use crate::state::{BondingCurve, GlobalState};
+
+        let global_state = GlobalState {
+            global_kill_switch: true,
+            ..GlobalState::default()
+        };
+
+        // Every mutating path is rejected, migrations included
+        assert!(global_state.check_not_killed().is_err());
+        assert!(global_state.check_buys_allowed().is_err());
+        assert!(global_state.check_sells_allowed().is_err());
+        for flag in [
+            GlobalState::PAUSE_CREATE,
+            GlobalState::PAUSE_BUY,
+            GlobalState::PAUSE_SELL,
+            GlobalState::PAUSE_MIGRATE,
+        ] {
+            assert_eq!(
+                global_state.check_instruction_enabled(flag).unwrap_err(),
+                crate::error::PumpCloneError::KillSwitchEngaged.into()
+            );
+        }
+
+        // Views don't consult the switch
+        let curve = BondingCurve {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            ..BondingCurve::default()
+        };
+        let attestation = curve.attest_reserves(Pubkey::new_unique(), 1);
+        assert_eq!(attestation.slot, 1);
+        assert!(canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).is_ok());
+
+        // Unlike `is_paused`, lifting the soft pause isn't enough
+        assert!(GlobalState { is_paused: false, ..global_state }.check_not_killed().is_err());
+        assert!(GlobalState::default().check_not_killed().is_ok());
+    }
+
+    #[test]
+    fn test_exact_tokens_out_quote_fills_the_requested_amount() {
+        let mut curve = BondingCurveState::new();
+
+        // Odd-sized amounts from dust to large buys, on a curve that moves
+        for step in 0..200u64 {
+            let token_amount = 1 + step * step * 7_919_777_777 % (curve.real_token_reserves / 50);
+            let (cost, _) = curve.quote_exact_tokens_out(token_amount, u64::MAX).unwrap();
+
+            // The buy handler fills through `calculate_buy_amount`; paying the
+            // quoted cost must deliver at least what was asked for, and one
+            // lamport less must not
+            let filled = calculate_buy_amount(
+                cost,
+                curve.virtual_sol_reserves,
+                curve.virtual_token_reserves,
+            )
+            .unwrap();
+            assert!(filled >= token_amount, "{} quoted for {} filled {}", cost, token_amount, filled);
+            if cost > 1 {
+                assert!(
+                    calculate_buy_amount(cost - 1, curve.virtual_sol_reserves, curve.virtual_token_reserves)
+                        .unwrap()
+                        < token_amount
+                );
+            }
+
+            // A cap one lamport under the cost is rejected
+            assert!(curve.quote_exact_tokens_out(token_amount, cost - 1).is_err());
+
+            curve.update_after_buy(cost, filled).unwrap();
+            curve.real_token_reserves -= filled;
+        }
+    }
+
+    #[test]
+    fn test_cancel_launch_before_trading() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve::default();
+        assert!(curve.check_cancellable().is_ok());
+
+        // The first buy puts SOL in the curve and locks the launch in
+        curve.real_sol_reserves = LAMPORTS_PER_SOL / 100;
+        assert_eq!(
+            curve.check_cancellable().unwrap_err(),
+            crate::error::PumpCloneError::TradingAlreadyActive.into()
+        );
+    }
+
+    #[test]
+    fn test_sell_tax_funds_price_floor() {
+        // 5% sell tax, 40% of it to the floor
+        let mut floor_vault = 0;
+        for sol_amount in [LAMPORTS_PER_SOL, 2 * LAMPORTS_PER_SOL] {
+            let (sell_tax, floor_share) = split_sell_tax(sol_amount, 500, 4_000).unwrap();
+            assert_eq!(sell_tax, sol_amount / 20);
+            floor_vault += floor_share;
+        }
+        assert_eq!(floor_vault, 3 * LAMPORTS_PER_SOL / 50);
+
+        // The buyback only fires once the price falls under the floor
+        let curve = BondingCurveState::new();
+        let spot = canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+        let buyback = |floor_price, vault| {
+            floor_buyback_amount(curve.virtual_sol_reserves, curve.virtual_token_reserves, floor_price, vault).unwrap()
+        };
+        assert_eq!(buyback(spot - 1, floor_vault), 0);
+        assert_eq!(buyback(spot, floor_vault), 0);
+        assert_eq!(buyback(0, floor_vault), 0);
+
+        // A vault too small to reach the floor is spent whole
+        assert_eq!(buyback(spot * 2, floor_vault), floor_vault);
+
+        // A larger one only spends what lifts the price to the floor
+        let vault = 100 * LAMPORTS_PER_SOL;
+        let sol_amount = buyback(spot * 2, vault);
+        assert!(sol_amount > 0 && sol_amount < vault);
+        let mut after = curve.clone();
+        let tokens = after.calculate_tokens_for_sol(sol_amount).unwrap();
+        after.virtual_sol_reserves += sol_amount;
+        after.virtual_token_reserves -= tokens;
+        let price_after = canonical_price(after.virtual_sol_reserves, after.virtual_token_reserves).unwrap();
+        assert!(price_after <= spot * 2 && price_after > spot * 2 - spot / 1_000);
+
+        // Combined with the default 1% creator and 1% platform fees
+        assert!(combined_sell_fees_within_cap(1_800, 100, 100));
+        assert!(!combined_sell_fees_within_cap(1_801, 100, 100));
+
+        // Free to set before trading, only lowerable after
+        let mut launch = crate::state::TokenLaunch::default();
+        launch.set_sell_tax(500, 4_000, spot, false).unwrap();
+        launch.set_sell_tax(800, 4_000, spot * 2, false).unwrap();
+        for (sell_tax_bps, floor_price) in [(801, spot * 2), (800, spot * 2 + 1)] {
+            assert_eq!(
+                launch.set_sell_tax(sell_tax_bps, 4_000, floor_price, true).unwrap_err(),
+                crate::error::PumpCloneError::SellTaxLocked.into()
+            );
+        }
+        launch.set_sell_tax(300, 10_000, spot, true).unwrap();
+        assert_eq!((launch.sell_tax_bps, launch.floor_price), (300, spot));
+    }
+
+    #[test]
+    fn test_vault_dust_on_close() {
+        let rent_exempt_minimum = 890_880;
+
+        // Dust below rent exemption goes to the treasury when configured
+        assert_eq!(vault_close_payouts(5_000, rent_exempt_minimum, true), (0, 5_000));
+        assert_eq!(vault_close_payouts(5_000, rent_exempt_minimum, false), (5_000, 0));
+
+        // Rent-backed balances still refund the closer
+        assert_eq!(vault_close_payouts(rent_exempt_minimum, rent_exempt_minimum, true), (rent_exempt_minimum, 0));
+
+        // Only the rent comes back to the closer; leftover SOL goes to the treasury
+        for dust_to_treasury in [true, false] {
+            assert_eq!(
+                vault_close_payouts(10 * rent_exempt_minimum, rent_exempt_minimum, dust_to_treasury),
+                (rent_exempt_minimum, 9 * rent_exempt_minimum)
+            );
+        }
+
+        // Nothing is left behind in the vault
+        for balance in [0, 1, 5_000, rent_exempt_minimum, 10 * rent_exempt_minimum] {
+            let (to_closer, to_treasury) = vault_close_payouts(balance, rent_exempt_minimum, true);
+            assert_eq!(to_closer + to_treasury, balance);
+        }
+    }
+
+    #[test]
+    fn test_all_time_high_price() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve::default();
+        for price in [100, 150, 220, 180, 120] {
+            curve.record_price_high(price);
+        }
+        assert_eq!(curve.ath_price, 220);
+
+        // 120 is ~45% below the 220 high
+        assert_eq!(curve.drawdown_from_ath_bps(120), 4_545);
+        assert_eq!(curve.drawdown_from_ath_bps(220), 0);
+        assert_eq!(BondingCurve::default().drawdown_from_ath_bps(100), 0);
+    }
+
+    #[test]
+    fn test_same_slot_round_trip() {
+        use crate::state::UserPosition;
+
+        let position = UserPosition {
+            buy_count: 1,
+            last_buy_slot: 1_000,
+            ..UserPosition::default()
+        };
+
+        // Selling in the slot of the buy is an intra-block round trip
+        assert!(position.check_same_slot_round_trip(1_000).is_err());
+        // The next slot is fine
+        assert!(position.check_same_slot_round_trip(1_001).is_ok());
+        // A wallet that never bought has no buy slot to collide with
+        assert!(UserPosition::default().check_same_slot_round_trip(0).is_ok());
+    }
+
+    #[test]
+    fn test_sold_fraction_graduation() {
+        use crate::state::BondingCurve;
+
+        let float = 800_000_000_000_000;
+        let mut curve = BondingCurve {
+            initial_real_token_reserves: float,
+            real_token_reserves: float,
+            max_sold_before_graduation_bps: 5_000,
+            ..BondingCurve::default()
+        };
+        assert!(!curve.reached_sold_target());
+
+        // Just short of half the float sold
+        curve.real_token_reserves = float / 2 + 1;
+        assert!(!curve.reached_sold_target());
+
+        // Crossing the threshold reaches the target
+        curve.real_token_reserves = float / 2;
+        assert!(curve.reached_sold_target());
+
+        curve.max_sold_before_graduation_bps = 0;
+        assert!(!curve.reached_sold_target());
+    }
+
+    #[test]
+    fn test_sold_target_waits_for_migration_threshold() {
+        use crate::state::BondingCurve;
+
+        let migration_threshold = 5 * LAMPORTS_PER_SOL;
+        let mut curve = BondingCurve {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            initial_real_token_reserves: REAL_TOKEN_RESERVES,
+            real_token_reserves: REAL_TOKEN_RESERVES / 2,
+            max_sold_before_graduation_bps: 5_000,
+            ..BondingCurve::default()
+        };
+        assert!(curve.reached_sold_target());
+
+        let completed = curve
+            .settle_buy(LAMPORTS_PER_SOL, 1_000, LAMPORTS_PER_SOL, REAL_SOL_RESERVES, migration_threshold, 0)
+            .unwrap();
+        assert!(completed.is_none());
+        assert!(curve.begin_migration(migration_threshold).is_err());
+
+        let completed = curve
+            .settle_buy(4 * LAMPORTS_PER_SOL, 1_000, 4 * LAMPORTS_PER_SOL, REAL_SOL_RESERVES, migration_threshold, 0)
+            .unwrap();
+        assert!(completed.is_some());
+        curve.graduated_slot = 1;
+        assert!(curve.begin_migration(migration_threshold).is_ok());
+    }
+
+    #[test]
+    fn test_attest_reserves() {
+        use crate::state::BondingCurve;
+
+        let key = Pubkey::new_unique();
+        let curve = BondingCurve {
+            real_sol_reserves: 12 * LAMPORTS_PER_SOL,
+            real_token_reserves: 700_000_000_000_000,
+            ..BondingCurve::default()
+        };
+        let slot = 250_000_000;
+
+        let attestation = curve.attest_reserves(key, slot);
+        assert_eq!(attestation.bonding_curve, key);
+        assert_eq!(attestation.real_sol_reserves, curve.real_sol_reserves);
+        assert_eq!(attestation.real_token_reserves, curve.real_token_reserves);
+        assert_eq!(attestation.slot, slot);
+
+        // Deterministic for the same inputs, distinct for another slot
+        assert_eq!(curve.attest_reserves(key, slot).digest, attestation.digest);
+        assert_ne!(curve.attest_reserves(key, slot + 1).digest, attestation.digest);
+    }
+
+    #[test]
+    fn test_fee_tier_boundaries() {
+        use crate::state::FeeTier;
+
+        // Stored out of order on purpose; selection must not depend on it
+        let tiers = [
+            FeeTier { min_trade_size: 10 * LAMPORTS_PER_SOL, fee_bps: 50 },
+            FeeTier::default(),
+            FeeTier { min_trade_size: LAMPORTS_PER_SOL, fee_bps: 80 },
+            FeeTier::default(),
+        ];
+
+        assert_eq!(fee_tier_bps(&tiers, LAMPORTS_PER_SOL - 1, 100), 100);
+        // Exactly on a boundary: the inclusive lower bound puts it in the upper tier
+        assert_eq!(fee_tier_bps(&tiers, LAMPORTS_PER_SOL, 100), 80);
+        assert_eq!(fee_tier_bps(&tiers, 10 * LAMPORTS_PER_SOL - 1, 100), 80);
+        assert_eq!(fee_tier_bps(&tiers, 10 * LAMPORTS_PER_SOL, 100), 50);
+
+        // Two traders of the same size get the same fee whatever the tier order
+        let mut reversed = tiers;
+        reversed.reverse();
+        assert_eq!(
+            fee_tier_bps(&reversed, 10 * LAMPORTS_PER_SOL, 100),
+            fee_tier_bps(&tiers, 10 * LAMPORTS_PER_SOL, 100)
+        );
+
+        assert!(validate_fee_tiers(&tiers));
+        let duplicate = [tiers[0], FeeTier { fee_bps: 60, ..tiers[0] }];
+        assert!(!validate_fee_tiers(&duplicate));
+    }
+
+    #[test]
+    fn test_compounding_reward_claim() {
+        use crate::state::UserPosition;
+
+        let reward = LAMPORTS_PER_SOL / 10;
+        let start = UserPosition {
+            token_balance: 1_000_000,
+            tokens_bought: 1_000_000,
+            sol_invested: LAMPORTS_PER_SOL,
+            ..UserPosition::default()
+        };
+
+        // Compounding buys tokens with the reward and grows the position
+        let tokens = BondingCurveState::new().calculate_tokens_for_sol(reward).unwrap();
+        let mut compounding = start.clone();
+        compounding.credit_compounded_reward(tokens, reward).unwrap();
+        assert_eq!(compounding.token_balance, start.token_balance + tokens);
+        assert_eq!(compounding.sol_invested, start.sol_invested + reward);
+
+        assert!(tokens > 0);
+    }
+
+    #[test]
+    fn test_lp_yield_estimate() {
+        // 1 SOL of fees on 100 SOL locked over a quarter year is ~4% APY
+        let quarter = SECONDS_PER_YEAR / 4;
+        assert_eq!(annualized_yield_bps(LAMPORTS_PER_SOL, 100 * LAMPORTS_PER_SOL, quarter), 400);
+
+        // The same fees over a full year
+        assert_eq!(annualized_yield_bps(LAMPORTS_PER_SOL, 100 * LAMPORTS_PER_SOL, SECONDS_PER_YEAR), 100);
+
+        // A lock taken this second, or an empty one, has no yield yet
+        assert_eq!(annualized_yield_bps(LAMPORTS_PER_SOL, 100 * LAMPORTS_PER_SOL, 0), 0);
+        assert_eq!(annualized_yield_bps(LAMPORTS_PER_SOL, 0, quarter), 0);
+    }
+
+    #[test]
+    fn test_lp_position_yield() {
+        use crate::state::LpLock;
+
+        let (seed_sol, seed_tokens) = (80 * LAMPORTS_PER_SOL, 200_000_000 * 1_000_000);
+        let lock = LpLock {
+            lp_amount: 1_000_000,
+            seed_sol_amount: seed_sol,
+            seed_token_amount: seed_tokens,
+            seed_lp_supply: 1_000_000,
+            ..LpLock::default()
+        };
+
+        // Untouched pool: the whole pool is the lock's, and nothing earned
+        let (value, fees) = lp_position_yield(&lock, seed_sol, seed_tokens, 1_000_000).unwrap();
+        assert_eq!((value, fees), (2 * seed_sol, 0));
+
+        // Fees left in the pool grow both sides by 1%; the growth in the
+        // invariant root is what the position earned
+        let (value, fees) =
+            lp_position_yield(&lock, seed_sol * 101 / 100, seed_tokens * 101 / 100, 1_000_000).unwrap();
+        assert_eq!(value, 2 * seed_sol * 101 / 100);
+        assert!(fees.abs_diff(value / 101) <= 1);
+
+        // A new LP doubling the pool isn't fee income for the lock
+        let (value, fees) = lp_position_yield(&lock, 2 * seed_sol, 2 * seed_tokens, 2_000_000).unwrap();
+        assert_eq!((value, fees), (2 * seed_sol, 0));
+
+        // A lock recorded before its LP supply was known reports nothing
+        let fresh = LpLock { seed_lp_supply: 0, ..lock };
+        assert_eq!(lp_position_yield(&fresh, seed_sol, seed_tokens, 1_000_000).unwrap(), (0, 0));
+    }
+
+    #[test]
+    fn test_governance_fee_discount() {
+        let fee = 1_000_000;
+        let min_balance = 1_000 * 1_000_000;
+
+        // A holder above the threshold gets 25% off
+        assert_eq!(governance_discounted_fee(fee, 2_500, Some(min_balance), min_balance).unwrap(), 750_000);
+
+        // Below the threshold, or with no account supplied, the full fee is due
+        assert_eq!(governance_discounted_fee(fee, 2_500, Some(min_balance - 1), min_balance).unwrap(), fee);
+        assert_eq!(governance_discounted_fee(fee, 2_500, None, min_balance).unwrap(), fee);
+    }
+
+    #[test]
+    fn test_pause_single_instruction() {
+        use crate::state::GlobalState;
+
+        let global_state = GlobalState {
+            paused_instructions: GlobalState::PAUSE_BUY,
+            ..GlobalState::default()
+        };
+
+        assert!(global_state.check_buys_allowed().is_err());
+        assert!(global_state.check_sells_allowed().is_ok());
+        assert!(global_state.check_instruction_enabled(GlobalState::PAUSE_CREATE).is_ok());
+        assert!(global_state.check_instruction_enabled(GlobalState::PAUSE_MIGRATE).is_ok());
+
+        // Clearing the bit restores buys
+        assert!(GlobalState::default().check_buys_allowed().is_ok());
+    }
+
+    #[test]
+    fn test_bootstrap_configures_once() {
+        use crate::state::{BootstrapConfig, GlobalState};
+
+        let authority = Pubkey::new_unique();
+        let config = BootstrapConfig {
+            fee_recipient: Pubkey::new_unique(),
+            platform_fee_bps: 100,
+            creator_fee_bps: 50,
+            migration_threshold: 85 * LAMPORTS_PER_SOL,
+            min_sol_threshold: LAMPORTS_PER_SOL,
+            max_sol_threshold: 100 * LAMPORTS_PER_SOL,
+        };
+
+        let mut global_state = GlobalState::default();
+        global_state.bootstrap(authority, &config, 254).unwrap();
+        assert_eq!(global_state.authority, authority);
+        assert_eq!(global_state.fee_recipient, config.fee_recipient);
+        assert_eq!(global_state.platform_fee_bps, 100);
+        assert_eq!(global_state.creator_fee_bps, 50);
+        assert_eq!(global_state.migration_threshold, 85 * LAMPORTS_PER_SOL);
+        assert_eq!(global_state.max_sol_threshold, 100 * LAMPORTS_PER_SOL);
+        assert!(global_state.allow_creator_initial_buy);
+        assert_eq!(global_state.bump, 254);
+
+        // A second run can't overwrite the live config
+        let hijack = BootstrapConfig { fee_recipient: Pubkey::new_unique(), ..config.clone() };
+        let err = global_state.bootstrap(Pubkey::new_unique(), &hijack, 254).unwrap_err();
+        assert_eq!(err, crate::error::PumpCloneError::AccountAlreadyInitialized.into());
+        assert_eq!(global_state.authority, authority);
+
+        let inverted = BootstrapConfig { min_sol_threshold: 2, max_sol_threshold: 1, ..config };
+        assert!(GlobalState::default().bootstrap(authority, &inverted, 254).is_err());
+    }
+
+    #[test]
+    fn test_sell_extraction_cap() {
+        use crate::state::UserPosition;
+
+        let tokens = 1_000_000;
+        let mut position = UserPosition {
+            sol_invested: LAMPORTS_PER_SOL,
+            token_balance: 3 * tokens,
+            ..UserPosition::default()
+        };
+
+        // 2x cap on 1 SOL invested
+        assert!(position.check_sell_extraction(tokens, LAMPORTS_PER_SOL, 20_000).is_ok());
+        position.sol_extracted = LAMPORTS_PER_SOL;
+        assert!(position.check_sell_extraction(tokens, LAMPORTS_PER_SOL, 20_000).is_ok());
+        position.sol_extracted = 2 * LAMPORTS_PER_SOL;
+
+        // Cumulative proceeds have hit the cap: any further sell is rejected
+        assert!(position.check_sell_extraction(tokens, 1, 20_000).is_err());
+
+        // Disabled cap never rejects
+        assert!(position.check_sell_extraction(tokens, LAMPORTS_PER_SOL, 0).is_ok());
+
+        // Only proceeds from tokens the position holds count: selling twice
+        // the tracked balance books half the proceeds
+        position.token_balance = tokens;
+        assert_eq!(position.tracked_proceeds(2 * tokens, LAMPORTS_PER_SOL), LAMPORTS_PER_SOL / 2);
+
+        // A wallet that never bought from the curve isn't capped at zero
+        let untracked = UserPosition::default();
+        assert_eq!(untracked.tracked_proceeds(tokens, LAMPORTS_PER_SOL), 0);
+        assert!(untracked.check_sell_extraction(tokens, LAMPORTS_PER_SOL, 20_000).is_ok());
+    }
+
+    #[test]
+    fn test_calculate_slippage() {
+        let price = 2 * PRICE_SCALE;
+
+        // Paying 1% over the reference price
+        assert_eq!(calculate_slippage(2_020, 1_000, price).unwrap(), 100);
+        // Filling at or under the reference is no slippage
+        assert_eq!(calculate_slippage(2_000, 1_000, price).unwrap(), 0);
+        assert_eq!(calculate_slippage(1_500, 1_000, price).unwrap(), 0);
+
+        // A buy that gets no tokens, or a missing reference, can't be measured
+        assert!(calculate_slippage(2_000, 0, price).is_err());
+        assert!(calculate_slippage(2_000, 1_000, 0).is_err());
+    }
+
+    #[test]
+    fn test_slippage_reference_price() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            ..BondingCurve::default()
+        };
+        let creation_price = canonical_price(VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES).unwrap();
+
+        // The first trade has no TWAP yet and is measured against the
+        // creation price: a small buy moves it by a fraction of a percent
+        // instead of "infinitely"
+        let now = 1_700_000_000;
+        curve.update_twap(now).unwrap();
+        let reference = curve.slippage_reference_price(now).unwrap();
+        assert_eq!(reference, creation_price);
+        let sol_amount = LAMPORTS_PER_SOL / 10;
+        let token_amount = VIRTUAL_TOKEN_RESERVES
+            - safe_mul_div(VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES, VIRTUAL_SOL_RESERVES + sol_amount).unwrap();
+        let impact_bps = calculate_slippage(sol_amount, token_amount, reference).unwrap();
+        assert!(impact_bps > 0 && impact_bps < 100);
+
+        // Once time has been observed both sides use the TWAP, so a price
+        // pumped just before a buy (or dumped before a sell) doesn't become
+        // the reference
+        curve.update_twap(now + 600).unwrap();
+        curve.virtual_sol_reserves = VIRTUAL_SOL_RESERVES * 3 / 2;
+        curve.update_twap(now + 601).unwrap();
+        let reference = curve.slippage_reference_price(now + 601).unwrap();
+        assert_eq!(reference, curve.twap_price(now + 601).unwrap().unwrap());
+        assert!(within_price_band(creation_price, reference, 100));
+    }
+
+    #[test]
+    fn test_rescue_orphaned_curve_ata() {
+        let program_id = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (live_curve, _) =
+            Pubkey::find_program_address(&[b"bonding_curve", mint.as_ref()], &program_id);
+
+        // An ATA left behind under the old seed prefix
+        let (orphaned_curve, bump) =
+            Pubkey::find_program_address(&[b"bonding-curve", mint.as_ref()], &program_id);
+        let orphaned_ata = anchor_spl::associated_token::get_associated_token_address(&orphaned_curve, &mint);
+        assert_ne!(
+            orphaned_ata,
+            anchor_spl::associated_token::get_associated_token_address(&live_curve, &mint)
+        );
+
+        // The legacy seed reproduces the orphaned owner, so the program can sign for it
+        assert_eq!(
+            legacy_curve_address(b"bonding-curve", &mint, &program_id, &live_curve),
+            Some((orphaned_curve, bump))
+        );
+        let signer = Pubkey::create_program_address(&[b"bonding-curve", mint.as_ref(), &[bump]], &program_id);
+        assert_eq!(signer.unwrap(), orphaned_curve);
+
+        // The live seeds, other live PDA prefixes and arbitrary prefixes are refused
+        assert_eq!(legacy_curve_address(b"bonding_curve", &mint, &program_id, &live_curve), None);
+        assert_eq!(legacy_curve_address(b"vesting", &mint, &program_id, &live_curve), None);
+        assert_eq!(legacy_curve_address(b"sol_vault", &mint, &program_id, &live_curve), None);
+        assert_eq!(legacy_curve_address(b"", &mint, &program_id, &live_curve), None);
+        assert_eq!(legacy_curve_address(&[0u8; 33], &mint, &program_id, &live_curve), None);
+    }
+
+    #[test]
+    fn test_reserve_ratio_catches_inconsistent_update() {
+        use crate::state::BondingCurve;
+
+        let launched = BondingCurve {
+            virtual_sol_reserves: 30 * LAMPORTS_PER_SOL,
+            virtual_token_reserves: 1_073_000_000_000_000,
+            real_token_reserves: 793_100_000_000_000,
+            initial_virtual_sol_reserves: 30 * LAMPORTS_PER_SOL,
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            ..BondingCurve::default()
+        };
+        assert!(launched.check_reserve_ratio().is_ok());
+
+        // A buy that moves both sides together keeps the gap
+        let mut curve = launched.clone();
+        curve.virtual_sol_reserves += LAMPORTS_PER_SOL;
+        curve.real_sol_reserves += LAMPORTS_PER_SOL;
+        curve.virtual_token_reserves -= 1_000_000;
+        curve.real_token_reserves -= 1_000_000;
+        assert!(curve.check_reserve_ratio().is_ok());
+
+        // Crediting real SOL twice leaves the virtual side behind
+        let mut double_counted = curve.clone();
+        double_counted.real_sol_reserves += LAMPORTS_PER_SOL;
+        assert!(double_counted.check_reserve_ratio().is_err());
+
+        // Tokens released from real reserves but not from virtual ones
+        let mut skipped_virtual = curve.clone();
+        skipped_virtual.real_token_reserves -= 1_000_000;
+        assert!(skipped_virtual.check_reserve_ratio().is_err());
+
+        // A curve tolerating 5% drift accepts a small discrepancy
+        double_counted.reserve_drift_tolerance_bps = 500;
+        assert!(double_counted.check_reserve_ratio().is_ok());
+
+        // Real reserves above virtual ones are always rejected
+        let legacy = BondingCurve {
+            virtual_sol_reserves: LAMPORTS_PER_SOL,
+            real_sol_reserves: 2 * LAMPORTS_PER_SOL,
+            virtual_token_reserves: 1,
+            ..BondingCurve::default()
+        };
+        assert!(legacy.check_reserve_ratio().is_err());
+    }
+
+    #[test]
+    fn test_reserve_ratio_survives_add_liquidity() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            virtual_sol_reserves: 31 * LAMPORTS_PER_SOL,
+            real_sol_reserves: LAMPORTS_PER_SOL,
+            virtual_token_reserves: 1_000_000_000_000_000,
+            real_token_reserves: 720_100_000_000_000,
+            initial_virtual_sol_reserves: 30 * LAMPORTS_PER_SOL,
+            initial_virtual_token_reserves: 1_073_000_000_000_000,
+            initial_real_token_reserves: 793_100_000_000_000,
+            ..BondingCurve::default()
+        };
+        assert_eq!(curve.reserve_drift_tolerance_bps, 0);
+        assert!(curve.check_reserve_ratio().is_ok());
+
+        curve.add_liquidity(5 * LAMPORTS_PER_SOL).unwrap();
+        assert!(curve.check_reserve_ratio().is_ok());
+
+        // The new baseline still catches a one-sided update
+        let mut after_buy = curve.clone();
+        after_buy.virtual_sol_reserves += LAMPORTS_PER_SOL;
+        after_buy.real_sol_reserves += LAMPORTS_PER_SOL;
+        after_buy.virtual_token_reserves -= 1_000_000;
+        after_buy.real_token_reserves -= 1_000_000;
+        assert!(after_buy.check_reserve_ratio().is_ok());
+        after_buy.real_sol_reserves += LAMPORTS_PER_SOL;
+        assert!(after_buy.check_reserve_ratio().is_err());
+    }
+
+    #[test]
+    fn test_atomic_round_trip_detection() {
+        use crate::instruction::{BuyExactTokens, BuyTokens, BuyTokensBatch, SellTokens, SellTokensWithSlippage};
+        use anchor_lang::Discriminator;
+
+        let program_id = Pubkey::new_unique();
+        let trader = Pubkey::new_unique();
+        let curve = Pubkey::new_unique();
+        let trade = |discriminator: [u8; 8], trader: Pubkey| Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(trader, true),
+                AccountMeta::new(curve, false),
+            ],
+            data: discriminator.to_vec(),
+        };
+
+        // Any buy paired with any sell of the same curve in one tx
+        for buy in [BuyTokens::DISCRIMINATOR, BuyExactTokens::DISCRIMINATOR, BuyTokensBatch::DISCRIMINATOR] {
+            for sell in [SellTokens::DISCRIMINATOR, SellTokensWithSlippage::DISCRIMINATOR] {
+                let atomic = vec![trade(buy, trader), trade(sell, trader)];
+                assert!(is_atomic_round_trip(&atomic, 0, &program_id, &SELL_DISCRIMINATORS, &trader, &curve));
+                assert!(is_atomic_round_trip(&atomic, 1, &program_id, &BUY_DISCRIMINATORS, &trader, &curve));
+            }
+        }
+
+        // Two buys are not a round trip
+        let buys = vec![trade(BuyTokens::DISCRIMINATOR, trader), trade(BuyExactTokens::DISCRIMINATOR, trader)];
+        assert!(!is_atomic_round_trip(&buys, 0, &program_id, &SELL_DISCRIMINATORS, &trader, &curve));
+
+        // A transaction carrying only the buy is a separate trade
+        let single = vec![trade(BuyTokens::DISCRIMINATOR, trader)];
+        assert!(!is_atomic_round_trip(&single, 0, &program_id, &SELL_DISCRIMINATORS, &trader, &curve));
+
+        // Another wallet selling in the same transaction is not a round trip
+        let other = vec![
+            trade(BuyTokens::DISCRIMINATOR, trader),
+            trade(SellTokensWithSlippage::DISCRIMINATOR, Pubkey::new_unique()),
+        ];
+        assert!(!is_atomic_round_trip(&other, 0, &program_id, &SELL_DISCRIMINATORS, &trader, &curve));
+    }
+
+    #[test]
+    fn test_market_maker_bypasses_wallet_throttle() {
+        use crate::state::{GlobalState, MarketMaker, UserPosition};
+
+        let global_state = GlobalState {
+            wallet_trade_cooldown_secs: 60,
+            wallet_daily_sol_limit: 10 * LAMPORTS_PER_SOL,
+            ..GlobalState::default()
+        };
+        let market_maker = MarketMaker { is_active: true, ..MarketMaker::default() };
+
+        assert!(global_state.wallet_throttle_applies(None).unwrap());
+        assert!(!global_state.wallet_throttle_applies(Some(&market_maker)).unwrap());
+
+        // A deactivated registration gives no exemption
+        let retired = MarketMaker { is_active: false, ..MarketMaker::default() };
+        assert!(global_state.wallet_throttle_applies(Some(&retired)).is_err());
+
+        // A normal wallet is held to the cooldown and the daily limit
+        let now = 1_700_000_000;
+        let mut position = UserPosition { last_trade_timestamp: now - 30, ..UserPosition::default() };
+        assert!(position.check_wallet_throttle(now, LAMPORTS_PER_SOL, 60, 10 * LAMPORTS_PER_SOL).is_err());
+        position.last_trade_timestamp = now - 60;
+        assert!(position.check_wallet_throttle(now, 6 * LAMPORTS_PER_SOL, 60, 10 * LAMPORTS_PER_SOL).is_ok());
+        position.last_trade_timestamp = now - 120;
+        assert!(position.check_wallet_throttle(now, 6 * LAMPORTS_PER_SOL, 60, 10 * LAMPORTS_PER_SOL).is_err());
+        assert_eq!(position.daily_volume, 6 * LAMPORTS_PER_SOL);
+
+        // The volume window rolls over with the day
+        let tomorrow = now + UserPosition::SECONDS_PER_DAY;
+        assert!(position.check_wallet_throttle(tomorrow, 6 * LAMPORTS_PER_SOL, 60, 10 * LAMPORTS_PER_SOL).is_ok());
+
+        // With nothing configured the throttle never applies
+        assert!(!GlobalState::default().wallet_throttle_applies(None).unwrap());
+    }
+
+    #[test]
+    fn test_close_migrated_curve_guard() {
+        use crate::state::TokenLaunch;
+
+        let creator = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let mut launch = TokenLaunch {
+            creator,
+            ..TokenLaunch::default()
+        };
+
+        // Still trading on the curve: nothing can be closed
+        assert!(launch.check_curve_closable(&creator, &authority).is_err());
+
+        // Migrated: creator or platform authority may reclaim the rent
+        launch.is_migrated = true;
+        assert!(launch.check_curve_closable(&creator, &authority).is_ok());
+        assert!(launch.check_curve_closable(&authority, &authority).is_ok());
+        assert!(launch.check_curve_closable(&Pubkey::new_unique(), &authority).is_err());
+
+        // Closing would forget fees still owed to either destination
+        launch.accrued_creator_fees = 1;
+        assert!(launch.check_curve_closable(&creator, &authority).is_err());
+        launch.accrued_creator_fees = 0;
+        launch.prior_accrued_creator_fees = 1;
+        assert!(launch.check_curve_closable(&creator, &authority).is_err());
+    }
+
+    #[test]
+    fn test_min_initial_virtual_sol_reserves() {
+        let min = 20 * LAMPORTS_PER_SOL;
+
+        // Below the minimum is rejected, at or above it is accepted
+        assert!(!meets_min_initial_reserves(10 * LAMPORTS_PER_SOL, min));
+        assert!(meets_min_initial_reserves(min, min));
+        assert!(meets_min_initial_reserves(VIRTUAL_SOL_RESERVES, min));
+
+        // A zero-liquidity curve is never accepted, even with no minimum set
+        assert!(!meets_min_initial_reserves(0, 0));
+    }
+
+    #[test]
+    fn test_renounce_authorities_after_migration() {
+        let curve = Pubkey::new_unique();
+
+        // Flag set: both authorities held by the curve end up null
+        assert_eq!(
+            authorities_to_renounce(true, &curve, Some(curve), Some(curve)),
+            vec![AuthorityType::MintTokens, AuthorityType::FreezeAccount]
+        );
+
+        // Already-null authorities are skipped
+        assert_eq!(
+            authorities_to_renounce(true, &curve, Some(curve), None),
+            vec![AuthorityType::MintTokens]
+        );
+
+        // Flag cleared: authorities are left in place
+        assert!(authorities_to_renounce(false, &curve, Some(curve), Some(curve)).is_empty());
+    }
+
+    #[test]
+    fn test_sells_only_mode() {
+        use crate::state::GlobalState;
+
+        let mut global_state = GlobalState::default();
+
+        // Normal mode allows both
+        assert!(global_state.check_buys_allowed().is_ok());
+        assert!(global_state.check_sells_allowed().is_ok());
+
+        // Sells-only rejects buys but keeps the exit open
+        global_state.sells_only = true;
+        assert!(global_state.check_buys_allowed().is_err());
+        assert!(global_state.check_sells_allowed().is_ok());
+
+        // A full pause still blocks everything
+        global_state.is_paused = true;
+        assert!(global_state.check_sells_allowed().is_err());
+    }
+
+    #[test]
+    fn test_price_in_quote_micro_units() {
+        // 6-decimal mint quoted in SOL: 1 SOL buys 1,000 whole tokens
+        let price = price_in_quote_micro_units(LAMPORTS_PER_SOL, 1_000 * 1_000_000, 6, SOL_DECIMALS).unwrap();
+        assert_eq!(price, 1_000); // 0.001 SOL
+
+        // Same ratio on a 9-decimal mint
+        let price = price_in_quote_micro_units(LAMPORTS_PER_SOL, 1_000 * 1_000_000_000, 9, SOL_DECIMALS).unwrap();
+        assert_eq!(price, 1_000);
+
+        // USDC quote: 10 USDC of reserves against 800,000 whole tokens
+        // gives $0.0000125 per token
+        let price = price_in_quote_micro_units(10 * 1_000_000, 800_000 * 1_000_000, 6, 6).unwrap();
+        assert_eq!(price, 12);
+        let price = price_in_quote_micro_units(10 * 1_000_000, 800_000 * 1_000_000_000, 9, 6).unwrap();
+        assert_eq!(price, 12);
+    }
+
+    #[test]
+    fn test_fully_diluted_valuation() {
+        let total_supply = MAX_TOKEN_SUPPLY;
+        let fdv = spot_value(VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES, total_supply).unwrap();
+
+        // 30 SOL * 1B / 1.073B tokens
+        assert_eq!(fdv, 27_958_993_476);
+
+        // Matches price x supply up to the price's own rounding
+        let price = canonical_price(VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES).unwrap();
+        let via_price = (price as u128 * total_supply as u128 / PRICE_SCALE as u128) as u64;
+        assert!(fdv >= via_price && fdv - via_price <= total_supply / PRICE_SCALE);
+
+        // Circulating market cap only counts tokens outside the curve
+        let market_cap = spot_value(VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES, total_supply / 4).unwrap();
+        assert!(market_cap < fdv);
+    }
+
+    #[test]
+    fn test_frozen_token_account_detected() {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token::spl_token::state::{Account, AccountState};
+
+        let mut account = Account {
+            mint: Pubkey::new_unique(),
+            owner: Pubkey::new_unique(),
+            amount: 1_000,
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        let mut data = [0u8; Account::LEN];
+
+        Account::pack(account, &mut data).unwrap();
+        assert!(!is_token_account_frozen(&data));
+
+        account.state = AccountState::Frozen;
+        Account::pack(account, &mut data).unwrap();
+        assert!(is_token_account_frozen(&data));
+
+        assert!(!is_token_account_frozen(&[]));
+    }
+
+    #[test]
+    fn test_default_slippage_from_user_settings() {
+        // Sentinel falls back to the stored default
+        assert_eq!(resolve_max_slippage_bps(USE_DEFAULT_SLIPPAGE, Some(150)).unwrap(), 150);
+        // An explicit per-trade value overrides it
+        assert_eq!(resolve_max_slippage_bps(500, Some(150)).unwrap(), 500);
+        assert_eq!(resolve_max_slippage_bps(500, None).unwrap(), 500);
+        // No settings: the sentinel is an error, not unbounded slippage
+        assert_eq!(
+            resolve_max_slippage_bps(USE_DEFAULT_SLIPPAGE, None).unwrap_err(),
+            crate::error::PumpCloneError::DefaultSlippageNotSet.into()
+        );
+
+        // Sells: 1,000 tokens at 2 SOL-per-token reference, 1% default
+        let price = 2 * PRICE_SCALE;
+        assert_eq!(
+            resolve_min_sol_output(USE_DEFAULT_SLIPPAGE, Some(100), 1_000, price).unwrap(),
+            1_980
+        );
+        assert_eq!(resolve_min_sol_output(1_500, Some(100), 1_000, price).unwrap(), 1_500);
+        assert_eq!(
+            resolve_min_sol_output(USE_DEFAULT_SLIPPAGE, None, 1_000, price).unwrap_err(),
+            crate::error::PumpCloneError::DefaultSlippageNotSet.into()
+        );
+    }
+
+    #[test]
+    fn test_referral_buy_allowance() {
+        let base = 5 * LAMPORTS_PER_SOL;
+        let bonus = LAMPORTS_PER_SOL / 2;
+
+        let without_referrals = max_buy_allowance(base, bonus, 0);
+        let with_referrals = max_buy_allowance(base, bonus, 4);
+
+        assert_eq!(without_referrals, base);
+        assert_eq!(with_referrals, 7 * LAMPORTS_PER_SOL);
+        assert!(with_referrals > without_referrals);
+
+        // The bonus stops growing past the referral cap
+        let capped = max_buy_allowance(base, bonus, MAX_BONUS_REFERRALS);
+        assert_eq!(capped, base + bonus * MAX_BONUS_REFERRALS as u64);
+        assert_eq!(max_buy_allowance(base, bonus, MAX_BONUS_REFERRALS + 1), capped);
+        assert_eq!(max_buy_allowance(base, bonus, u32::MAX), capped);
+
+        // No base cap configured
+        assert_eq!(max_buy_allowance(0, bonus, 4), u64::MAX);
+    }
+
+    #[test]
+    fn test_creator_initial_buy_flag() {
+        // Flag off: a nonzero initial buy is rejected, a plain launch is not
+        assert!(!is_initial_buy_permitted(LAMPORTS_PER_SOL, false));
+        assert!(is_initial_buy_permitted(0, false));
+
+        // Flag on: initial buys are allowed
+        assert!(is_initial_buy_permitted(LAMPORTS_PER_SOL, true));
+    }
+
+    #[test]
+    fn test_trade_deadline() {
+        let now = 1_700_000_000;
+
+        // Expired deadline is rejected
+        assert!(is_deadline_expired(now, now - 1));
+
+        // Future deadline, or exactly now, is accepted
+        assert!(!is_deadline_expired(now, now + 30));
+        assert!(!is_deadline_expired(now, now));
+
+        // Zero disables the check
+        assert!(!is_deadline_expired(now, 0));
+    }
+
+    #[test]
+    fn test_creator_fee_split() {
+        let creator_fee = calculate_fee(3 * LAMPORTS_PER_SOL, CREATOR_FEE_BASIS_POINTS).unwrap();
+
+        // 25% to the community wallet, the rest to the creator
+        let (creator_share, community_share) = split_creator_fee(creator_fee, 2_500).unwrap();
+        assert_eq!(community_share, 7_500_000);
+        assert_eq!(creator_share, 22_500_000);
+        assert_eq!(creator_share + community_share, creator_fee);
+
+        // No split configured: everything goes to the creator
+        assert_eq!(split_creator_fee(creator_fee, 0).unwrap(), (creator_fee, 0));
+    }
+
+    #[test]
+    fn test_creator_self_buy_skips_creator_fee() {
+        let creator = Pubkey::new_unique();
+        let buyer = Pubkey::new_unique();
+        let sol_amount = LAMPORTS_PER_SOL;
+        let creator_fee = calculate_fee(sol_amount, CREATOR_FEE_BASIS_POINTS).unwrap();
+        let platform_fee = calculate_fee(sol_amount, PLATFORM_FEE_BASIS_POINTS).unwrap();
+
+        // Self-buy with the waiver on: no creator fee, platform fee unchanged
+        assert_eq!(creator_fee_for_buyer(creator_fee, &creator, &creator, true), 0);
+        assert!(platform_fee > 0);
+
+        // Waiver off, or a different buyer: creator fee still charged
+        assert_eq!(creator_fee_for_buyer(creator_fee, &creator, &creator, false), creator_fee);
+        assert_eq!(creator_fee_for_buyer(creator_fee, &buyer, &creator, true), creator_fee);
+    }
+
+    #[test]
+    fn test_trading_fee_decays_to_floor() {
+        use crate::state::BondingCurve;
+
+        let mut curve = BondingCurve {
+            fee_decay_start_bps: 500,
+            fee_decay_floor_bps: 50,
+            fee_decay_volume: 1_000 * LAMPORTS_PER_SOL,
+            ..Default::default()
+        };
+
+        let mut last_fee = curve.trading_fee_bps(PLATFORM_FEE_BASIS_POINTS);
+        assert_eq!(last_fee, 500);
+
+        for _ in 0..10 {
+            curve.total_volume += 100 * LAMPORTS_PER_SOL;
+            let fee = curve.trading_fee_bps(PLATFORM_FEE_BASIS_POINTS);
+            assert!(fee < last_fee);
+            last_fee = fee;
+        }
+        assert_eq!(last_fee, 50);
+
+        // Bottoms out at the floor
+        curve.total_volume *= 5;
+        assert_eq!(curve.trading_fee_bps(PLATFORM_FEE_BASIS_POINTS), 50);
+
+        // Curves without a decay schedule use the default
+        assert_eq!(
+            BondingCurve::default().trading_fee_bps(PLATFORM_FEE_BASIS_POINTS),
+            PLATFORM_FEE_BASIS_POINTS
+        );
+    }
+
+    #[test]
+    fn test_graduation_gross_sol_remaining() {
+        let raised = 84 * LAMPORTS_PER_SOL + 123_456_789;
+        let remaining = REAL_SOL_RESERVES - raised;
+
+        let gross = gross_sol_for_net(remaining, CREATOR_FEE_BASIS_POINTS, PLATFORM_FEE_BASIS_POINTS)
+            .unwrap();
+        assert!(gross > remaining);
+
+        // Trading exactly the reported gross graduates the curve...
+        let mut curve = BondingCurveState {
+            real_sol_reserves: raised,
+            ..BondingCurveState::new()
+        };
+        let net = net_after_trade_fees(gross, CREATOR_FEE_BASIS_POINTS, PLATFORM_FEE_BASIS_POINTS)
+            .unwrap();
+        let tokens = curve.calculate_tokens_for_sol(net).unwrap();
+        curve.update_after_buy(net, tokens).unwrap();
+        assert!(curve.is_migration_ready());
+
+        // ...while one lamport less falls short
+        let short = net_after_trade_fees(gross - 1, CREATOR_FEE_BASIS_POINTS, PLATFORM_FEE_BASIS_POINTS)
+            .unwrap();
+        assert!(raised + short < REAL_SOL_RESERVES);
     }
 
     #[test]
@@ -0,0 +1,98 @@
+//! `buy_exact_tokens` through the handler: the requested amount arrives and
+//! the buyer pays the quoted cost plus fees, never the whole cap.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::buy::buy_fee_bps;
+use pump_clone::state::{BondingCurve, GlobalState, UserPosition};
+use pump_clone::utils::{gross_up_for_fees, BondingCurveState};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signer;
+
+/// What the buyer pays for `token_amount`: the curve's cost grossed up by
+/// the fees, since buys are priced on what reaches the curve.
+fn quote(global: &GlobalState, curve: &BondingCurve, buyer: &Pubkey, token_amount: u64) -> u64 {
+    let cost = BondingCurveState {
+        virtual_sol_reserves: curve.virtual_sol_reserves,
+        virtual_token_reserves: curve.virtual_token_reserves,
+        real_sol_reserves: curve.real_sol_reserves,
+        real_token_reserves: curve.real_token_reserves,
+    }
+    .calculate_buy_price(token_amount)
+    .unwrap();
+    gross_up_for_fees(cost, buy_fee_bps(global, curve, buyer, cost)).unwrap()
+}
+
+#[tokio::test]
+async fn exact_buy_delivers_the_requested_tokens_for_the_quoted_cost() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let buyer_tokens = get_associated_token_address(&buyer.pubkey(), &launch.mint);
+
+    // Odd amounts, so rounding in the quote and the fill both matter
+    for (step, token_amount) in [1_234_567_891u64, 33_333_333_333, 7].into_iter().enumerate() {
+        env.warp_to_timestamp(common::START_TS + step as i64 + 1).await;
+        let curve: BondingCurve = env.account(launch.bonding_curve).await;
+        let cost = quote(&global, &curve, &buyer.pubkey(), token_amount);
+        let (lamports_before, tokens_before) = (
+            env.lamports(buyer.pubkey()).await,
+            env.token_balance(buyer_tokens).await,
+        );
+
+        let buy = common::instruction(
+            launch.buy_accounts(&buyer.pubkey()),
+            pump_clone::instruction::BuyExactTokens {
+                token_amount,
+                max_sol_in: cost + LAMPORTS_PER_SOL,
+                deadline: 0,
+            },
+        );
+        env.process(&[buy], &[&buyer]).await.unwrap();
+
+        assert!(env.token_balance(buyer_tokens).await - tokens_before >= token_amount);
+        // Only the cost and fees left the wallet, plus rent for the token account and
+        // position the first buy opens; none of the headroom in the cap
+        let rent = if step == 0 {
+            let rent = Rent::default();
+            rent.minimum_balance(spl_token::state::Account::LEN) + rent.minimum_balance(UserPosition::LEN)
+        } else {
+            0
+        };
+        assert_eq!(lamports_before - env.lamports(buyer.pubkey()).await, cost + rent);
+    }
+}
+
+#[tokio::test]
+async fn exact_buy_with_a_cap_under_the_cost_fails() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    let curve = launch.fresh_curve();
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+
+    let token_amount = 1_000_000_000_000;
+    let buy = common::instruction(
+        launch.buy_accounts(&buyer.pubkey()),
+        pump_clone::instruction::BuyExactTokens {
+            token_amount,
+            max_sol_in: quote(&global, &curve, &buyer.pubkey(), token_amount) - 1,
+            deadline: 0,
+        },
+    );
+    assert!(env.process(&[buy], &[&buyer]).await.is_err());
+    assert_eq!(
+        env.token_balance(get_associated_token_address(&buyer.pubkey(), &launch.mint)).await,
+        0
+    );
+}
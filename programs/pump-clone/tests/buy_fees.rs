@@ -0,0 +1,48 @@
+//! `buy_tokens` pays the creator fee to the launch's creator and the
+//! platform fee to the fee vault, and puts only the rest in the curve.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::{Launch, TestEnv};
+use pump_clone::state::BondingCurve;
+use pump_clone::utils::{calculate_fee, AUTO_SLIPPAGE, CREATOR_FEE_BASIS_POINTS, PLATFORM_FEE_BASIS_POINTS};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn buy_fees_reach_creator_and_fee_vault() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+
+    let sol_amount = 2 * LAMPORTS_PER_SOL;
+    let creator_fee = calculate_fee(sol_amount, CREATOR_FEE_BASIS_POINTS).unwrap();
+    let platform_fee = calculate_fee(sol_amount, PLATFORM_FEE_BASIS_POINTS).unwrap();
+
+    // A creator account other than the launch's is rejected
+    let mut accounts = launch.buy_accounts(&buyer.pubkey());
+    accounts.creator = Pubkey::new_unique();
+    let wrong_creator = common::instruction(
+        accounts,
+        pump_clone::instruction::BuyTokens { sol_amount, max_slippage_bps: AUTO_SLIPPAGE, deadline: 0 },
+    );
+    assert!(env.process(&[wrong_creator], &[&buyer]).await.is_err());
+
+    let creator_before = env.lamports(launch.creator.pubkey()).await;
+    let fee_vault_before = env.lamports(launch.fee_vault).await;
+    let sol_vault_before = env.lamports(launch.sol_vault).await;
+    env.process(&[launch.buy_ix(&buyer.pubkey(), sol_amount, AUTO_SLIPPAGE)], &[&buyer])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(launch.creator.pubkey()).await - creator_before, creator_fee);
+    assert_eq!(env.lamports(launch.fee_vault).await - fee_vault_before, platform_fee);
+    let net_sol = sol_amount - creator_fee - platform_fee;
+    assert_eq!(env.lamports(launch.sol_vault).await - sol_vault_before, net_sol);
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_eq!(curve.real_sol_reserves, net_sol);
+}
@@ -0,0 +1,261 @@
+//! `buy_tokens_batch` through the handler: legs run the single-buy guards and
+//! fees, book positions and settle all-or-nothing.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::events::{TokensPurchased, Trade};
+use pump_clone::state::{BondingCurve, BuyLeg, UserPosition};
+use pump_clone::utils::{BondingCurveState, PLATFORM_FEE_BASIS_POINTS};
+use solana_program_test::ProgramTest;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn leg_accounts(launch: &Launch, buyer: &Keypair) -> Vec<AccountMeta> {
+    let buyer = buyer.pubkey();
+    vec![
+        AccountMeta::new(launch.bonding_curve, false),
+        AccountMeta::new_readonly(launch.mint, false),
+        AccountMeta::new(launch.curve_token_account, false),
+        AccountMeta::new(launch.sol_vault, false),
+        AccountMeta::new(get_associated_token_address(&buyer, &launch.mint), false),
+        AccountMeta::new_readonly(launch.token_launch, false),
+        AccountMeta::new(launch.creator.pubkey(), false),
+        AccountMeta::new(launch.creator.pubkey(), false),
+        AccountMeta::new(launch.user_position(&buyer), false),
+        AccountMeta::new_readonly(
+            Pubkey::find_program_address(
+                &[b"whitelist", launch.mint.as_ref(), buyer.as_ref()],
+                &pump_clone::ID,
+            )
+            .0,
+            false,
+        ),
+    ]
+}
+
+fn batch_ix(launches: &[&Launch], buyer: &Keypair, legs: Vec<BuyLeg>, deadline: i64) -> Instruction {
+    governed_batch_ix(launches, buyer, legs, deadline, None)
+}
+
+/// `batch_ix` with the buyer's governance token account passed for the fee
+/// discount.
+fn governed_batch_ix(
+    launches: &[&Launch],
+    buyer: &Keypair,
+    legs: Vec<BuyLeg>,
+    deadline: i64,
+    governance_token_account: Option<Pubkey>,
+) -> Instruction {
+    let mut instruction = common::instruction(
+        pump_clone::accounts::BuyTokensBatch {
+            buyer: buyer.pubkey(),
+            buyer_blacklist: Pubkey::find_program_address(
+                &[b"blacklist", buyer.pubkey().as_ref()],
+                &pump_clone::ID,
+            )
+            .0,
+            global_state: launches[0].global_state,
+            fee_vault: launches[0].fee_vault,
+            buyer_referral: None,
+            market_maker: None,
+            instructions_sysvar: None,
+            governance_token_account,
+            token_program: spl_token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::BuyTokensBatch { legs, deadline },
+    );
+    for launch in launches {
+        instruction.accounts.extend(leg_accounts(launch, buyer));
+    }
+    instruction
+}
+
+/// Two fresh launches and a funded buyer holding an empty token account for each.
+fn setup(pumped: &Launch, fresh: &Launch, pumped_curve: &BondingCurve, buyer: &Keypair) -> ProgramTest {
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    pumped.seed(&mut program_test, pumped_curve, &pumped.token_launch_account(), &global);
+    fresh.seed(&mut program_test, &fresh.fresh_curve(), &fresh.token_launch_account(), &global);
+    common::add_system_account(&mut program_test, buyer.pubkey(), 10 * LAMPORTS_PER_SOL);
+    for launch in [pumped, fresh] {
+        common::add_token_account(
+            &mut program_test,
+            get_associated_token_address(&buyer.pubkey(), &launch.mint),
+            launch.mint,
+            buyer.pubkey(),
+            0,
+        );
+    }
+    program_test
+}
+
+fn pumped_curve(launch: &Launch) -> BondingCurve {
+    let mut curve = launch.fresh_curve();
+    let mut state = BondingCurveState {
+        virtual_sol_reserves: curve.virtual_sol_reserves,
+        virtual_token_reserves: curve.virtual_token_reserves,
+        real_sol_reserves: curve.real_sol_reserves,
+        real_token_reserves: curve.real_token_reserves,
+    };
+    let bought = state.calculate_tokens_for_sol(10 * LAMPORTS_PER_SOL).unwrap();
+    state.update_after_buy(10 * LAMPORTS_PER_SOL, bought).unwrap();
+    curve.virtual_sol_reserves = state.virtual_sol_reserves;
+    curve.virtual_token_reserves = state.virtual_token_reserves;
+    curve.real_sol_reserves = state.real_sol_reserves;
+    curve.real_token_reserves -= bought;
+    curve
+}
+
+#[tokio::test]
+async fn batch_fills_two_curves_and_books_positions() {
+    let (pumped, fresh, buyer) = (Launch::new(), Launch::new(), Keypair::new());
+    let before = pumped_curve(&pumped);
+    let mut env = TestEnv::start(setup(&pumped, &fresh, &before, &buyer)).await;
+
+    let sol = LAMPORTS_PER_SOL;
+    let legs = vec![
+        BuyLeg { sol_amount: sol, min_tokens_out: 1 },
+        BuyLeg { sol_amount: sol, min_tokens_out: 1 },
+    ];
+    let logs = env
+        .process(&[batch_ix(&[&pumped, &fresh], &buyer, legs, 0)], &[&buyer])
+        .await
+        .unwrap();
+
+    let trades = common::events::<Trade>(&logs);
+    assert_eq!(trades.len(), 2);
+    // The same SOL buys more on the curve nobody has bought into yet
+    assert!(trades[1].token_amount > trades[0].token_amount);
+    // Each leg also emits the legacy purchase event `buy_tokens` does
+    let purchases = common::events::<TokensPurchased>(&logs);
+    assert_eq!(purchases.len(), 2);
+    for (purchase, trade) in purchases.iter().zip(&trades) {
+        assert_eq!((purchase.buyer, purchase.mint), (trade.wallet, trade.mint));
+        assert_eq!((purchase.sol_amount, purchase.token_amount), (trade.sol_amount, trade.token_amount));
+        assert_eq!(purchase.price_after, trade.price);
+    }
+
+    for (launch, trade) in [(&pumped, &trades[0]), (&fresh, &trades[1])] {
+        let curve: BondingCurve = env.account(launch.bonding_curve).await;
+        assert_eq!(curve.virtual_token_reserves, trade.virtual_token_reserves);
+        assert_eq!(
+            env.token_balance(get_associated_token_address(&buyer.pubkey(), &launch.mint)).await,
+            trade.token_amount
+        );
+        let position: UserPosition = env.account(launch.user_position(&buyer.pubkey())).await;
+        assert_eq!(position.user, buyer.pubkey());
+        assert_eq!(position.tokens_bought, trade.token_amount);
+        assert_eq!(position.token_balance, trade.token_amount);
+    }
+    let curve: BondingCurve = env.account(pumped.bonding_curve).await;
+    assert_eq!(curve.real_sol_reserves, before.real_sol_reserves + sol * 98 / 100);
+}
+
+#[tokio::test]
+async fn one_leg_over_the_anti_snipe_cap_fails_the_batch() {
+    let (pumped, fresh, buyer) = (Launch::new(), Launch::new(), Keypair::new());
+    let before = pumped_curve(&pumped);
+    let mut program_test = setup(&pumped, &fresh, &before, &buyer);
+    // The fresh launch is still in its anti-snipe window with a tiny cap
+    let mut capped = fresh.fresh_curve();
+    capped.anti_snipe_duration = 3_600;
+    capped.max_buy_per_wallet = 1;
+    common::add_program_account(&mut program_test, fresh.bonding_curve, &capped, BondingCurve::LEN);
+    let mut env = TestEnv::start(program_test).await;
+
+    let legs = vec![
+        BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 },
+        BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 },
+    ];
+    assert!(env
+        .process(&[batch_ix(&[&pumped, &fresh], &buyer, legs, 0)], &[&buyer])
+        .await
+        .is_err());
+
+    // Neither leg settled
+    let curve: BondingCurve = env.account(pumped.bonding_curve).await;
+    assert_eq!(curve.virtual_sol_reserves, before.virtual_sol_reserves);
+    assert_eq!(env.token_balance(get_associated_token_address(&buyer.pubkey(), &pumped.mint)).await, 0);
+}
+
+#[tokio::test]
+async fn expired_deadline_rejects_the_batch() {
+    let (pumped, fresh, buyer) = (Launch::new(), Launch::new(), Keypair::new());
+    let before = pumped_curve(&pumped);
+    let mut env = TestEnv::start(setup(&pumped, &fresh, &before, &buyer)).await;
+
+    let legs = vec![BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 }];
+    let instruction = batch_ix(&[&pumped], &buyer, legs, common::START_TS - 1);
+    assert!(env.process(&[instruction], &[&buyer]).await.is_err());
+}
+
+#[tokio::test]
+async fn trading_hours_apply_to_every_leg() {
+    let (pumped, fresh, buyer) = (Launch::new(), Launch::new(), Keypair::new());
+    let before = pumped_curve(&pumped);
+    let mut program_test = setup(&pumped, &fresh, &before, &buyer);
+    // A one-second trading window that START_TS falls outside of
+    let mut launch = fresh.token_launch_account();
+    let seconds_into_day = (common::START_TS % 86_400) as u32;
+    launch.trading_window_start = (seconds_into_day + 3_600) % 86_400;
+    launch.trading_window_end = (seconds_into_day + 3_601) % 86_400;
+    common::add_program_account(&mut program_test, fresh.token_launch, &launch, pump_clone::state::TokenLaunch::LEN);
+    let mut env = TestEnv::start(program_test).await;
+
+    let legs = vec![
+        BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 },
+        BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 },
+    ];
+    assert!(env
+        .process(&[batch_ix(&[&pumped, &fresh], &buyer, legs, 0)], &[&buyer])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn legs_create_token_accounts_and_get_the_governance_discount() {
+    let (first, second, buyer) = (Launch::new(), Launch::new(), Keypair::new());
+    let governance_mint = Pubkey::new_unique();
+    let governance_account = Pubkey::new_unique();
+    let mut global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    global.governance_mint = governance_mint;
+    global.governance_min_balance = 1;
+    global.governance_fee_discount_bps = 5_000;
+    let mut program_test = common::program_test();
+    for launch in [&first, &second] {
+        launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    }
+    common::add_system_account(&mut program_test, buyer.pubkey(), 10 * LAMPORTS_PER_SOL);
+    common::add_mint(&mut program_test, governance_mint, None, 1);
+    common::add_token_account(&mut program_test, governance_account, governance_mint, buyer.pubkey(), 1);
+    let mut env = TestEnv::start(program_test).await;
+
+    // The buyer holds no token account for either launch yet
+    let legs = vec![
+        BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 },
+        BuyLeg { sol_amount: LAMPORTS_PER_SOL, min_tokens_out: 1 },
+    ];
+    let logs = env
+        .process(
+            &[governed_batch_ix(&[&first, &second], &buyer, legs, 0, Some(governance_account))],
+            &[&buyer],
+        )
+        .await
+        .unwrap();
+
+    let trades = common::events::<Trade>(&logs);
+    assert_eq!(trades.len(), 2);
+    for (launch, trade) in [(&first, &trades[0]), (&second, &trades[1])] {
+        assert_eq!(trade.platform_fee, LAMPORTS_PER_SOL * PLATFORM_FEE_BASIS_POINTS as u64 / 10_000 / 2);
+        assert_eq!(
+            env.token_balance(get_associated_token_address(&buyer.pubkey(), &launch.mint)).await,
+            trade.token_amount
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Closing a migrated curve refunds rent to the closer's recipient and
+//! sweeps anything above it to the platform fee vault; holders' emptied
+//! positions can still be closed afterwards.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::{Launch, TestEnv};
+use pump_clone::events::CurveClosed;
+use pump_clone::state::{BondingCurve, GlobalState, TokenLaunch, UserPosition};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signer;
+
+fn close(launch: &Launch, rent_recipient: Pubkey) -> Instruction {
+    common::instruction(
+        pump_clone::accounts::CloseMigratedCurve {
+            closer: launch.creator.pubkey(),
+            global_state: launch.global_state,
+            token_launch: launch.token_launch,
+            bonding_curve: launch.bonding_curve,
+            sol_vault: launch.sol_vault,
+            rent_recipient,
+            fee_vault: launch.fee_vault,
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::CloseMigratedCurve {},
+    )
+}
+
+#[tokio::test]
+async fn unmigrated_curve_cannot_be_closed() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+
+    assert!(env
+        .process(&[close(&launch, Pubkey::new_unique())], &[&launch.creator])
+        .await
+        .is_err());
+    let launch_account: TokenLaunch = env.account(launch.token_launch).await;
+    assert!(!launch_account.is_migrated);
+}
+
+#[tokio::test]
+async fn closer_gets_rent_and_leftover_sol_goes_to_the_fee_vault() {
+    let launch = Launch::new();
+    let mut curve = launch.fresh_curve();
+    curve.complete = true;
+    // SOL left behind in the vault after migration
+    let leftover = 2 * LAMPORTS_PER_SOL;
+    curve.real_sol_reserves = leftover;
+    let mut token_launch = launch.token_launch_account();
+    token_launch.is_migrated = true;
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &curve, &token_launch, &global);
+    let mut env = TestEnv::start(program_test).await;
+    let rent = Rent::default();
+    let fee_vault_before = env.lamports(launch.fee_vault).await;
+
+    let rent_recipient = Pubkey::new_unique();
+    let logs = env
+        .process(&[close(&launch, rent_recipient)], &[&launch.creator])
+        .await
+        .unwrap();
+
+    // Exactly the rent of the three closed accounts is refunded
+    assert_eq!(
+        env.lamports(rent_recipient).await,
+        rent.minimum_balance(0)
+            + rent.minimum_balance(TokenLaunch::LEN)
+            + rent.minimum_balance(BondingCurve::LEN)
+    );
+    assert_eq!(env.lamports(launch.fee_vault).await, fee_vault_before + leftover);
+    assert_eq!(env.lamports(launch.sol_vault).await, 0);
+    assert_eq!(common::events::<CurveClosed>(&logs)[0].to_treasury, leftover);
+    let state: GlobalState = env.account(launch.global_state).await;
+    assert_eq!(state.total_fees_collected, leftover);
+}
+
+#[tokio::test]
+async fn empty_positions_can_be_closed_after_the_curve() {
+    let launch = Launch::new();
+    let mut curve = launch.fresh_curve();
+    curve.complete = true;
+    let mut token_launch = launch.token_launch_account();
+    token_launch.is_migrated = true;
+    let user = Pubkey::new_unique();
+    let position = launch.user_position(&user);
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &curve, &token_launch, &global);
+    common::add_system_account(&mut program_test, user, 0);
+    common::add_program_account(
+        &mut program_test,
+        position,
+        &UserPosition {
+            user,
+            token_launch: launch.token_launch,
+            mint: launch.mint,
+            bump: Pubkey::find_program_address(
+                &[b"user_position", user.as_ref(), launch.token_launch.as_ref()],
+                &pump_clone::ID,
+            )
+            .1,
+            ..Default::default()
+        },
+        UserPosition::LEN,
+    );
+    let mut env = TestEnv::start(program_test).await;
+
+    env.process(&[close(&launch, Pubkey::new_unique())], &[&launch.creator])
+        .await
+        .unwrap();
+    assert!(env.context.banks_client.get_account(launch.token_launch).await.unwrap().is_none());
+
+    // Anyone may sweep it now, and the rent goes back to its owner
+    let sweeper = env.funded_wallet(LAMPORTS_PER_SOL).await;
+    let close_position = common::instruction(
+        pump_clone::accounts::ClosePosition {
+            closer: sweeper.pubkey(),
+            user,
+            token_launch: launch.token_launch,
+            user_position: position,
+            global_state: launch.global_state,
+        },
+        pump_clone::instruction::ClosePosition {},
+    );
+    env.process(&[close_position], &[&sweeper]).await.unwrap();
+    assert!(env.context.banks_client.get_account(position).await.unwrap().is_none());
+    assert_eq!(env.lamports(user).await, Rent::default().minimum_balance(UserPosition::LEN));
+}
@@ -0,0 +1,721 @@
+//! Program-test harness shared by the handler tests.
+//!
+//! Launches are seeded straight into the bank as accounts, so each test
+//! starts from exactly the curve it needs, then drives the real instruction
+//! handlers through a `BanksClient` and reads the resulting accounts, logs
+//! and events back.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, ProgramResult};
+use anchor_lang::{
+    AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator, InstructionData,
+    ToAccountMetas,
+};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::metadata::mpl_token_metadata;
+use pump_clone::dex::{raydium_amm_id, RAYDIUM_AMM_V4_PROGRAM_ID, SERUM_DEX_PROGRAM_ID};
+use pump_clone::state::{BondingCurve, GlobalState, TokenLaunch};
+use pump_clone::utils::{
+    CREATOR_FEE_BASIS_POINTS, PLATFORM_FEE_BASIS_POINTS, REAL_TOKEN_RESERVES,
+    VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES,
+};
+use solana_program_test::{processor, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+/// Unix time the bank's clock starts from in every test.
+pub const START_TS: i64 = 1_700_000_000;
+
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    // Anchor's entrypoint ties the slice and its accounts to one lifetime
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    pump_clone::entry(program_id, accounts, data)
+}
+
+pub fn program_test() -> ProgramTest {
+    let mut program_test = ProgramTest::new("pump_clone", pump_clone::ID, processor!(process_instruction));
+    program_test.prefer_bpf(false);
+    program_test.add_program("mpl_token_metadata", mpl_token_metadata::ID, processor!(token_metadata));
+    program_test.add_program("raydium_amm", RAYDIUM_AMM_V4_PROGRAM_ID, processor!(raydium_amm));
+    program_test
+}
+
+/// `CreateMetadataAccountV3` and `UpdateMetadataAccountV2` discriminators
+const CREATE_METADATA: u8 = 33;
+const UPDATE_METADATA: u8 = 15;
+
+/// Stand-in for Metaplex Token Metadata keeping the parts the program relies
+/// on: creating the mint's metadata needs the mint authority, and only the
+/// recorded update authority can update it, while it is still mutable. The
+/// account holds just the update authority and the mutable flag.
+fn token_metadata(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    use anchor_lang::solana_program::{
+        program::invoke_signed, program_error::ProgramError, program_option::COption, system_instruction,
+    };
+    use mpl_token_metadata::instructions::{
+        CreateMetadataAccountV3InstructionArgs, UpdateMetadataAccountV2InstructionArgs,
+    };
+
+    match data.split_first() {
+        Some((&CREATE_METADATA, args)) => {
+            let args = CreateMetadataAccountV3InstructionArgs::deserialize(&mut &args[..])?;
+            let [metadata, mint, mint_authority, payer, update_authority, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            let mint_state = spl_token::state::Mint::unpack(&mint.try_borrow_data()?)?;
+            if !mint_authority.is_signer || mint_state.mint_authority != COption::Some(*mint_authority.key) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            let seeds: &[&[u8]] = &[b"metadata", program_id.as_ref(), mint.key.as_ref()];
+            let (address, bump) = Pubkey::find_program_address(seeds, program_id);
+            if address != *metadata.key {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            invoke_signed(
+                &system_instruction::create_account(payer.key, metadata.key, rent_exempt(33), 33, program_id),
+                accounts,
+                &[&[seeds[0], seeds[1], seeds[2], &[bump]]],
+            )?;
+            let mut record = metadata.try_borrow_mut_data()?;
+            record[..32].copy_from_slice(update_authority.key.as_ref());
+            record[32] = args.is_mutable as u8;
+            Ok(())
+        }
+        Some((&UPDATE_METADATA, args)) => {
+            let args = UpdateMetadataAccountV2InstructionArgs::deserialize(&mut &args[..])?;
+            let [metadata, update_authority, ..] = accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            if metadata.owner != program_id || metadata.data_len() != 33 {
+                return Err(ProgramError::UninitializedAccount);
+            }
+            let mut record = metadata.try_borrow_mut_data()?;
+            if !update_authority.is_signer || record[..32] != update_authority.key.to_bytes() {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if record[32] == 0 {
+                return Err(ProgramError::Immutable);
+            }
+            if args.is_mutable == Some(false) {
+                record[32] = 0;
+            }
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+/// Seed of the PDA the Raydium stand-in signs LP mints with.
+const AMM_AUTHORITY_SEED: &[u8] = b"amm authority";
+
+/// Stand-in for the Raydium AMM v4 pool init `migrate_liquidity` sends,
+/// keeping what the program relies on afterwards: the seed amounts move from
+/// the migration authority's accounts into the pool vaults, and LP is minted
+/// to its LP account. The pool vaults and LP mint are the ones
+/// `Migration::seed` adds, owned by the stand-in's authority PDA.
+fn raydium_amm(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    use anchor_lang::solana_program::{
+        program::{invoke, invoke_signed},
+        program_error::ProgramError,
+    };
+
+    let [token_program, _, _, _, _, amm_authority, _, lp_mint, _, _, coin_vault, pc_vault, _, _, _, _, _, user_coin, user_pc, user_wallet, user_lp, ..] =
+        accounts
+    else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let amount_at = |offset: usize| -> Result<u64, ProgramError> {
+        let bytes = data.get(offset..offset + 8).ok_or(ProgramError::InvalidInstructionData)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    };
+    // `nonce: u8, open_time: u64, init_pc_amount: u64, init_coin_amount: u64`
+    let (pc_amount, coin_amount) = (amount_at(9)?, amount_at(17)?);
+
+    for (from, to, amount) in [(user_coin, coin_vault, coin_amount), (user_pc, pc_vault, pc_amount)] {
+        invoke(
+            &spl_token::instruction::transfer(token_program.key, from.key, to.key, user_wallet.key, &[], amount)?,
+            accounts,
+        )?;
+    }
+    let (authority, bump) = Pubkey::find_program_address(&[AMM_AUTHORITY_SEED], program_id);
+    if authority != *amm_authority.key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let lp_amount = ((coin_amount as f64) * (pc_amount as f64)).sqrt() as u64;
+    invoke_signed(
+        &spl_token::instruction::mint_to(token_program.key, lp_mint.key, user_lp.key, amm_authority.key, &[], lp_amount)?,
+        accounts,
+        &[&[AMM_AUTHORITY_SEED, &[bump]]],
+    )
+}
+
+/// The mint's Metaplex metadata account.
+pub fn metadata_account(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", mpl_token_metadata::ID.as_ref(), mint.as_ref()],
+        &mpl_token_metadata::ID,
+    )
+    .0
+}
+
+/// Update authority and mutability the stand-in recorded for `mint`.
+pub async fn metadata_record(env: &mut TestEnv, mint: &Pubkey) -> (Pubkey, bool) {
+    let account = env
+        .context
+        .banks_client
+        .get_account(metadata_account(mint))
+        .await
+        .unwrap()
+        .unwrap();
+    (Pubkey::try_from(&account.data[..32]).unwrap(), account.data[32] != 0)
+}
+
+fn rent_exempt(space: usize) -> u64 {
+    Rent::default().minimum_balance(space)
+}
+
+/// Stores `data` at `address` as a program-owned account of `space` bytes.
+pub fn add_program_account<T: AccountSerialize>(
+    program_test: &mut ProgramTest,
+    address: Pubkey,
+    data: &T,
+    space: usize,
+) {
+    let mut bytes = Vec::with_capacity(space);
+    data.try_serialize(&mut bytes).unwrap();
+    bytes.resize(space, 0);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: rent_exempt(space),
+            data: bytes,
+            owner: pump_clone::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub fn add_system_account(program_test: &mut ProgramTest, address: Pubkey, lamports: u64) {
+    program_test.add_account(
+        address,
+        Account::new(lamports, 0, &solana_sdk::system_program::ID),
+    );
+}
+
+pub fn add_mint(program_test: &mut ProgramTest, address: Pubkey, authority: Option<Pubkey>, supply: u64) {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        mint_authority: authority.into(),
+        supply,
+        decimals: 6,
+        is_initialized: true,
+        freeze_authority: None.into(),
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: rent_exempt(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub fn add_token_account(program_test: &mut ProgramTest, address: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: rent_exempt(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+pub fn add_native_mint(program_test: &mut ProgramTest) {
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    spl_token::state::Mint {
+        decimals: spl_token::native_mint::DECIMALS,
+        is_initialized: true,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        spl_token::native_mint::ID,
+        Account {
+            lamports: rent_exempt(data.len()),
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// A wSOL account holding `amount` wrapped lamports for `owner`.
+pub fn add_wsol_account(program_test: &mut ProgramTest, address: Pubkey, owner: Pubkey, amount: u64) {
+    let rent = rent_exempt(spl_token::state::Account::LEN);
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: spl_token::native_mint::ID,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: Some(rent).into(),
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: rent + amount,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// Global settings with every optional control off.
+pub fn global_state(authority: Pubkey, fee_recipient: Pubkey) -> GlobalState {
+    GlobalState {
+        authority,
+        fee_recipient,
+        platform_fee_bps: PLATFORM_FEE_BASIS_POINTS,
+        creator_fee_bps: CREATOR_FEE_BASIS_POINTS,
+        allow_creator_initial_buy: true,
+        renounce_authorities_on_migration: true,
+        max_allowed_slippage_bps: GlobalState::DEFAULT_MAX_ALLOWED_SLIPPAGE_BPS,
+        max_fee_bps: 10_000,
+        max_tokens_per_buy_bps: 10_000,
+        bump: pump_clone::pda::global_state().1,
+        ..Default::default()
+    }
+}
+
+/// Addresses of one seeded launch.
+pub struct Launch {
+    pub mint: Pubkey,
+    pub creator: Keypair,
+    pub token_launch: Pubkey,
+    pub bonding_curve: Pubkey,
+    pub curve_token_account: Pubkey,
+    pub sol_vault: Pubkey,
+    pub fee_vault: Pubkey,
+    pub global_state: Pubkey,
+}
+
+impl Launch {
+    pub fn new() -> Self {
+        Self::for_mint(Pubkey::new_unique())
+    }
+
+    /// Addresses of the launch `create_token` makes for `mint`.
+    pub fn for_mint(mint: Pubkey) -> Self {
+        let bonding_curve = pump_clone::pda::bonding_curve(&mint).0;
+        Self {
+            mint,
+            creator: Keypair::new(),
+            token_launch: Pubkey::find_program_address(&[b"token_launch", mint.as_ref()], &pump_clone::ID).0,
+            bonding_curve,
+            curve_token_account: pump_clone::pda::curve_token_account(&mint),
+            sol_vault: pump_clone::pda::sol_vault(&mint).0,
+            fee_vault: Pubkey::find_program_address(&[b"fee_vault"], &pump_clone::ID).0,
+            global_state: pump_clone::pda::global_state().0,
+        }
+    }
+
+    /// A curve at its launch reserves, as `create_token` leaves it.
+    pub fn fresh_curve(&self) -> BondingCurve {
+        BondingCurve {
+            token_launch: self.token_launch,
+            mint: self.mint,
+            creator: self.creator.pubkey(),
+            virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            real_token_reserves: REAL_TOKEN_RESERVES,
+            initial_virtual_sol_reserves: VIRTUAL_SOL_RESERVES,
+            initial_virtual_token_reserves: VIRTUAL_TOKEN_RESERVES,
+            initial_real_token_reserves: REAL_TOKEN_RESERVES,
+            total_supply: pump_clone::utils::MAX_TOKEN_SUPPLY,
+            creator_fee_bps: CREATOR_FEE_BASIS_POINTS,
+            platform_fee_bps: PLATFORM_FEE_BASIS_POINTS,
+            created_at: START_TS,
+            bump: pump_clone::pda::bonding_curve(&self.mint).1,
+            ..Default::default()
+        }
+    }
+
+    pub fn token_launch_account(&self) -> TokenLaunch {
+        TokenLaunch {
+            creator: self.creator.pubkey(),
+            mint: self.mint,
+            bonding_curve: self.bonding_curve,
+            created_at: START_TS,
+            total_supply: pump_clone::utils::MAX_TOKEN_SUPPLY,
+            decimals: 6,
+            bump: Pubkey::find_program_address(&[b"token_launch", self.mint.as_ref()], &pump_clone::ID).1,
+            ..Default::default()
+        }
+    }
+
+    /// Seeds the launch's accounts, with the curve's unsold supply in its
+    /// token account and `real_sol_reserves` in its SOL vault.
+    pub fn seed(&self, program_test: &mut ProgramTest, curve: &BondingCurve, launch: &TokenLaunch, global: &GlobalState) {
+        add_program_account(program_test, self.global_state, global, GlobalState::LEN);
+        add_program_account(program_test, self.token_launch, launch, TokenLaunch::LEN);
+        add_program_account(program_test, self.bonding_curve, curve, BondingCurve::LEN);
+        add_mint(program_test, self.mint, None, curve.total_supply);
+        add_token_account(
+            program_test,
+            self.curve_token_account,
+            self.mint,
+            self.bonding_curve,
+            curve.real_token_reserves,
+        );
+        add_system_account(program_test, self.sol_vault, rent_exempt(0) + curve.real_sol_reserves);
+        add_system_account(program_test, self.fee_vault, rent_exempt(0));
+        add_system_account(program_test, self.creator.pubkey(), LAMPORTS_PER_SOL);
+    }
+
+    pub fn user_position(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"user_position", user.as_ref(), self.token_launch.as_ref()],
+            &pump_clone::ID,
+        )
+        .0
+    }
+
+    pub fn buy_accounts(&self, buyer: &Pubkey) -> pump_clone::accounts::BuyTokens {
+        pump_clone::accounts::BuyTokens {
+            buyer: *buyer,
+            token_launch: self.token_launch,
+            mint: self.mint,
+            bonding_curve: self.bonding_curve,
+            associated_bonding_curve: self.curve_token_account,
+            sol_vault: self.sol_vault,
+            associated_user: get_associated_token_address(buyer, &self.mint),
+            global_state: self.global_state,
+            fee_vault: self.fee_vault,
+            holder_rewards: None,
+            buyer_wsol_account: None,
+            native_mint: None,
+            wsol_unwrap_account: None,
+            user_position: Some(self.user_position(buyer)),
+            creator: self.creator.pubkey(),
+            buyer_blacklist: Pubkey::find_program_address(&[b"blacklist", buyer.as_ref()], &pump_clone::ID).0,
+            buyer_whitelist: Pubkey::find_program_address(
+                &[b"whitelist", self.mint.as_ref(), buyer.as_ref()],
+                &pump_clone::ID,
+            )
+            .0,
+            buyer_referral: None,
+            referrer_referral: None,
+            user_settings: None,
+            community_wallet: None,
+            market_maker: None,
+            instructions_sysvar: None,
+            governance_token_account: None,
+            token_program: spl_token::ID,
+            associated_token_program: anchor_spl::associated_token::ID,
+            system_program: solana_sdk::system_program::ID,
+            rent: solana_sdk::sysvar::rent::ID,
+        }
+    }
+
+    pub fn buy_ix(&self, buyer: &Pubkey, sol_amount: u64, max_slippage_bps: u64) -> Instruction {
+        instruction(
+            self.buy_accounts(buyer),
+            pump_clone::instruction::BuyTokens { sol_amount, max_slippage_bps, deadline: 0 },
+        )
+    }
+
+    pub fn sell_accounts(&self, seller: &Pubkey) -> pump_clone::accounts::SellTokens {
+        pump_clone::accounts::SellTokens {
+            seller: *seller,
+            token_launch: self.token_launch,
+            mint: self.mint,
+            associated_user: get_associated_token_address(seller, &self.mint),
+            bonding_curve: self.bonding_curve,
+            associated_bonding_curve: self.curve_token_account,
+            sol_vault: self.sol_vault,
+            global_state: self.global_state,
+            fee_vault: self.fee_vault,
+            sell_vesting_escrow: None,
+            seller_blacklist: Pubkey::find_program_address(&[b"blacklist", seller.as_ref()], &pump_clone::ID).0,
+            seller_position: Some(self.user_position(seller)),
+            user_settings: None,
+            market_maker: None,
+            instructions_sysvar: None,
+            governance_token_account: None,
+            community_wallet: None,
+            price_floor_vault: None,
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        }
+    }
+
+    pub fn sell_ix(&self, seller: &Pubkey, token_amount: u64, min_sol_output: u64) -> Instruction {
+        instruction(
+            self.sell_accounts(seller),
+            pump_clone::instruction::SellTokens { token_amount, min_sol_output, deadline: 0 },
+        )
+    }
+}
+
+/// The pool side of one launch's migration, served by the Raydium stand-in.
+pub struct Migration {
+    /// Receives the seed amounts and the LP before it moves into the lock;
+    /// signs the migrate transaction
+    pub migration_authority: Keypair,
+    pub serum_market: Pubkey,
+    pub amm_pool: Pubkey,
+    pub amm_authority: Pubkey,
+    pub lp_mint: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub lp_lock: Pubkey,
+}
+
+impl Migration {
+    pub fn new(launch: &Launch) -> Self {
+        let serum_market = Pubkey::new_unique();
+        Self {
+            migration_authority: Keypair::new(),
+            serum_market,
+            amm_pool: raydium_amm_id(&RAYDIUM_AMM_V4_PROGRAM_ID, &serum_market),
+            amm_authority: Pubkey::find_program_address(&[AMM_AUTHORITY_SEED], &RAYDIUM_AMM_V4_PROGRAM_ID).0,
+            lp_mint: Pubkey::new_unique(),
+            coin_vault: Pubkey::new_unique(),
+            pc_vault: Pubkey::new_unique(),
+            lp_lock: Pubkey::find_program_address(&[b"lp_lock", launch.mint.as_ref()], &pump_clone::ID).0,
+        }
+    }
+
+    /// Seeds the pool's LP mint and vaults, the migration authority's LP
+    /// account Raydium would create, and the native mint.
+    pub fn seed(&self, program_test: &mut ProgramTest, launch: &Launch) {
+        add_native_mint(program_test);
+        add_mint(program_test, self.lp_mint, Some(self.amm_authority), 0);
+        add_token_account(program_test, self.coin_vault, launch.mint, self.amm_authority, 0);
+        add_wsol_account(program_test, self.pc_vault, self.amm_authority, 0);
+        add_token_account(
+            program_test,
+            get_associated_token_address(&self.migration_authority.pubkey(), &self.lp_mint),
+            self.lp_mint,
+            self.migration_authority.pubkey(),
+            0,
+        );
+    }
+
+    pub fn ix(&self, launch: &Launch, payer: &Pubkey) -> Instruction {
+        let authority = self.migration_authority.pubkey();
+        let unused = Pubkey::new_unique;
+        instruction(
+            pump_clone::accounts::MigrateLiquidity {
+                global_state: launch.global_state,
+                bonding_curve: launch.bonding_curve,
+                token_launch: launch.token_launch,
+                token_mint: launch.mint,
+                curve_token_account: launch.curve_token_account,
+                curve_sol_vault: launch.sol_vault,
+                raydium_amm_program: RAYDIUM_AMM_V4_PROGRAM_ID,
+                amm_pool: self.amm_pool,
+                amm_authority: self.amm_authority,
+                amm_open_orders: unused(),
+                lp_mint: self.lp_mint,
+                lp_lock: self.lp_lock,
+                lp_lock_token_account: get_associated_token_address(&self.lp_lock, &self.lp_mint),
+                coin_vault: self.coin_vault,
+                pc_vault: self.pc_vault,
+                withdraw_queue: unused(),
+                amm_target_orders: unused(),
+                pool_temp_lp: unused(),
+                serum_market: self.serum_market,
+                serum_program: SERUM_DEX_PROGRAM_ID,
+                serum_coin_vault_signer: unused(),
+                serum_pc_vault_signer: unused(),
+                serum_vault_signer: unused(),
+                serum_asks: unused(),
+                serum_bids: unused(),
+                serum_event_queue: unused(),
+                serum_coin_vault: unused(),
+                serum_pc_vault: unused(),
+                wsol_mint: spl_token::native_mint::ID,
+                curve_wsol_account: get_associated_token_address(&launch.bonding_curve, &spl_token::native_mint::ID),
+                migration_token_account: get_associated_token_address(&authority, &launch.mint),
+                migration_wsol_account: get_associated_token_address(&authority, &spl_token::native_mint::ID),
+                migration_authority: authority,
+                migration_lp_account: get_associated_token_address(&authority, &self.lp_mint),
+                payer: *payer,
+                system_program: solana_sdk::system_program::ID,
+                token_program: spl_token::ID,
+                associated_token_program: anchor_spl::associated_token::ID,
+                rent: solana_sdk::sysvar::rent::ID,
+            },
+            pump_clone::instruction::MigrateLiquidity {},
+        )
+    }
+}
+
+/// Accounts `create_token` initializes for a new `mint` keypair; the vesting
+/// escrow is only passed along with an initial buy.
+pub fn create_token_accounts(creator: &Pubkey, mint: &Pubkey, initial_buy: bool) -> pump_clone::accounts::CreateToken {
+    let bonding_curve = pump_clone::pda::bonding_curve(mint).0;
+    let creator_vesting = Pubkey::find_program_address(&[b"vesting", mint.as_ref()], &pump_clone::ID).0;
+    pump_clone::accounts::CreateToken {
+        creator: *creator,
+        global_state: pump_clone::pda::global_state().0,
+        mint: *mint,
+        token_launch: Pubkey::find_program_address(&[b"token_launch", mint.as_ref()], &pump_clone::ID).0,
+        bonding_curve,
+        associated_bonding_curve: pump_clone::pda::curve_token_account(mint),
+        associated_user: get_associated_token_address(creator, mint),
+        sol_vault: pump_clone::pda::sol_vault(mint).0,
+        fee_vault: Pubkey::find_program_address(&[b"fee_vault"], &pump_clone::ID).0,
+        creator_vesting: initial_buy.then_some(creator_vesting),
+        vesting_token_account: initial_buy.then(|| get_associated_token_address(&creator_vesting, mint)),
+        metadata: metadata_account(mint),
+        token_program: spl_token::ID,
+        associated_token_program: anchor_spl::associated_token::ID,
+        token_metadata_program: mpl_token_metadata::ID,
+        system_program: solana_sdk::system_program::ID,
+        rent: solana_sdk::sysvar::rent::ID,
+    }
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: pump_clone::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Decodes every `E` the program emitted into `logs`.
+pub fn events<E: AnchorDeserialize + Discriminator>(logs: &[String]) -> Vec<E> {
+    use anchor_lang::__private::base64::{engine::general_purpose::STANDARD, Engine};
+
+    logs.iter()
+        .filter_map(|log| log.strip_prefix("Program data: "))
+        .filter_map(|data| STANDARD.decode(data).ok())
+        .filter(|data| data.starts_with(&E::DISCRIMINATOR))
+        .map(|data| E::deserialize(&mut &data[8..]).unwrap())
+        .collect()
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+}
+
+impl TestEnv {
+    pub async fn start(program_test: ProgramTest) -> Self {
+        let mut context = program_test.start_with_context().await;
+        let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = START_TS;
+        context.set_sysvar(&clock);
+        Self { context }
+    }
+
+    /// Signs with the payer plus `signers` and returns the transaction logs.
+    pub async fn process(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<Vec<String>, TransactionError> {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let outcome = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await
+            .unwrap();
+        outcome.result.map(|()| outcome.metadata.map(|metadata| metadata.log_messages).unwrap_or_default())
+    }
+
+    /// Simulates `instruction` and decodes what it passed to
+    /// `set_return_data`, for view instructions.
+    pub async fn view<T: AnchorDeserialize>(&mut self, instruction: Instruction) -> T {
+        let blockhash = self.context.banks_client.get_latest_blockhash().await.unwrap();
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.context.payer.pubkey()),
+            &[&self.context.payer],
+            blockhash,
+        );
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+        T::deserialize(&mut return_data.data.as_slice()).unwrap()
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self.context.banks_client.get_account(address).await.unwrap().unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn lamports(&mut self, address: Pubkey) -> u64 {
+        self.context.banks_client.get_balance(address).await.unwrap()
+    }
+
+    pub async fn token_balance(&mut self, address: Pubkey) -> u64 {
+        match self.context.banks_client.get_account(address).await.unwrap() {
+            Some(account) => spl_token::state::Account::unpack(&account.data).unwrap().amount,
+            None => 0,
+        }
+    }
+
+    /// Moves the bank's clock to `unix_timestamp`, on a fresh slot so
+    /// per-slot limits start over.
+    pub async fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        self.context.warp_to_slot(clock.slot + 1).unwrap();
+        clock.slot += 1;
+        clock.unix_timestamp = unix_timestamp;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// A funded wallet to trade from.
+    pub async fn funded_wallet(&mut self, lamports: u64) -> Keypair {
+        let wallet = Keypair::new();
+        self.process(
+            &[solana_sdk::system_instruction::transfer(
+                &self.context.payer.pubkey(),
+                &wallet.pubkey(),
+                lamports,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        wallet
+    }
+}
@@ -0,0 +1,158 @@
+//! `create_token` with an initial buy: the launch and its metadata are
+//! created, the creator's buy is settled like any other and its tokens land
+//! in vesting escrow, out of `sell_tokens`' reach until `claim_vested`
+//! releases them.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::{BondingCurve, CreatorVesting, GlobalState, LaunchConfig, TokenLaunch};
+use pump_clone::utils::PLATFORM_FEE_BASIS_POINTS;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn initial_buy_is_settled_into_vesting_escrow() {
+    let (creator, mint) = (Keypair::new(), Keypair::new());
+    let global_state = pump_clone::pda::global_state().0;
+    let fee_vault = Pubkey::find_program_address(&[b"fee_vault"], &pump_clone::ID).0;
+    let bonding_curve = pump_clone::pda::bonding_curve(&mint.pubkey()).0;
+    let sol_vault = pump_clone::pda::sol_vault(&mint.pubkey()).0;
+    let token_launch = Pubkey::find_program_address(&[b"token_launch", mint.pubkey().as_ref()], &pump_clone::ID).0;
+    let creator_vesting = Pubkey::find_program_address(&[b"vesting", mint.pubkey().as_ref()], &pump_clone::ID).0;
+    let vesting_token_account = get_associated_token_address(&creator_vesting, &mint.pubkey());
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    common::add_program_account(&mut program_test, global_state, &global, GlobalState::LEN);
+    let rent = Rent::default().minimum_balance(0);
+    common::add_system_account(&mut program_test, fee_vault, rent);
+    common::add_system_account(&mut program_test, creator.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    let initial_buy = LAMPORTS_PER_SOL;
+    let create = common::instruction(
+        common::create_token_accounts(&creator.pubkey(), &mint.pubkey(), true),
+        pump_clone::instruction::CreateToken {
+            name: "Escrowed".to_string(),
+            symbol: "ESC".to_string(),
+            uri: "https://example.com/esc.json".to_string(),
+            initial_buy,
+            config: LaunchConfig {
+                creator_vesting_duration: 30 * 24 * 60 * 60,
+                ..Default::default()
+            },
+        },
+    );
+    env.process(&[create], &[&creator, &mint]).await.unwrap();
+
+    let curve: BondingCurve = env.account(bonding_curve).await;
+    let launch: TokenLaunch = env.account(token_launch).await;
+    let vesting: CreatorVesting = env.account(creator_vesting).await;
+    assert_eq!(launch.bonding_curve, bonding_curve);
+    assert_eq!(curve.token_launch, token_launch);
+    let state: GlobalState = env.account(global_state).await;
+    assert_eq!(state.total_tokens_created, 1);
+
+    // Metaplex metadata exists, editable by the curve until it is locked
+    assert_eq!(common::metadata_record(&mut env, &mint.pubkey()).await, (bonding_curve, true));
+
+    // Every token the curve sold is in escrow, none with the creator
+    let bought = curve.initial_real_token_reserves - curve.real_token_reserves;
+    assert!(bought > 0);
+    assert_eq!(vesting.total_amount, bought);
+    assert_eq!(env.token_balance(vesting_token_account).await, bought);
+    assert_eq!(
+        env.token_balance(get_associated_token_address(&creator.pubkey(), &mint.pubkey())).await,
+        0
+    );
+
+    // The platform fee went to the fee vault and the rest of the buy, less
+    // the creator fee paid to the creator, to the curve
+    let platform_fee = initial_buy * PLATFORM_FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(env.lamports(fee_vault).await, rent + platform_fee);
+    assert_eq!(state.total_fees_collected, platform_fee);
+    assert_eq!(state.total_volume, initial_buy);
+    assert!(curve.real_sol_reserves > 0 && curve.real_sol_reserves < initial_buy - platform_fee);
+    assert_eq!(env.lamports(sol_vault).await, rent + curve.real_sol_reserves);
+}
+
+#[tokio::test]
+async fn escrowed_tokens_only_reach_sell_tokens_once_claimed() {
+    let mint = Keypair::new();
+    let launch = Launch::for_mint(mint.pubkey());
+    let creator = launch.creator.pubkey();
+    let creator_vesting = Pubkey::find_program_address(&[b"vesting", mint.pubkey().as_ref()], &pump_clone::ID).0;
+    let vesting_token_account = get_associated_token_address(&creator_vesting, &mint.pubkey());
+    let creator_tokens = get_associated_token_address(&creator, &mint.pubkey());
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    common::add_program_account(&mut program_test, launch.global_state, &global, GlobalState::LEN);
+    common::add_system_account(&mut program_test, launch.fee_vault, Rent::default().minimum_balance(0));
+    common::add_system_account(&mut program_test, creator, 10 * LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    let vesting_duration = 30 * 24 * 60 * 60;
+    let create = common::instruction(
+        common::create_token_accounts(&creator, &mint.pubkey(), true),
+        pump_clone::instruction::CreateToken {
+            name: "Escrowed".to_string(),
+            symbol: "ESC".to_string(),
+            uri: "https://example.com/esc.json".to_string(),
+            initial_buy: LAMPORTS_PER_SOL,
+            config: LaunchConfig { creator_vesting_duration: vesting_duration, ..Default::default() },
+        },
+    );
+    env.process(&[create], &[&launch.creator, &mint]).await.unwrap();
+    let vesting: CreatorVesting = env.account(creator_vesting).await;
+
+    // The creator has no position, so sells go without one
+    let sell = |token_amount: u64, associated_user: Pubkey| {
+        common::instruction(
+            pump_clone::accounts::SellTokens {
+                associated_user,
+                seller_position: None,
+                ..launch.sell_accounts(&creator)
+            },
+            pump_clone::instruction::SellTokens { token_amount, min_sol_output: 0, deadline: 0 },
+        )
+    };
+
+    // Nothing is in the creator's own account, and the escrow isn't theirs
+    // to sell from
+    assert!(env.process(&[sell(vesting.total_amount, creator_tokens)], &[&launch.creator]).await.is_err());
+    assert!(env
+        .process(&[sell(vesting.total_amount, vesting_token_account)], &[&launch.creator])
+        .await
+        .is_err());
+    assert_eq!(env.token_balance(vesting_token_account).await, vesting.total_amount);
+
+    // Halfway through, the vested half is claimed and only that much sells
+    env.warp_to_timestamp(common::START_TS + vesting_duration / 2).await;
+    env.process(
+        &[common::instruction(
+            pump_clone::accounts::ClaimVested {
+                creator,
+                creator_vesting,
+                vesting_token_account,
+                creator_token_account: creator_tokens,
+                global_state: launch.global_state,
+                token_program: spl_token::ID,
+            },
+            pump_clone::instruction::ClaimVested {},
+        )],
+        &[&launch.creator],
+    )
+    .await
+    .unwrap();
+    let claimed = env.token_balance(creator_tokens).await;
+    assert!(claimed > 0 && claimed < vesting.total_amount);
+    assert!(env.process(&[sell(claimed + 1, creator_tokens)], &[&launch.creator]).await.is_err());
+    env.process(&[sell(claimed, creator_tokens)], &[&launch.creator]).await.unwrap();
+    assert_eq!(env.token_balance(creator_tokens).await, 0);
+    assert_eq!(env.token_balance(vesting_token_account).await, vesting.total_amount - claimed);
+}
@@ -0,0 +1,71 @@
+//! A launch's community split applies to the creator fee on both sides:
+//! buys pay both shares out, sells pay the community share and accrue the
+//! creator's.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::events::Trade;
+use pump_clone::state::TokenLaunch;
+use pump_clone::utils::{calculate_fee, split_creator_fee, AUTO_SLIPPAGE, CREATOR_FEE_BASIS_POINTS};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn creator_fee_is_split_on_buys_and_sells() {
+    let launch = Launch::new();
+    let community_wallet = Pubkey::new_unique();
+    let mut token_launch = launch.token_launch_account();
+    token_launch.community_fee_bps = 2_500;
+    token_launch.community_wallet = community_wallet;
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &token_launch, &global);
+    common::add_system_account(&mut program_test, community_wallet, LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+
+    let sol_amount = 2 * LAMPORTS_PER_SOL;
+    let (creator_share, community_share) =
+        split_creator_fee(calculate_fee(sol_amount, CREATOR_FEE_BASIS_POINTS).unwrap(), 2_500).unwrap();
+    assert!(community_share > 0);
+    let creator_before = env.lamports(launch.creator.pubkey()).await;
+    let mut accounts = launch.buy_accounts(&trader.pubkey());
+    accounts.community_wallet = Some(community_wallet);
+    let buy = common::instruction(
+        accounts,
+        pump_clone::instruction::BuyTokens { sol_amount, max_slippage_bps: AUTO_SLIPPAGE, deadline: 0 },
+    );
+    env.process(&[buy], &[&trader]).await.unwrap();
+    assert_eq!(env.lamports(launch.creator.pubkey()).await - creator_before, creator_share);
+    assert_eq!(env.lamports(community_wallet).await, LAMPORTS_PER_SOL + community_share);
+
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    let held = env
+        .token_balance(get_associated_token_address(&trader.pubkey(), &launch.mint))
+        .await;
+    let sell = |community_wallet| {
+        let mut accounts = launch.sell_accounts(&trader.pubkey());
+        accounts.community_wallet = community_wallet;
+        common::instruction(
+            accounts,
+            pump_clone::instruction::SellTokens { token_amount: held / 2, min_sol_output: 0, deadline: 0 },
+        )
+    };
+    // The community's share can't be skipped by leaving its wallet out
+    assert!(env.process(&[sell(None)], &[&trader]).await.is_err());
+    let logs = env.process(&[sell(Some(community_wallet))], &[&trader]).await.unwrap();
+
+    let sell_creator_fee = common::events::<Trade>(&logs)[0].creator_fee;
+    let (creator_share, sell_community_share) = split_creator_fee(sell_creator_fee, 2_500).unwrap();
+    assert!(sell_community_share > 0);
+    assert_eq!(
+        env.lamports(community_wallet).await,
+        LAMPORTS_PER_SOL + community_share + sell_community_share
+    );
+    let token_launch: TokenLaunch = env.account(launch.token_launch).await;
+    assert_eq!(token_launch.accrued_creator_fees, creator_share);
+}
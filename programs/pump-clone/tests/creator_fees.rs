@@ -0,0 +1,49 @@
+//! Buy-side creator fees follow the launch's fee destination.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::{Launch, TestEnv};
+use pump_clone::utils::{AUTO_SLIPPAGE, CREATOR_FEE_BASIS_POINTS};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn buy_pays_the_creator_fee_to_the_fee_destination() {
+    let launch = Launch::new();
+    let destination = Pubkey::new_unique();
+    let mut token_launch = launch.token_launch_account();
+    token_launch.creator_fee_destination = destination;
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &token_launch, &global);
+    common::add_system_account(&mut program_test, destination, LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let creator_before = env.lamports(launch.creator.pubkey()).await;
+
+    // The curve's creator is no longer where the fee goes
+    assert!(env
+        .process(&[launch.buy_ix(&buyer.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&buyer])
+        .await
+        .is_err());
+
+    let mut accounts = launch.buy_accounts(&buyer.pubkey());
+    accounts.creator = destination;
+    let buy = common::instruction(
+        accounts,
+        pump_clone::instruction::BuyTokens {
+            sol_amount: LAMPORTS_PER_SOL,
+            max_slippage_bps: AUTO_SLIPPAGE,
+            deadline: 0,
+        },
+    );
+    env.process(&[buy], &[&buyer]).await.unwrap();
+
+    assert_eq!(
+        env.lamports(destination).await,
+        LAMPORTS_PER_SOL + LAMPORTS_PER_SOL * CREATOR_FEE_BASIS_POINTS as u64 / 10_000
+    );
+    assert_eq!(env.lamports(launch.creator.pubkey()).await, creator_before);
+}
@@ -0,0 +1,38 @@
+//! The "use my stored default" slippage sentinel needs a `UserSettings`
+//! account; without one the trade is rejected instead of running unbounded.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::utils::{AUTO_SLIPPAGE, USE_DEFAULT_SLIPPAGE};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn default_slippage_without_settings_is_rejected() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let trader_tokens = get_associated_token_address(&trader.pubkey(), &launch.mint);
+
+    assert!(env
+        .process(&[launch.buy_ix(&trader.pubkey(), LAMPORTS_PER_SOL, USE_DEFAULT_SLIPPAGE)], &[&trader])
+        .await
+        .is_err());
+    env.process(&[launch.buy_ix(&trader.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&trader])
+        .await
+        .unwrap();
+
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    let held = env.token_balance(trader_tokens).await;
+    assert!(env
+        .process(&[launch.sell_ix(&trader.pubkey(), held, USE_DEFAULT_SLIPPAGE)], &[&trader])
+        .await
+        .is_err());
+    assert_eq!(env.token_balance(trader_tokens).await, held);
+}
@@ -0,0 +1,63 @@
+//! Platform fees from both trade directions land in the one global fee vault.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::GlobalState;
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn initialize_global_state_funds_the_fee_vault() {
+    let authority = Keypair::new();
+    let mut program_test = common::program_test();
+    common::add_system_account(&mut program_test, authority.pubkey(), LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+    let fee_vault = Launch::new().fee_vault;
+
+    let initialize = common::instruction(
+        pump_clone::accounts::InitializeGlobalState {
+            authority: authority.pubkey(),
+            global_state: pump_clone::pda::global_state().0,
+            fee_vault,
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::InitializeGlobalState { fee_recipient: Pubkey::new_unique() },
+    );
+    env.process(&[initialize], &[&authority]).await.unwrap();
+
+    assert_eq!(env.lamports(fee_vault).await, Rent::default().minimum_balance(0));
+}
+
+#[tokio::test]
+async fn sell_fees_are_collected_in_the_global_fee_vault() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+
+    env.process(&[launch.buy_ix(&trader.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&trader])
+        .await
+        .unwrap();
+    let after_buy: GlobalState = env.account(launch.global_state).await;
+    let vault_after_buy = env.lamports(launch.fee_vault).await;
+
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    let held = env
+        .token_balance(get_associated_token_address(&trader.pubkey(), &launch.mint))
+        .await;
+    env.process(&[launch.sell_ix(&trader.pubkey(), held, 0)], &[&trader])
+        .await
+        .unwrap();
+
+    let after_sell: GlobalState = env.account(launch.global_state).await;
+    let sell_fee = after_sell.total_fees_collected - after_buy.total_fees_collected;
+    assert!(sell_fee > 0);
+    assert_eq!(env.lamports(launch.fee_vault).await, vault_after_buy + sell_fee);
+}
@@ -0,0 +1,64 @@
+//! `get_quote` predicts exactly what `buy_tokens` and `sell_tokens` then do
+//! to the same curve.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::{BondingCurve, TradeDirection, TradeQuote};
+use pump_clone::utils::{canonical_price, AUTO_SLIPPAGE};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+fn get_quote(launch: &Launch, direction: TradeDirection, amount_in: u64) -> Instruction {
+    common::instruction(
+        pump_clone::accounts::GetQuote {
+            global_state: launch.global_state,
+            token_launch: launch.token_launch,
+            bonding_curve: launch.bonding_curve,
+        },
+        pump_clone::instruction::GetQuote { direction, amount_in },
+    )
+}
+
+async fn spot_price(env: &mut TestEnv, launch: &Launch) -> u64 {
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap()
+}
+
+#[tokio::test]
+async fn quotes_match_the_trades_that_follow() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let trader_tokens = get_associated_token_address(&trader.pubkey(), &launch.mint);
+
+    let sol_in = LAMPORTS_PER_SOL;
+    let buy_quote: TradeQuote = env.view(get_quote(&launch, TradeDirection::Buy, sol_in)).await;
+    let fee_vault_before = env.lamports(launch.fee_vault).await;
+    env.process(&[launch.buy_ix(&trader.pubkey(), sol_in, AUTO_SLIPPAGE)], &[&trader])
+        .await
+        .unwrap();
+
+    assert_eq!(env.token_balance(trader_tokens).await, buy_quote.amount_out);
+    assert_eq!(env.lamports(launch.fee_vault).await - fee_vault_before, buy_quote.platform_fee);
+    assert_eq!(spot_price(&mut env, &launch).await, buy_quote.price_after);
+
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    let tokens_in = buy_quote.amount_out / 2;
+    let sell_quote: TradeQuote = env.view(get_quote(&launch, TradeDirection::Sell, tokens_in)).await;
+    let (trader_before, fee_vault_before) =
+        (env.lamports(trader.pubkey()).await, env.lamports(launch.fee_vault).await);
+    env.process(&[launch.sell_ix(&trader.pubkey(), tokens_in, 0)], &[&trader])
+        .await
+        .unwrap();
+
+    assert_eq!(env.lamports(trader.pubkey()).await - trader_before, sell_quote.amount_out);
+    assert_eq!(env.lamports(launch.fee_vault).await - fee_vault_before, sell_quote.platform_fee);
+    assert_eq!(spot_price(&mut env, &launch).await, sell_quote.price_after);
+}
@@ -0,0 +1,233 @@
+//! Claiming a finalized holder-reward round: as SOL, or compounded into
+//! tokens bought from the curve under the usual buy guards and booked like
+//! any other buy.
+
+mod common;
+
+use anchor_lang::AccountSerialize;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::events::{CurveCompleted, Trade};
+use pump_clone::state::{BondingCurve, GlobalState, HolderRewards, UserPosition, UserSettings};
+use pump_clone::utils::{REAL_TOKEN_RESERVES, VIRTUAL_TOKEN_RESERVES};
+use solana_program_test::ProgramTest;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+
+const ROUND_REWARDS: u64 = LAMPORTS_PER_SOL;
+const HELD: u64 = 1_000_000_000;
+
+fn holder_rewards_address(launch: &Launch) -> Pubkey {
+    Pubkey::find_program_address(&[b"holder_rewards", launch.mint.as_ref()], &pump_clone::ID).0
+}
+
+fn user_settings_address(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_settings", user.as_ref()], &pump_clone::ID).0
+}
+
+/// A closed round of `ROUND_REWARDS`, funded on top of the account's rent.
+fn seed_round(program_test: &mut ProgramTest, launch: &Launch) {
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"holder_rewards", launch.mint.as_ref()], &pump_clone::ID);
+    let rewards = HolderRewards {
+        mint: launch.mint,
+        pending_rewards: 0,
+        round: 1,
+        round_open: false,
+        snapshot_timestamp: common::START_TS,
+        total_weight: 2,
+        round_rewards: ROUND_REWARDS,
+        round_claimed: 0,
+        bump,
+    };
+    let mut data = Vec::with_capacity(HolderRewards::LEN);
+    rewards.try_serialize(&mut data).unwrap();
+    data.resize(HolderRewards::LEN, 0);
+    program_test.add_account(
+        address,
+        Account {
+            lamports: Rent::default().minimum_balance(HolderRewards::LEN) + ROUND_REWARDS,
+            data,
+            owner: pump_clone::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+/// A holder with half the round's weight and `HELD` tokens.
+fn seed_holder(program_test: &mut ProgramTest, launch: &Launch, holder: &Keypair, compound: bool) {
+    let user = holder.pubkey();
+    common::add_system_account(program_test, user, LAMPORTS_PER_SOL);
+    common::add_token_account(
+        program_test,
+        get_associated_token_address(&user, &launch.mint),
+        launch.mint,
+        user,
+        HELD,
+    );
+    let position = UserPosition {
+        user,
+        token_launch: launch.token_launch,
+        mint: launch.mint,
+        token_balance: HELD,
+        tokens_bought: HELD,
+        reward_round: 1,
+        reward_weight: 1,
+        ..Default::default()
+    };
+    common::add_program_account(program_test, launch.user_position(&user), &position, UserPosition::LEN);
+    let settings = UserSettings {
+        user,
+        compound_holder_rewards: compound,
+        bump: Pubkey::find_program_address(&[b"user_settings", user.as_ref()], &pump_clone::ID).1,
+        ..Default::default()
+    };
+    common::add_program_account(program_test, user_settings_address(&user), &settings, UserSettings::LEN);
+}
+
+fn claim(launch: &Launch, user: &Pubkey) -> Instruction {
+    common::instruction(
+        pump_clone::accounts::ClaimHolderRewards {
+            user: *user,
+            holder_rewards: holder_rewards_address(launch),
+            user_position: launch.user_position(user),
+            user_settings: Some(user_settings_address(user)),
+            bonding_curve: Some(launch.bonding_curve),
+            token_launch: Some(launch.token_launch),
+            user_blacklist: Some(
+                Pubkey::find_program_address(&[b"blacklist", user.as_ref()], &pump_clone::ID).0,
+            ),
+            user_whitelist: Some(
+                Pubkey::find_program_address(
+                    &[b"whitelist", launch.mint.as_ref(), user.as_ref()],
+                    &pump_clone::ID,
+                )
+                .0,
+            ),
+            user_referral: None,
+            market_maker: None,
+            instructions_sysvar: None,
+            sol_vault: Some(launch.sol_vault),
+            curve_token_account: Some(launch.curve_token_account),
+            user_token_account: Some(get_associated_token_address(user, &launch.mint)),
+            global_state: launch.global_state,
+            token_program: Some(spl_token::ID),
+        },
+        pump_clone::instruction::ClaimHolderRewards {},
+    )
+}
+
+#[tokio::test]
+async fn compounding_claim_buys_tokens_where_a_plain_claim_pays_sol() {
+    let launch = Launch::new();
+    let (compounder, claimer) = (Keypair::new(), Keypair::new());
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    seed_round(&mut program_test, &launch);
+    seed_holder(&mut program_test, &launch, &compounder, true);
+    seed_holder(&mut program_test, &launch, &claimer, false);
+    let mut env = TestEnv::start(program_test).await;
+    let share = ROUND_REWARDS / 2;
+    let sol_vault_before = env.lamports(launch.sol_vault).await;
+
+    // A plain claim pays the share in lamports and leaves the tokens alone
+    env.process(&[claim(&launch, &claimer.pubkey())], &[&claimer]).await.unwrap();
+    assert_eq!(env.lamports(claimer.pubkey()).await, LAMPORTS_PER_SOL + share);
+    assert_eq!(
+        env.token_balance(get_associated_token_address(&claimer.pubkey(), &launch.mint)).await,
+        HELD
+    );
+
+    // A compounding claim spends the same share on the curve instead
+    env.process(&[claim(&launch, &compounder.pubkey())], &[&compounder]).await.unwrap();
+    assert_eq!(env.lamports(compounder.pubkey()).await, LAMPORTS_PER_SOL);
+    let compounded = env
+        .token_balance(get_associated_token_address(&compounder.pubkey(), &launch.mint))
+        .await
+        - HELD;
+    assert!(compounded > 0);
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_eq!(curve.real_sol_reserves, share);
+    assert_eq!(env.lamports(launch.sol_vault).await, sol_vault_before + share);
+    let position: UserPosition = env.account(launch.user_position(&compounder.pubkey())).await;
+    assert_eq!(position.token_balance, HELD + compounded);
+
+    // Both shares are booked against the round, and neither claims twice
+    let rewards: HolderRewards = env.account(holder_rewards_address(&launch)).await;
+    assert_eq!(rewards.round_claimed, ROUND_REWARDS);
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    assert!(env.process(&[claim(&launch, &claimer.pubkey())], &[&claimer]).await.is_err());
+}
+
+#[tokio::test]
+async fn compounding_claim_is_blocked_like_any_other_buy() {
+    let launch = Launch::new();
+    let compounder = Keypair::new();
+    let mut program_test = common::program_test();
+    let mut global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    // Caps every buy, compounded rewards included, below the share
+    global.max_buy_base = ROUND_REWARDS / 4;
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    seed_round(&mut program_test, &launch);
+    seed_holder(&mut program_test, &launch, &compounder, true);
+    let mut env = TestEnv::start(program_test).await;
+
+    assert!(env.process(&[claim(&launch, &compounder.pubkey())], &[&compounder]).await.is_err());
+    assert_eq!(
+        env.token_balance(get_associated_token_address(&compounder.pubkey(), &launch.mint)).await,
+        HELD
+    );
+}
+
+#[tokio::test]
+async fn compounding_claim_can_complete_the_curve() {
+    let launch = Launch::new();
+    let compounder = Keypair::new();
+    let share = ROUND_REWARDS / 2;
+    // A deeper virtual SOL reserve leaves tokens in the curve when the raise
+    // is half a share short of the 85 SOL goal
+    let initial_virtual_sol = 60 * LAMPORTS_PER_SOL;
+    let raised = 85 * LAMPORTS_PER_SOL - share / 2;
+    let virtual_token_reserves =
+        (VIRTUAL_TOKEN_RESERVES as u128 * initial_virtual_sol as u128 / (initial_virtual_sol + raised) as u128) as u64;
+    let curve = BondingCurve {
+        virtual_sol_reserves: initial_virtual_sol + raised,
+        virtual_token_reserves,
+        real_sol_reserves: raised,
+        real_token_reserves: REAL_TOKEN_RESERVES - (VIRTUAL_TOKEN_RESERVES - virtual_token_reserves),
+        initial_virtual_sol_reserves: initial_virtual_sol,
+        ..launch.fresh_curve()
+    };
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    seed_round(&mut program_test, &launch);
+    seed_holder(&mut program_test, &launch, &compounder, true);
+    let mut env = TestEnv::start(program_test).await;
+
+    let logs = env.process(&[claim(&launch, &compounder.pubkey())], &[&compounder]).await.unwrap();
+    let compounded = env
+        .token_balance(get_associated_token_address(&compounder.pubkey(), &launch.mint))
+        .await
+        - HELD;
+
+    // The share counts as volume and completes the raise
+    let after: BondingCurve = env.account(launch.bonding_curve).await;
+    assert!(after.complete);
+    assert_eq!(after.real_sol_reserves, raised + share);
+    assert_eq!(after.total_volume, share);
+    assert!(after.ath_price > 0);
+    let state: GlobalState = env.account(launch.global_state).await;
+    assert_eq!(state.total_volume, share);
+    let completed = &common::events::<CurveCompleted>(&logs)[0];
+    assert_eq!(completed.real_sol_reserves, raised + share);
+    let trade = &common::events::<Trade>(&logs)[0];
+    assert_eq!((trade.sol_amount, trade.token_amount), (share, compounded));
+    assert_eq!((trade.creator_fee, trade.platform_fee), (0, 0));
+}
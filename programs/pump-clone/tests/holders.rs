@@ -0,0 +1,45 @@
+//! The curve's `holders_count` counts wallets, not purchases.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::BondingCurve;
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn repeat_buys_count_once_and_selling_out_drops_the_holder() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let holders = |curve: BondingCurve| curve.holders_count;
+
+    for step in 1..=2 {
+        env.warp_to_timestamp(common::START_TS + step).await;
+        env.process(&[launch.buy_ix(&trader.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&trader])
+            .await
+            .unwrap();
+        assert_eq!(holders(env.account(launch.bonding_curve).await), 1);
+    }
+
+    let held = env
+        .token_balance(get_associated_token_address(&trader.pubkey(), &launch.mint))
+        .await;
+    env.warp_to_timestamp(common::START_TS + 3).await;
+    env.process(&[launch.sell_ix(&trader.pubkey(), held / 2, 0)], &[&trader])
+        .await
+        .unwrap();
+    assert_eq!(holders(env.account(launch.bonding_curve).await), 1);
+
+    env.warp_to_timestamp(common::START_TS + 4).await;
+    env.process(&[launch.sell_ix(&trader.pubkey(), held - held / 2, 0)], &[&trader])
+        .await
+        .unwrap();
+    assert_eq!(holders(env.account(launch.bonding_curve).await), 0);
+}
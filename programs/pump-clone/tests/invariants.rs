@@ -0,0 +1,220 @@
+//! Randomized invariant harness for the bonding-curve math.
+//!
+//! Each seed drives a sequence of buys and sells against a fresh curve and
+//! checks after every step that reserves stay consistent, `k` never shrinks,
+//! fees never exceed volume and no trader can extract more SOL than was put
+//! in. Trades are priced through `quote_trade`, the same quote the handlers
+//! settle, so the harness can't pass on math the program doesn't run. The
+//! seeded regression cases at the bottom each reproduce a bug the
+//! harness caught in the original `BondingCurveState` math.
+//!
+//! `program_invariants.rs` runs the same kind of sequences through the
+//! program's handlers in a program-test bank.
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use pump_clone::state::{TradeDirection, TradeQuote};
+use pump_clone::utils::{
+    quote_trade, BondingCurveState, CREATOR_FEE_BASIS_POINTS, PLATFORM_FEE_BASIS_POINTS,
+    VIRTUAL_SOL_RESERVES,
+};
+
+/// Small deterministic PRNG so failures replay from the seed alone.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        self.0 >> 33
+    }
+
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low + 1)
+    }
+}
+
+#[derive(Default)]
+struct Totals {
+    tokens_held: u64,
+    sol_deposited: u128,
+    sol_withdrawn: u128,
+    volume: u128,
+    fees: u128,
+}
+
+struct Harness {
+    curve: BondingCurveState,
+    totals: Totals,
+}
+
+impl Harness {
+    fn new(curve: BondingCurveState) -> Self {
+        Self {
+            curve,
+            totals: Totals::default(),
+        }
+    }
+
+    /// Prices the trade with the same quote `get_quote` serves and the buy
+    /// and sell handlers settle.
+    fn quote(&self, direction: TradeDirection, amount_in: u64) -> Option<TradeQuote> {
+        quote_trade(
+            &self.curve,
+            direction,
+            amount_in,
+            CREATOR_FEE_BASIS_POINTS,
+            PLATFORM_FEE_BASIS_POINTS,
+            0,
+        )
+        .ok()
+    }
+
+    fn buy(&mut self, sol_amount: u64) -> Option<u64> {
+        let quote = self.quote(TradeDirection::Buy, sol_amount)?;
+        let fee = quote.creator_fee + quote.platform_fee;
+        let net = sol_amount - fee;
+        let tokens = quote.amount_out;
+        if tokens == 0 || tokens > self.curve.real_token_reserves {
+            return None;
+        }
+
+        let k_before = self.curve.k().unwrap();
+        self.curve.update_after_buy(net, tokens).unwrap();
+        self.curve.real_token_reserves -= tokens;
+        assert!(self.curve.k().unwrap() >= k_before, "buy shrank k");
+
+        self.totals.tokens_held += tokens;
+        self.totals.sol_deposited += net as u128;
+        self.totals.volume += sol_amount as u128;
+        self.totals.fees += fee as u128;
+        Some(tokens)
+    }
+
+    fn sell(&mut self, tokens: u64) -> Option<u64> {
+        let quote = self.quote(TradeDirection::Sell, tokens)?;
+        let fee = quote.creator_fee + quote.platform_fee;
+        let gross = quote.amount_out + fee;
+        if gross == 0 || gross > self.curve.real_sol_reserves {
+            return None;
+        }
+
+        let k_before = self.curve.k().unwrap();
+        self.curve.update_after_sell(gross, tokens).unwrap();
+        self.curve.real_token_reserves += tokens;
+        assert!(self.curve.k().unwrap() >= k_before, "sell shrank k");
+
+        self.totals.tokens_held -= tokens;
+        self.totals.sol_withdrawn += gross as u128;
+        self.totals.volume += gross as u128;
+        self.totals.fees += fee as u128;
+        Some(quote.amount_out)
+    }
+
+    fn check_invariants(&self, initial_virtual_sol: u64) {
+        let curve = &self.curve;
+        let totals = &self.totals;
+
+        assert!(curve.virtual_token_reserves > 0);
+        assert_eq!(
+            curve.real_sol_reserves as u128,
+            totals.sol_deposited - totals.sol_withdrawn,
+            "real SOL reserves drifted from net deposits"
+        );
+        assert!(
+            curve.virtual_sol_reserves >= initial_virtual_sol,
+            "traders extracted more SOL than they deposited"
+        );
+        assert!(totals.fees <= totals.volume, "fees exceeded volume");
+    }
+}
+
+fn run_seed(seed: u64, steps: usize) {
+    let mut rng = Lcg(seed);
+    let mut harness = Harness::new(BondingCurveState::new());
+
+    for _ in 0..steps {
+        if harness.totals.tokens_held == 0 || rng.range(0, 2) > 0 {
+            let sol_amount = rng.range(LAMPORTS_PER_SOL / 100, 5 * LAMPORTS_PER_SOL);
+            harness.buy(sol_amount);
+        } else {
+            let tokens = rng.range(1, harness.totals.tokens_held);
+            harness.sell(tokens);
+        }
+
+        harness.check_invariants(VIRTUAL_SOL_RESERVES);
+    }
+
+    // Unwinding every position must never return more than went in
+    if harness.totals.tokens_held > 0 {
+        harness.sell(harness.totals.tokens_held);
+        harness.check_invariants(VIRTUAL_SOL_RESERVES);
+    }
+}
+
+#[test]
+fn randomized_trade_sequences_hold_invariants() {
+    for seed in 1..=64 {
+        run_seed(seed, 200);
+    }
+}
+
+#[test]
+fn round_trip_is_never_profitable() {
+    let mut rng = Lcg(0xB0B);
+
+    for _ in 0..500 {
+        let mut harness = Harness::new(BondingCurveState::new());
+        let sol_amount = rng.range(LAMPORTS_PER_SOL / 1_000, 10 * LAMPORTS_PER_SOL);
+
+        let tokens = harness.buy(sol_amount).unwrap();
+        let proceeds = harness.sell(tokens).unwrap();
+        assert!(proceeds < sol_amount);
+    }
+}
+
+/// Seed 1: `k` was computed as `u64`, so any buy against the default
+/// reserves (30 SOL * 1.073B tokens) overflowed before pricing.
+#[test]
+fn regression_default_curve_buy_overflowed_k() {
+    let curve = BondingCurveState::new();
+    assert!(curve.calculate_tokens_for_sol(LAMPORTS_PER_SOL).unwrap() > 0);
+    assert!(curve.calculate_buy_price(1_000_000).unwrap() > 0);
+    assert!(curve.calculate_sell_price(1_000_000).unwrap() > 0);
+}
+
+/// Seed 2: floor division on the new reserve handed rounding to the trader
+/// and let `k` shrink on every buy.
+#[test]
+fn regression_buy_rounding_shrank_k() {
+    let mut harness = Harness::new(BondingCurveState {
+        virtual_sol_reserves: 10,
+        virtual_token_reserves: 7,
+        real_sol_reserves: 0,
+        real_token_reserves: 7,
+    });
+
+    // Floor division priced 3 lamports at 2 tokens, leaving k = 13 * 5 = 65
+    let tokens = harness.curve.calculate_tokens_for_sol(3).unwrap();
+    assert_eq!(tokens, 1);
+
+    let k_before = harness.curve.k().unwrap();
+    harness.curve.update_after_buy(3, tokens).unwrap();
+    assert!(harness.curve.k().unwrap() >= k_before);
+}
+
+/// Seed 3: the same floor division on sells paid out one lamport too many,
+/// letting a buy-then-sell loop drain the virtual SOL reserve.
+#[test]
+fn regression_sell_rounding_overpaid() {
+    let curve = BondingCurveState {
+        virtual_sol_reserves: 13,
+        virtual_token_reserves: 6,
+        real_sol_reserves: 3,
+        real_token_reserves: 1,
+    };
+
+    // k = 78; selling 1 token leaves ceil(78 / 7) = 12 lamports, paying 1
+    assert_eq!(curve.calculate_sell_price(1).unwrap(), 1);
+}
@@ -0,0 +1,92 @@
+//! `lp_yield` reads the locked position and the pool's reserves on chain
+//! rather than trusting caller-supplied figures.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::TestEnv;
+use pump_clone::events::YieldEstimate;
+use pump_clone::state::LpLock;
+use pump_clone::utils::annualized_yield_bps;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+const QUARTER: i64 = 90 * 86_400;
+const LP_SUPPLY: u64 = 1_000_000;
+
+struct Pool {
+    lp_lock: Pubkey,
+    lp_mint: Pubkey,
+    sol_vault: Pubkey,
+    token_vault: Pubkey,
+}
+
+impl Pool {
+    fn lp_yield(&self, sol_vault: Pubkey) -> Instruction {
+        common::instruction(
+            pump_clone::accounts::LpYield {
+                lp_lock: self.lp_lock,
+                lp_mint: self.lp_mint,
+                pool_sol_vault: sol_vault,
+                pool_token_vault: self.token_vault,
+            },
+            pump_clone::instruction::LpYield {},
+        )
+    }
+}
+
+#[tokio::test]
+async fn yield_comes_from_pool_growth_since_the_lock() {
+    let (mint, seed_sol, seed_tokens) = (Pubkey::new_unique(), 80 * LAMPORTS_PER_SOL, 200_000_000 * 1_000_000);
+    let (lp_lock, bump) = Pubkey::find_program_address(&[b"lp_lock", mint.as_ref()], &pump_clone::ID);
+    let pool = Pool {
+        lp_lock,
+        lp_mint: Pubkey::new_unique(),
+        sol_vault: Pubkey::new_unique(),
+        token_vault: Pubkey::new_unique(),
+    };
+
+    let mut program_test = common::program_test();
+    common::add_program_account(
+        &mut program_test,
+        lp_lock,
+        &LpLock {
+            mint,
+            lp_mint: pool.lp_mint,
+            locked_at: common::START_TS,
+            lp_amount: LP_SUPPLY,
+            pool_sol_vault: pool.sol_vault,
+            pool_token_vault: pool.token_vault,
+            seed_sol_amount: seed_sol,
+            seed_token_amount: seed_tokens,
+            seed_lp_supply: LP_SUPPLY,
+            bump,
+            ..Default::default()
+        },
+        LpLock::LEN,
+    );
+    common::add_mint(&mut program_test, pool.lp_mint, None, LP_SUPPLY);
+    // Fees left in the pool since migration grew both sides by 1%
+    let (sol_reserve, token_reserve) = (seed_sol * 101 / 100, seed_tokens * 101 / 100);
+    common::add_token_account(&mut program_test, pool.sol_vault, Pubkey::new_unique(), Pubkey::new_unique(), sol_reserve);
+    common::add_token_account(&mut program_test, pool.token_vault, mint, Pubkey::new_unique(), token_reserve);
+    // Any other token account can't stand in for the pool's vault
+    let spoofed_vault = Pubkey::new_unique();
+    common::add_token_account(&mut program_test, spoofed_vault, Pubkey::new_unique(), Pubkey::new_unique(), 10 * sol_reserve);
+    let mut env = TestEnv::start(program_test).await;
+
+    env.warp_to_timestamp(common::START_TS + QUARTER).await;
+    assert!(env.process(&[pool.lp_yield(spoofed_vault)], &[]).await.is_err());
+    let logs = env.process(&[pool.lp_yield(pool.sol_vault)], &[]).await.unwrap();
+    let estimate = &common::events::<YieldEstimate>(&logs)[0];
+
+    assert_eq!(estimate.mint, mint);
+    assert_eq!(estimate.elapsed_seconds, QUARTER);
+    assert_eq!(estimate.locked_value, 2 * sol_reserve);
+    assert!(estimate.fees_accrued.abs_diff(estimate.locked_value / 101) <= 1);
+    assert!(estimate.apy_bps > 0);
+    assert_eq!(
+        estimate.apy_bps,
+        annualized_yield_bps(estimate.fees_accrued, estimate.locked_value, QUARTER)
+    );
+}
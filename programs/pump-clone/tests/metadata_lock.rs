@@ -0,0 +1,138 @@
+//! `lock_metadata` freezes the launch's metadata for good, and both metadata
+//! instructions only sign for the mint's own Metaplex account, the one
+//! `create_token` created with the curve as update authority.
+
+mod common;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::metadata::mpl_token_metadata;
+use common::TestEnv;
+use pump_clone::accounts::CreateToken;
+use pump_clone::error::PumpCloneError;
+use pump_clone::state::{GlobalState, LaunchConfig, MetadataUpdate, TokenLaunch};
+use solana_sdk::instruction::{Instruction, InstructionError};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::TransactionError;
+
+/// Creates a launch through `create_token`, metadata included.
+async fn create_launch(creator: &Keypair) -> (TestEnv, CreateToken) {
+    let mint = Keypair::new();
+    let accounts = common::create_token_accounts(&creator.pubkey(), &mint.pubkey(), false);
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    common::add_program_account(&mut program_test, accounts.global_state, &global, GlobalState::LEN);
+    common::add_system_account(&mut program_test, accounts.fee_vault, Rent::default().minimum_balance(0));
+    common::add_system_account(&mut program_test, creator.pubkey(), 10 * LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    let create = common::instruction(
+        common::create_token_accounts(&creator.pubkey(), &mint.pubkey(), false),
+        pump_clone::instruction::CreateToken {
+            name: "Locked".to_string(),
+            symbol: "LCK".to_string(),
+            uri: "https://example.com/v0.json".to_string(),
+            initial_buy: 0,
+            config: LaunchConfig::default(),
+        },
+    );
+    env.process(&[create], &[creator, &mint]).await.unwrap();
+    (env, accounts)
+}
+
+fn lock_metadata(creator: &Pubkey, launch: &CreateToken, metadata: Pubkey) -> Instruction {
+    common::instruction(
+        pump_clone::accounts::LockMetadata {
+            creator: *creator,
+            token_launch: launch.token_launch,
+            bonding_curve: launch.bonding_curve,
+            metadata,
+            global_state: launch.global_state,
+            token_metadata_program: mpl_token_metadata::ID,
+        },
+        pump_clone::instruction::LockMetadata {},
+    )
+}
+
+fn update_metadata(creator: &Pubkey, launch: &CreateToken, metadata: Pubkey, update: MetadataUpdate) -> Instruction {
+    common::instruction(
+        pump_clone::accounts::UpdateTokenMetadata {
+            creator: *creator,
+            token_launch: launch.token_launch,
+            bonding_curve: launch.bonding_curve,
+            metadata,
+            global_state: launch.global_state,
+            token_metadata_program: mpl_token_metadata::ID,
+        },
+        pump_clone::instruction::UpdateTokenMetadata { update },
+    )
+}
+
+fn new_uri() -> MetadataUpdate {
+    MetadataUpdate { uri: Some("https://example.com/v2.json".to_string()), ..Default::default() }
+}
+
+fn program_error(error: PumpCloneError) -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(ERROR_CODE_OFFSET + error as u32))
+}
+
+#[tokio::test]
+async fn metadata_account_must_be_the_mints_pda() {
+    let creator = Keypair::new();
+    let (mut env, launch) = create_launch(&creator).await;
+    let other = Pubkey::new_unique();
+
+    assert_eq!(
+        env.process(&[update_metadata(&creator.pubkey(), &launch, other, new_uri())], &[&creator])
+            .await
+            .unwrap_err(),
+        program_error(PumpCloneError::InvalidMetadataAccount)
+    );
+    assert_eq!(
+        env.process(&[lock_metadata(&creator.pubkey(), &launch, other)], &[&creator])
+            .await
+            .unwrap_err(),
+        program_error(PumpCloneError::InvalidMetadataAccount)
+    );
+    let token_launch: TokenLaunch = env.account(launch.token_launch).await;
+    assert!(!token_launch.metadata_locked);
+    assert_eq!(common::metadata_record(&mut env, &launch.mint).await, (launch.bonding_curve, true));
+}
+
+#[tokio::test]
+async fn updates_fail_once_metadata_is_locked() {
+    let creator = Keypair::new();
+    let (mut env, launch) = create_launch(&creator).await;
+    let metadata = launch.metadata;
+
+    // Editable until locked
+    let first_uri = MetadataUpdate { uri: Some("https://example.com/v1.json".to_string()), ..Default::default() };
+    env.process(&[update_metadata(&creator.pubkey(), &launch, metadata, first_uri)], &[&creator])
+        .await
+        .unwrap();
+
+    env.process(&[lock_metadata(&creator.pubkey(), &launch, metadata)], &[&creator])
+        .await
+        .unwrap();
+    let token_launch: TokenLaunch = env.account(launch.token_launch).await;
+    assert!(token_launch.metadata_locked);
+    assert_eq!(token_launch.metadata_uri, "https://example.com/v1.json");
+    // Metaplex itself now refuses updates, whoever signs
+    assert_eq!(common::metadata_record(&mut env, &launch.mint).await, (launch.bonding_curve, false));
+
+    // Neither the URI nor the socials can change afterwards
+    let socials = MetadataUpdate { website: Some("https://example.com".to_string()), ..Default::default() };
+    for update in [new_uri(), socials] {
+        assert_eq!(
+            env.process(&[update_metadata(&creator.pubkey(), &launch, metadata, update)], &[&creator])
+                .await
+                .unwrap_err(),
+            program_error(PumpCloneError::MetadataLocked)
+        );
+    }
+    let after: TokenLaunch = env.account(launch.token_launch).await;
+    assert_eq!(after.metadata_uri, token_launch.metadata_uri);
+    assert_eq!(after.website, token_launch.website);
+}
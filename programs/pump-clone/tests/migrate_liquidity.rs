@@ -0,0 +1,156 @@
+//! `migrate_liquidity` against the Raydium stand-in: the pool is seeded with
+//! exactly what `preview_migration` reported, out of the curve's own token
+//! account and SOL vault, and the LP ends up in the lock. Only a curve
+//! `graduate` has stamped, cooldown and all, can migrate.
+
+mod common;
+
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use anchor_lang::solana_program::clock::Clock;
+use common::{Launch, Migration, TestEnv};
+use pump_clone::error::PumpCloneError;
+use pump_clone::events::LiquidityMigrated;
+use pump_clone::state::{BondingCurve, GlobalState, LpLock, MigrationPreview, TokenLaunch};
+use pump_clone::utils::{MAX_TOKEN_SUPPLY, REAL_SOL_RESERVES, REAL_TOKEN_RESERVES, VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES};
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+
+/// A curve that sold its whole allocation and has been graduated.
+fn completed_curve(launch: &Launch) -> BondingCurve {
+    BondingCurve {
+        complete: true,
+        virtual_sol_reserves: VIRTUAL_SOL_RESERVES + REAL_SOL_RESERVES,
+        virtual_token_reserves: VIRTUAL_TOKEN_RESERVES - REAL_TOKEN_RESERVES,
+        real_sol_reserves: REAL_SOL_RESERVES,
+        real_token_reserves: MAX_TOKEN_SUPPLY - REAL_TOKEN_RESERVES,
+        graduated_slot: 1,
+        ..launch.fresh_curve()
+    }
+}
+
+#[tokio::test]
+async fn migrate_seeds_the_pool_with_the_previewed_amounts() {
+    let launch = Launch::new();
+    let migration = Migration::new(&launch);
+    let curve = completed_curve(&launch);
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    migration.seed(&mut program_test, &launch);
+    let mut env = TestEnv::start(program_test).await;
+
+    let preview: MigrationPreview = env
+        .view(common::instruction(
+            pump_clone::accounts::PreviewMigration {
+                global_state: launch.global_state,
+                bonding_curve: launch.bonding_curve,
+                curve_token_account: launch.curve_token_account,
+                curve_sol_vault: launch.sol_vault,
+            },
+            pump_clone::instruction::PreviewMigration {},
+        ))
+        .await;
+    assert!(preview.token_amount > 0 && preview.sol_amount > 0);
+    let tokens_before = env.token_balance(launch.curve_token_account).await;
+    let lamports_before = env.lamports(launch.sol_vault).await;
+
+    let payer = env.context.payer.pubkey();
+    let logs = env
+        .process(&[migration.ix(&launch, &payer)], &[&migration.migration_authority])
+        .await
+        .unwrap();
+
+    // What the preview reported is what left the curve and reached the pool
+    assert_eq!(tokens_before - env.token_balance(launch.curve_token_account).await, preview.token_amount);
+    assert_eq!(lamports_before - env.lamports(launch.sol_vault).await, preview.sol_amount);
+    assert_eq!(env.token_balance(migration.coin_vault).await, preview.token_amount);
+    assert_eq!(env.token_balance(migration.pc_vault).await, preview.sol_amount);
+    let migrated = &common::events::<LiquidityMigrated>(&logs)[0];
+    assert_eq!((migrated.token_amount, migrated.sol_amount), (preview.token_amount, preview.sol_amount));
+    assert_eq!(migrated.raydium_pool, migration.amm_pool);
+
+    let lock: LpLock = env.account(migration.lp_lock).await;
+    assert_eq!((lock.seed_token_amount, lock.seed_sol_amount), (preview.token_amount, preview.sol_amount));
+    assert!(lock.lp_amount > 0);
+    assert_eq!(lock.lp_amount, lock.seed_lp_supply);
+    let token_launch: TokenLaunch = env.account(launch.token_launch).await;
+    assert!(token_launch.is_migrated);
+    assert_eq!(token_launch.raydium_pool, Some(migration.amm_pool));
+
+    // The curve is claimed, so a second migrate moves nothing
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    assert!(curve.migrated);
+    assert!(env
+        .process(&[migration.ix(&launch, &payer)], &[&migration.migration_authority])
+        .await
+        .is_err());
+    assert_eq!(env.lamports(launch.sol_vault).await, lamports_before - preview.sol_amount);
+}
+
+#[tokio::test]
+async fn migrate_waits_for_graduation() {
+    let launch = Launch::new();
+    let migration = Migration::new(&launch);
+    let curve = BondingCurve { graduated_slot: 0, ..completed_curve(&launch) };
+    // Another curve graduated at slot 1, so this one is inside the cooldown
+    let global = GlobalState {
+        graduation_cooldown_slots: 500,
+        last_graduation_slot: 1,
+        ..common::global_state(Pubkey::new_unique(), Pubkey::new_unique())
+    };
+    let mut program_test = common::program_test();
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    migration.seed(&mut program_test, &launch);
+    let mut env = TestEnv::start(program_test).await;
+    let payer = env.context.payer.pubkey();
+    let graduate = common::instruction(
+        pump_clone::accounts::Graduate {
+            payer,
+            global_state: launch.global_state,
+            bonding_curve: launch.bonding_curve,
+        },
+        pump_clone::instruction::Graduate {},
+    );
+    let lamports_before = env.lamports(launch.sol_vault).await;
+
+    // Complete and funded, but never graduated
+    assert_eq!(
+        env.process(&[migration.ix(&launch, &payer)], &[&migration.migration_authority])
+            .await
+            .unwrap_err(),
+        not_graduated()
+    );
+
+    // Graduating inside the cooldown is deferred, which leaves the curve
+    // just as unmigratable
+    env.process(&[graduate.clone()], &[]).await.unwrap();
+    let deferred: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_eq!(deferred.graduated_slot, 0);
+    assert_eq!(
+        env.process(&[migration.ix(&launch, &payer)], &[&migration.migration_authority])
+            .await
+            .unwrap_err(),
+        not_graduated()
+    );
+    assert_eq!(env.lamports(launch.sol_vault).await, lamports_before);
+
+    // Once the cooldown has elapsed, graduate stamps the curve and it migrates
+    env.context.warp_to_slot(501).unwrap();
+    let mut clock: Clock = env.context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = common::START_TS;
+    env.context.set_sysvar(&clock);
+    env.process(&[graduate], &[]).await.unwrap();
+    let graduated: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_ne!(graduated.graduated_slot, 0);
+    env.process(&[migration.ix(&launch, &payer)], &[&migration.migration_authority])
+        .await
+        .unwrap();
+    let migrated: BondingCurve = env.account(launch.bonding_curve).await;
+    assert!(migrated.migrated);
+}
+
+fn not_graduated() -> TransactionError {
+    TransactionError::InstructionError(0, InstructionError::Custom(ERROR_CODE_OFFSET + PumpCloneError::NotGraduated as u32))
+}
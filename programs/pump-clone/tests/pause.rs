@@ -0,0 +1,65 @@
+//! The global pause stops every instruction that moves a curve's reserves,
+//! not just `buy_tokens` and `sell_tokens`.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::BondingCurve;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn pause_blocks_add_liquidity_and_refunds() {
+    let (launch, holder) = (Launch::new(), Keypair::new());
+    let mut curve = launch.fresh_curve();
+    let sold = 100_000_000;
+    curve.real_token_reserves -= sold;
+    curve.virtual_token_reserves -= sold;
+    curve.real_sol_reserves = LAMPORTS_PER_SOL;
+    curve.virtual_sol_reserves += LAMPORTS_PER_SOL;
+    curve.refund_mode = true;
+
+    let mut program_test = common::program_test();
+    let mut global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    global.creator_liquidity_enabled = true;
+    global.is_paused = true;
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    let holder_tokens = get_associated_token_address(&holder.pubkey(), &launch.mint);
+    common::add_token_account(&mut program_test, holder_tokens, launch.mint, holder.pubkey(), sold);
+    common::add_system_account(&mut program_test, holder.pubkey(), LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    let add_liquidity = common::instruction(
+        pump_clone::accounts::AddLiquidity {
+            creator: launch.creator.pubkey(),
+            global_state: launch.global_state,
+            bonding_curve: launch.bonding_curve,
+            sol_vault: launch.sol_vault,
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::AddLiquidity { sol_amount: LAMPORTS_PER_SOL / 10 },
+    );
+    assert!(env.process(&[add_liquidity], &[&launch.creator]).await.is_err());
+
+    let claim = common::instruction(
+        pump_clone::accounts::ClaimRefund {
+            holder: holder.pubkey(),
+            global_state: launch.global_state,
+            bonding_curve: launch.bonding_curve,
+            mint: launch.mint,
+            holder_token_account: holder_tokens,
+            sol_vault: launch.sol_vault,
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::ClaimRefund { token_amount: sold },
+    );
+    assert!(env.process(&[claim], &[&holder]).await.is_err());
+
+    let after: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_eq!(after.real_sol_reserves, curve.real_sol_reserves);
+    assert_eq!(after.virtual_sol_reserves, curve.virtual_sol_reserves);
+    assert_eq!(env.token_balance(holder_tokens).await, sold);
+}
@@ -0,0 +1,72 @@
+//! The `pda` helpers name the accounts the instruction constraints accept:
+//! a launch built from them goes through `create_token` and trades, and an
+//! address from the old per-curve vault seeds is turned away.
+
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::{Launch, TestEnv};
+use pump_clone::pda;
+use pump_clone::state::{BondingCurve, GlobalState, LaunchConfig};
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::TransactionError;
+
+#[tokio::test]
+async fn pda_helpers_match_account_seeds() {
+    let mint = Keypair::new();
+    let launch = Launch::for_mint(mint.pubkey());
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    common::add_program_account(&mut program_test, pda::global_state().0, &global, GlobalState::LEN);
+    common::add_system_account(&mut program_test, launch.fee_vault, Rent::default().minimum_balance(0));
+    common::add_system_account(&mut program_test, launch.creator.pubkey(), LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    // `create_token_accounts` takes every address from the helpers
+    let create = common::instruction(
+        common::create_token_accounts(&launch.creator.pubkey(), &mint.pubkey(), false),
+        pump_clone::instruction::CreateToken {
+            name: "Seeds".to_string(),
+            symbol: "SDS".to_string(),
+            uri: "https://example.com/seeds.json".to_string(),
+            initial_buy: 0,
+            config: LaunchConfig::default(),
+        },
+    );
+    env.process(&[create], &[&launch.creator, &mint]).await.unwrap();
+
+    let (curve_address, curve_bump) = pda::bonding_curve(&mint.pubkey());
+    let curve: BondingCurve = env.account(curve_address).await;
+    assert_eq!(curve.bump, curve_bump);
+    assert_eq!(env.token_balance(pda::curve_token_account(&mint.pubkey())).await, curve.total_supply);
+
+    // A buy through the helpers' SOL vault lands there
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    env.process(&[launch.buy_ix(&buyer.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&buyer])
+        .await
+        .unwrap();
+    let curve: BondingCurve = env.account(curve_address).await;
+    assert_eq!(
+        env.lamports(pda::sol_vault(&mint.pubkey()).0).await,
+        Rent::default().minimum_balance(0) + curve.real_sol_reserves
+    );
+
+    // The vault keyed by the curve rather than the mint is not the one the
+    // constraints check
+    let mut accounts = launch.buy_accounts(&buyer.pubkey());
+    accounts.sol_vault =
+        Pubkey::find_program_address(&[pda::SOL_VAULT_SEED, curve_address.as_ref()], &pump_clone::ID).0;
+    let buy = common::instruction(
+        accounts,
+        pump_clone::instruction::BuyTokens { sol_amount: LAMPORTS_PER_SOL, max_slippage_bps: AUTO_SLIPPAGE, deadline: 0 },
+    );
+    assert_eq!(
+        env.process(&[buy], &[&buyer]).await.unwrap_err(),
+        TransactionError::InstructionError(0, InstructionError::Custom(ErrorCode::ConstraintSeeds as u32))
+    );
+}
@@ -0,0 +1,104 @@
+//! `preview_migration` reports the pool seed amounts, and fails wherever
+//! `migrate_liquidity` would fail before moving funds.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::{Launch, TestEnv};
+use pump_clone::state::{BondingCurve, GlobalState, MigrationPreview};
+use pump_clone::utils::{MAX_TOKEN_SUPPLY, REAL_SOL_RESERVES, REAL_TOKEN_RESERVES, VIRTUAL_SOL_RESERVES, VIRTUAL_TOKEN_RESERVES};
+use solana_sdk::pubkey::Pubkey;
+
+/// 20% of the 1B supply, with 6 decimals
+const POOL_TOKENS: u64 = 200_000_000 * 1_000_000;
+
+/// A curve that sold its whole allocation: 85 SOL raised, and the supply it
+/// didn't sell still in its token account.
+fn completed_curve(launch: &Launch) -> BondingCurve {
+    BondingCurve {
+        complete: true,
+        virtual_sol_reserves: VIRTUAL_SOL_RESERVES + REAL_SOL_RESERVES,
+        virtual_token_reserves: VIRTUAL_TOKEN_RESERVES - REAL_TOKEN_RESERVES,
+        real_sol_reserves: REAL_SOL_RESERVES,
+        real_token_reserves: MAX_TOKEN_SUPPLY - REAL_TOKEN_RESERVES,
+        graduated_slot: 1,
+        ..launch.fresh_curve()
+    }
+}
+
+/// Runs `preview_migration` against `curve` under `global`; `None` when it
+/// fails.
+async fn preview(launch: &Launch, curve: &BondingCurve, global: &GlobalState) -> Option<MigrationPreview> {
+    let mut program_test = common::program_test();
+    launch.seed(&mut program_test, curve, &launch.token_launch_account(), global);
+    let mut env = TestEnv::start(program_test).await;
+    let instruction = common::instruction(
+        pump_clone::accounts::PreviewMigration {
+            global_state: launch.global_state,
+            bonding_curve: launch.bonding_curve,
+            curve_token_account: launch.curve_token_account,
+            curve_sol_vault: launch.sol_vault,
+        },
+        pump_clone::instruction::PreviewMigration {},
+    );
+
+    if env.process(&[instruction.clone()], &[]).await.is_err() {
+        return None;
+    }
+    Some(env.view(instruction).await)
+}
+
+#[tokio::test]
+async fn completed_curve_previews_the_pool_seed() {
+    let launch = Launch::new();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+
+    assert_eq!(
+        preview(&launch, &completed_curve(&launch), &global).await,
+        Some(MigrationPreview { token_amount: POOL_TOKENS, sol_amount: 85 * LAMPORTS_PER_SOL })
+    );
+
+    // Still trading, not yet graduated, or short of pool tokens
+    assert_eq!(preview(&launch, &launch.fresh_curve(), &global).await, None);
+    let ungraduated = BondingCurve { graduated_slot: 0, ..completed_curve(&launch) };
+    assert_eq!(preview(&launch, &ungraduated, &global).await, None);
+    let short = BondingCurve { real_token_reserves: POOL_TOKENS - 1, ..completed_curve(&launch) };
+    assert_eq!(preview(&launch, &short, &global).await, None);
+}
+
+#[tokio::test]
+async fn preview_applies_the_migration_gates() {
+    let launch = Launch::new();
+    let curve = completed_curve(&launch);
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+
+    // Migrations paused
+    let paused = GlobalState { paused_instructions: GlobalState::PAUSE_MIGRATE, ..global.clone() };
+    assert_eq!(preview(&launch, &curve, &paused).await, None);
+
+    // The raise hasn't reached the global threshold
+    let threshold = GlobalState { migration_threshold: REAL_SOL_RESERVES + 1, ..global.clone() };
+    assert_eq!(preview(&launch, &curve, &threshold).await, None);
+
+    // Listing valuation below the minimum
+    let min_cap = GlobalState { min_migration_market_cap: u64::MAX, ..global.clone() };
+    assert_eq!(preview(&launch, &curve, &min_cap).await, None);
+}
+
+#[tokio::test]
+async fn preview_rejects_a_seed_price_outside_the_twap_band() {
+    let launch = Launch::new();
+    // A TWAP of the final spot price over the hour before the preview
+    let curve = BondingCurve {
+        price_cumulative_timestamp: common::START_TS - 3_600,
+        twap_anchor_timestamp: common::START_TS - 3_600,
+        ..completed_curve(&launch)
+    };
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+
+    // The seed price sits a few percent off the final spot price
+    let tight = GlobalState { migration_price_band_bps: 1, ..global.clone() };
+    assert_eq!(preview(&launch, &curve, &tight).await, None);
+    let wide = GlobalState { migration_price_band_bps: 5_000, ..global };
+    assert!(preview(&launch, &curve, &wide).await.is_some());
+}
@@ -0,0 +1,272 @@
+//! Randomized buy/sell sequences driven through the program itself.
+//!
+//! `invariants.rs` checks the curve math in isolation; this creates a launch
+//! through `create_token` and runs the same kind of seeded sequences through
+//! the `buy_tokens` and `sell_tokens` handlers in a program-test bank, so the
+//! reserve bookkeeping, fee routing and token transfers are checked together
+//! after every step, up to and through migration.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use common::{Launch, Migration, TestEnv};
+use pump_clone::state::{BondingCurve, GlobalState, LaunchConfig, MigrationPreview};
+use pump_clone::utils::{canonical_k_constant, AUTO_SLIPPAGE};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Same PRNG as the math harness, so a failing seed replays exactly.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        self.0 >> 33
+    }
+
+    fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next() % (high - low + 1)
+    }
+}
+
+const WALLET_FUNDING: u64 = 50 * LAMPORTS_PER_SOL;
+
+/// Tokens `create_token` minted to the curve beyond what it sells, held for
+/// migration; trades never touch them.
+fn migration_reserve(curve: &BondingCurve) -> u64 {
+    curve.total_supply - curve.initial_real_token_reserves
+}
+
+async fn check_invariants(env: &mut TestEnv, launch: &Launch, previous_k: &mut u128) {
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    let global: GlobalState = env.account(launch.global_state).await;
+
+    assert!(curve.real_sol_reserves <= curve.virtual_sol_reserves);
+    assert!(curve.real_token_reserves <= curve.virtual_token_reserves);
+    // The curve's books match what it actually holds
+    assert_eq!(
+        env.token_balance(launch.curve_token_account).await,
+        curve.real_token_reserves + migration_reserve(&curve)
+    );
+    let rent = Rent::default().minimum_balance(0);
+    assert!(env.lamports(launch.sol_vault).await >= rent + curve.real_sol_reserves);
+    // Fees rounding in the curve's favour means k never shrinks
+    let k = canonical_k_constant(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+    assert!(k >= *previous_k, "k shrank from {} to {}", previous_k, k);
+    *previous_k = k;
+    assert!(global.total_fees_collected <= global.total_volume);
+}
+
+/// Starts a bank with only the global state, fee vault and the pool side of
+/// a Raydium migration, and creates the launch through `create_token`.
+async fn create_launch(config: LaunchConfig) -> (TestEnv, Launch, Migration) {
+    let mint = Keypair::new();
+    let launch = Launch::for_mint(mint.pubkey());
+    let migration = Migration::new(&launch);
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    common::add_program_account(&mut program_test, launch.global_state, &global, GlobalState::LEN);
+    common::add_system_account(&mut program_test, launch.fee_vault, Rent::default().minimum_balance(0));
+    common::add_system_account(&mut program_test, launch.creator.pubkey(), LAMPORTS_PER_SOL);
+    migration.seed(&mut program_test, &launch);
+    let mut env = TestEnv::start(program_test).await;
+
+    let create = common::instruction(
+        common::create_token_accounts(&launch.creator.pubkey(), &mint.pubkey(), false),
+        pump_clone::instruction::CreateToken {
+            name: "Invariant".to_string(),
+            symbol: "INV".to_string(),
+            uri: "https://example.com/inv.json".to_string(),
+            initial_buy: 0,
+            config,
+        },
+    );
+    env.process(&[create], &[&launch.creator, &mint]).await.unwrap();
+    (env, launch, migration)
+}
+
+async fn run_seed(seed: u64, steps: usize) {
+    let (mut env, launch, _) = create_launch(LaunchConfig::default()).await;
+
+    let mut wallets: Vec<Keypair> = Vec::new();
+    for _ in 0..3 {
+        wallets.push(env.funded_wallet(WALLET_FUNDING).await);
+    }
+    let mut rng = Lcg(seed);
+    let mut previous_k = 0;
+    check_invariants(&mut env, &launch, &mut previous_k).await;
+
+    let (mut buys, mut sells) = (0, 0);
+    for step in 0..steps {
+        env.warp_to_timestamp(common::START_TS + step as i64 + 1).await;
+        let wallet = &wallets[rng.range(0, wallets.len() as u64 - 1) as usize];
+        let held = env
+            .token_balance(anchor_spl::associated_token::get_associated_token_address(
+                &wallet.pubkey(),
+                &launch.mint,
+            ))
+            .await;
+
+        let sell = held > 0 && rng.range(0, 2) == 0;
+        let instruction = if sell {
+            launch.sell_ix(&wallet.pubkey(), rng.range(1, held), 0)
+        } else {
+            launch.buy_ix(&wallet.pubkey(), rng.range(LAMPORTS_PER_SOL / 100, 2 * LAMPORTS_PER_SOL), AUTO_SLIPPAGE)
+        };
+        let curve_before: BondingCurve = env.account(launch.bonding_curve).await;
+        match env.process(&[instruction], &[wallet]).await {
+            Ok(_) if sell => sells += 1,
+            Ok(_) => buys += 1,
+            // A rejected trade must leave the books untouched
+            Err(_) => {
+                let curve_after: BondingCurve = env.account(launch.bonding_curve).await;
+                assert_eq!(curve_after.real_sol_reserves, curve_before.real_sol_reserves);
+                assert_eq!(curve_after.real_token_reserves, curve_before.real_token_reserves);
+            }
+        }
+        check_invariants(&mut env, &launch, &mut previous_k).await;
+    }
+    // Nothing here should be rejected often; a sequence that mostly fails
+    // would pass the checks above without testing anything
+    assert!(buys + sells >= steps * 3 / 4, "only {} of {} trades went through", buys + sells, steps);
+    assert!(buys > 0 && sells > 0, "{} buys and {} sells", buys, sells);
+
+    // Round trips never pay: once everyone has sold out, no wallet has more
+    // SOL than it was funded with
+    for wallet in &wallets {
+        let held = env
+            .token_balance(anchor_spl::associated_token::get_associated_token_address(
+                &wallet.pubkey(),
+                &launch.mint,
+            ))
+            .await;
+        if held > 0 {
+            env.warp_to_timestamp(common::START_TS + steps as i64 + 10).await;
+            env.process(&[launch.sell_ix(&wallet.pubkey(), held, 0)], &[wallet])
+                .await
+                .unwrap();
+        }
+        assert!(env.lamports(wallet.pubkey()).await <= WALLET_FUNDING);
+    }
+    check_invariants(&mut env, &launch, &mut previous_k).await;
+}
+
+#[tokio::test]
+async fn randomized_trades_keep_curve_invariants() {
+    for seed in 0..4 {
+        run_seed(seed, 24).await;
+    }
+}
+
+/// Long enough for several wallets to sell back through the bottom of the
+/// curve, where rounding in the sell path is most likely to overdraw it.
+#[tokio::test]
+async fn long_sequence_keeps_curve_invariants() {
+    run_seed(0x5eed_0007, 80).await;
+}
+
+/// Trades a launch to completion, then graduates and migrates it: the pool
+/// gets exactly what the preview reported, out of the curve's own vaults, and
+/// the curve trades no more.
+#[tokio::test]
+async fn randomized_trades_through_migration() {
+    // A deeper virtual SOL reserve raises the 85 SOL goal well before the
+    // curve runs out of tokens
+    let config = LaunchConfig { initial_virtual_sol_reserves: 60 * LAMPORTS_PER_SOL, ..LaunchConfig::default() };
+    let (mut env, launch, migration) = create_launch(config).await;
+
+    let mut wallets: Vec<Keypair> = Vec::new();
+    for _ in 0..4 {
+        wallets.push(env.funded_wallet(WALLET_FUNDING).await);
+    }
+    let mut rng = Lcg(0x5eed_0690);
+    let mut previous_k = 0;
+    let mut step = 0;
+    loop {
+        let curve: BondingCurve = env.account(launch.bonding_curve).await;
+        if curve.complete {
+            break;
+        }
+        assert!(step < 200, "curve still trading after {} steps", step);
+        step += 1;
+        env.warp_to_timestamp(common::START_TS + step as i64).await;
+        let wallet = &wallets[rng.range(0, wallets.len() as u64 - 1) as usize];
+        let held = env
+            .token_balance(anchor_spl::associated_token::get_associated_token_address(
+                &wallet.pubkey(),
+                &launch.mint,
+            ))
+            .await;
+        let instruction = if held > 0 && rng.range(0, 3) == 0 {
+            launch.sell_ix(&wallet.pubkey(), rng.range(1, held), 0)
+        } else {
+            launch.buy_ix(&wallet.pubkey(), rng.range(LAMPORTS_PER_SOL, 8 * LAMPORTS_PER_SOL), AUTO_SLIPPAGE)
+        };
+        let _ = env.process(&[instruction], &[wallet]).await;
+        check_invariants(&mut env, &launch, &mut previous_k).await;
+    }
+
+    // The completing buy closed the curve to trading
+    let wallet = &wallets[0];
+    assert!(env
+        .process(&[launch.buy_ix(&wallet.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[wallet])
+        .await
+        .is_err());
+
+    let payer = env.context.payer.pubkey();
+    env.process(
+        &[common::instruction(
+            pump_clone::accounts::Graduate {
+                payer,
+                global_state: launch.global_state,
+                bonding_curve: launch.bonding_curve,
+            },
+            pump_clone::instruction::Graduate {},
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let preview: MigrationPreview = env
+        .view(common::instruction(
+            pump_clone::accounts::PreviewMigration {
+                global_state: launch.global_state,
+                bonding_curve: launch.bonding_curve,
+                curve_token_account: launch.curve_token_account,
+                curve_sol_vault: launch.sol_vault,
+            },
+            pump_clone::instruction::PreviewMigration {},
+        ))
+        .await;
+    let tokens_before = env.token_balance(launch.curve_token_account).await;
+    let lamports_before = env.lamports(launch.sol_vault).await;
+
+    env.process(&[migration.ix(&launch, &payer)], &[&migration.migration_authority])
+        .await
+        .unwrap();
+
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    assert!(curve.migrated);
+    assert_eq!(env.token_balance(launch.curve_token_account).await, tokens_before - preview.token_amount);
+    assert_eq!(env.lamports(launch.sol_vault).await, lamports_before - preview.sol_amount);
+    assert!(env.lamports(launch.sol_vault).await >= Rent::default().minimum_balance(0));
+    assert_eq!(env.token_balance(migration.coin_vault).await, preview.token_amount);
+    assert_eq!(env.token_balance(migration.pc_vault).await, preview.sol_amount);
+
+    // Holders can no longer trade against the curve
+    for wallet in &wallets {
+        let held = env
+            .token_balance(anchor_spl::associated_token::get_associated_token_address(
+                &wallet.pubkey(),
+                &launch.mint,
+            ))
+            .await;
+        if held > 0 {
+            assert!(env.process(&[launch.sell_ix(&wallet.pubkey(), held, 0)], &[wallet]).await.is_err());
+        }
+    }
+}
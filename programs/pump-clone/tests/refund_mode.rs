@@ -0,0 +1,68 @@
+//! Refund mode through the handlers: one-way, and refunds burn tokens
+//! without handing them back to the curve.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::BondingCurve;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn refunds_burn_tokens_and_refund_mode_cannot_be_lifted() {
+    let (launch, authority, holder) = (Launch::new(), Keypair::new(), Keypair::new());
+    let mut curve = launch.fresh_curve();
+    let sold = 100_000_000;
+    curve.real_token_reserves -= sold;
+    curve.virtual_token_reserves -= sold;
+    curve.real_sol_reserves = LAMPORTS_PER_SOL;
+    curve.virtual_sol_reserves += LAMPORTS_PER_SOL;
+    curve.refund_mode = true;
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(authority.pubkey(), solana_sdk::pubkey::Pubkey::new_unique());
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    let holder_tokens = get_associated_token_address(&holder.pubkey(), &launch.mint);
+    common::add_token_account(&mut program_test, holder_tokens, launch.mint, holder.pubkey(), sold);
+    common::add_system_account(&mut program_test, holder.pubkey(), LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    let set_refund_mode = |enabled| {
+        common::instruction(
+            pump_clone::accounts::SetRefundMode {
+                authority: authority.pubkey(),
+                global_state: launch.global_state,
+                bonding_curve: launch.bonding_curve,
+            },
+            pump_clone::instruction::SetRefundMode { enabled },
+        )
+    };
+    assert!(env.process(&[set_refund_mode(false)], &[&authority]).await.is_err());
+
+    let claim = |token_amount| {
+        common::instruction(
+            pump_clone::accounts::ClaimRefund {
+                holder: holder.pubkey(),
+                global_state: launch.global_state,
+                bonding_curve: launch.bonding_curve,
+                mint: launch.mint,
+                holder_token_account: holder_tokens,
+                sol_vault: launch.sol_vault,
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            },
+            pump_clone::instruction::ClaimRefund { token_amount },
+        )
+    };
+    env.process(&[claim(sold / 4)], &[&holder]).await.unwrap();
+    env.process(&[claim(sold - sold / 4)], &[&holder]).await.unwrap();
+
+    let after: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_eq!(after.real_sol_reserves, 0);
+    assert_eq!(after.tokens_refunded, sold);
+    // Nothing went back into the curve's unsold supply
+    assert_eq!(after.real_token_reserves, curve.real_token_reserves);
+    assert_eq!(env.token_balance(holder_tokens).await, 0);
+    assert!(after.refund_mode);
+}
@@ -0,0 +1,55 @@
+//! A buy rejected by its guards creates nothing and moves no lamports: the
+//! buyer's token account and position only appear once every check passes.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::{BondingCurve, UserPosition};
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn slippage_rejected_buy_creates_nothing_and_moves_no_lamports() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let buyer_tokens = get_associated_token_address(&buyer.pubkey(), &launch.mint);
+    let position = launch.user_position(&buyer.pubkey());
+
+    let watched = [buyer.pubkey(), launch.sol_vault, launch.fee_vault, launch.creator.pubkey()];
+    let mut lamports_before = Vec::new();
+    for address in watched {
+        lamports_before.push(env.lamports(address).await);
+    }
+    let curve_before: BondingCurve = env.account(launch.bonding_curve).await;
+
+    // A whole SOL moves the price far more than 1 bp
+    assert!(env
+        .process(&[launch.buy_ix(&buyer.pubkey(), LAMPORTS_PER_SOL, 1)], &[&buyer])
+        .await
+        .is_err());
+
+    for address in [buyer_tokens, position] {
+        assert!(env.context.banks_client.get_account(address).await.unwrap().is_none());
+    }
+    for (address, before) in watched.into_iter().zip(lamports_before) {
+        assert_eq!(env.lamports(address).await, before);
+    }
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    assert_eq!(curve.real_sol_reserves, curve_before.real_sol_reserves);
+    assert_eq!(curve.real_token_reserves, curve_before.real_token_reserves);
+
+    // The same buy within its tolerance creates both accounts
+    env.process(&[launch.buy_ix(&buyer.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&buyer])
+        .await
+        .unwrap();
+    assert!(env.token_balance(buyer_tokens).await > 0);
+    let position: UserPosition = env.account(position).await;
+    assert_eq!(position.user, buyer.pubkey());
+}
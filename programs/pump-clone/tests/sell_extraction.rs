@@ -0,0 +1,71 @@
+//! The per-wallet extraction cap only bounds proceeds from tokens the
+//! position bought from the curve.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::state::UserPosition;
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn tokens_received_outside_the_curve_are_not_capped() {
+    let (launch, recipient) = (Launch::new(), Keypair::new());
+    let mut program_test = common::program_test();
+    let mut global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    global.max_sell_extraction_multiple_bps = 10_000;
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+
+    // The recipient has a position on the launch but never bought
+    let recipient_tokens = get_associated_token_address(&recipient.pubkey(), &launch.mint);
+    common::add_token_account(&mut program_test, recipient_tokens, launch.mint, recipient.pubkey(), 0);
+    common::add_system_account(&mut program_test, recipient.pubkey(), LAMPORTS_PER_SOL);
+    let position = UserPosition {
+        user: recipient.pubkey(),
+        token_launch: launch.token_launch,
+        mint: launch.mint,
+        bump: Pubkey::find_program_address(
+            &[b"user_position", recipient.pubkey().as_ref(), launch.token_launch.as_ref()],
+            &pump_clone::ID,
+        )
+        .1,
+        ..Default::default()
+    };
+    common::add_program_account(
+        &mut program_test,
+        launch.user_position(&recipient.pubkey()),
+        &position,
+        UserPosition::LEN,
+    );
+    let mut env = TestEnv::start(program_test).await;
+
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let buyer_tokens = get_associated_token_address(&buyer.pubkey(), &launch.mint);
+    env.process(&[launch.buy_ix(&buyer.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&buyer])
+        .await
+        .unwrap();
+    let gift = env.token_balance(buyer_tokens).await / 2;
+    let transfer = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &buyer_tokens,
+        &recipient_tokens,
+        &buyer.pubkey(),
+        &[],
+        gift,
+    )
+    .unwrap();
+    env.process(&[transfer], &[&buyer]).await.unwrap();
+
+    // Nothing was invested, yet the gifted tokens can still be sold
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    env.process(&[launch.sell_ix(&recipient.pubkey(), gift, 0)], &[&recipient])
+        .await
+        .unwrap();
+    assert_eq!(env.token_balance(recipient_tokens).await, 0);
+    let position: UserPosition = env.account(launch.user_position(&recipient.pubkey())).await;
+    assert_eq!(position.sol_extracted, 0);
+    assert_eq!(position.token_balance, 0);
+}
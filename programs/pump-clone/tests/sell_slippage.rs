@@ -0,0 +1,47 @@
+//! `sell_tokens_with_slippage` bounds what the seller receives after fees,
+//! not the curve's gross quote.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn slippage_tolerance_covers_fees() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let trader_tokens = get_associated_token_address(&trader.pubkey(), &launch.mint);
+
+    env.process(&[launch.buy_ix(&trader.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&trader])
+        .await
+        .unwrap();
+    env.warp_to_timestamp(common::START_TS + 1).await;
+
+    // A small sell barely moves the price, but the 1% creator and 1%
+    // platform fees alone put the proceeds 2% under spot
+    let token_amount = env.token_balance(trader_tokens).await / 100;
+    let sell = |max_slippage_bps| {
+        common::instruction(
+            launch.sell_accounts(&trader.pubkey()),
+            pump_clone::instruction::SellTokensWithSlippage {
+                token_amount,
+                max_slippage_bps,
+                min_sol_output: 0,
+                deadline: 0,
+            },
+        )
+    };
+    assert!(env.process(&[sell(100)], &[&trader]).await.is_err());
+    let held = env.token_balance(trader_tokens).await;
+
+    env.process(&[sell(300)], &[&trader]).await.unwrap();
+    assert_eq!(env.token_balance(trader_tokens).await, held - token_amount);
+}
@@ -0,0 +1,136 @@
+//! The creator's sell tax through the handlers: the retained part stays in
+//! the curve's reserves, the floor share funds buybacks that stop at the
+//! floor, and the tax can't be raised once trading starts.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::events::Trade;
+use pump_clone::state::{BondingCurve, TokenLaunch};
+use pump_clone::utils::{canonical_price, AUTO_SLIPPAGE};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::Signer;
+
+fn price_floor_vault(launch: &Launch) -> Pubkey {
+    Pubkey::find_program_address(&[b"price_floor", launch.mint.as_ref()], &pump_clone::ID).0
+}
+
+fn set_sell_tax(launch: &Launch, sell_tax_bps: u16, floor_price: u64) -> Instruction {
+    common::instruction(
+        pump_clone::accounts::SetSellTax {
+            creator: launch.creator.pubkey(),
+            global_state: launch.global_state,
+            token_launch: launch.token_launch,
+            bonding_curve: launch.bonding_curve,
+            price_floor_vault: price_floor_vault(launch),
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::SetSellTax { sell_tax_bps, floor_share_bps: 4_000, floor_price },
+    )
+}
+
+#[tokio::test]
+async fn taxed_sell_keeps_the_retained_tax_in_reserves() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let rent = Rent::default().minimum_balance(0);
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+
+    // 5% tax, 40% of it to the floor
+    env.process(&[set_sell_tax(&launch, 500, 0)], &[&launch.creator]).await.unwrap();
+    assert_eq!(env.lamports(price_floor_vault(&launch)).await, rent);
+
+    env.process(&[launch.buy_ix(&trader.pubkey(), LAMPORTS_PER_SOL, AUTO_SLIPPAGE)], &[&trader])
+        .await
+        .unwrap();
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    let held = env
+        .token_balance(get_associated_token_address(&trader.pubkey(), &launch.mint))
+        .await;
+    let mut accounts = launch.sell_accounts(&trader.pubkey());
+    accounts.price_floor_vault = Some(price_floor_vault(&launch));
+    let sell = common::instruction(
+        accounts,
+        pump_clone::instruction::SellTokens { token_amount: held / 2, min_sol_output: 0, deadline: 0 },
+    );
+    let logs = env.process(&[sell], &[&trader]).await.unwrap();
+
+    let sol_amount = common::events::<Trade>(&logs)[0].sol_amount;
+    let sell_tax = sol_amount * 500 / 10_000;
+    let floor_share = sell_tax * 4_000 / 10_000;
+    assert!(sell_tax - floor_share > 0);
+    assert_eq!(env.lamports(price_floor_vault(&launch)).await, rent + floor_share);
+
+    // Everything in the SOL vault is rent, reserves or owed to the creator
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    let token_launch: TokenLaunch = env.account(launch.token_launch).await;
+    assert_eq!(
+        env.lamports(launch.sol_vault).await,
+        rent + curve.real_sol_reserves + token_launch.accrued_creator_fees
+    );
+    assert_eq!(
+        curve.virtual_sol_reserves - curve.initial_virtual_sol_reserves,
+        curve.real_sol_reserves
+    );
+
+    // Now that the curve has traded, the tax only comes down
+    assert!(env.process(&[set_sell_tax(&launch, 501, 0)], &[&launch.creator]).await.is_err());
+    env.process(&[set_sell_tax(&launch, 200, 0)], &[&launch.creator]).await.unwrap();
+}
+
+#[tokio::test]
+async fn floor_buyback_stops_at_the_floor() {
+    let launch = Launch::new();
+    let curve = launch.fresh_curve();
+    let spot = canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+    let floor_price = spot + spot / 10;
+    let mut token_launch = launch.token_launch_account();
+    token_launch.floor_price = floor_price;
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    launch.seed(&mut program_test, &curve, &token_launch, &global);
+    let rent = Rent::default().minimum_balance(0);
+    let vault_balance = rent + 100 * LAMPORTS_PER_SOL;
+    common::add_system_account(&mut program_test, price_floor_vault(&launch), vault_balance);
+    let mut env = TestEnv::start(program_test).await;
+
+    let buyback = common::instruction(
+        pump_clone::accounts::FloorBuyback {
+            caller: env.context.payer.pubkey(),
+            token_launch: launch.token_launch,
+            bonding_curve: launch.bonding_curve,
+            price_floor_vault: price_floor_vault(&launch),
+            sol_vault: launch.sol_vault,
+            mint: launch.mint,
+            curve_token_account: launch.curve_token_account,
+            global_state: launch.global_state,
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        pump_clone::instruction::FloorBuyback {},
+    );
+    env.process(&[buyback], &[]).await.unwrap();
+
+    let after: BondingCurve = env.account(launch.bonding_curve).await;
+    let price = canonical_price(after.virtual_sol_reserves, after.virtual_token_reserves).unwrap();
+    assert!(price <= floor_price && price > floor_price - spot / 1_000);
+    assert!(after.tokens_burned > 0);
+    // The burned tokens leave the supply the curve prices and migrates against
+    assert_eq!(after.total_supply, curve.total_supply - after.tokens_burned);
+    let mint = env.context.banks_client.get_account(launch.mint).await.unwrap().unwrap();
+    assert_eq!(spl_token::state::Mint::unpack(&mint.data).unwrap().supply, after.total_supply);
+    assert_eq!(after.total_volume, after.real_sol_reserves);
+    // Only what lifted the price was spent
+    let spent = vault_balance - env.lamports(price_floor_vault(&launch)).await;
+    assert_eq!(spent, after.real_sol_reserves);
+    assert!(spent < 100 * LAMPORTS_PER_SOL);
+}
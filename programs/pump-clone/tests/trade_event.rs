@@ -0,0 +1,79 @@
+//! `buy_tokens` and `sell_tokens` both emit a `Trade` describing the settled
+//! curve, so indexers can follow either side from one event.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::events::Trade;
+use pump_clone::state::{BondingCurve, TradeDirection};
+use pump_clone::utils::{canonical_price, price_impact_bps, AUTO_SLIPPAGE};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+/// The event matches the curve as the handler left it.
+fn assert_matches_curve(trade: &Trade, curve: &BondingCurve, price_before: u64) {
+    assert_eq!(trade.schema_version, Trade::SCHEMA_VERSION);
+    assert_eq!(trade.virtual_sol_reserves, curve.virtual_sol_reserves);
+    assert_eq!(trade.virtual_token_reserves, curve.virtual_token_reserves);
+    assert_eq!(trade.real_sol_reserves, curve.real_sol_reserves);
+    assert_eq!(trade.real_token_reserves, curve.real_token_reserves);
+    assert_eq!(
+        trade.price,
+        canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap()
+    );
+    assert_eq!(trade.price_impact_bps, price_impact_bps(price_before, trade.price));
+    assert!(trade.price_impact_bps > 0);
+}
+
+#[tokio::test]
+async fn buy_and_sell_emit_a_trade_for_the_settled_curve() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    let curve = launch.fresh_curve();
+    let start_price = canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let trader = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+    let trader_tokens = get_associated_token_address(&trader.pubkey(), &launch.mint);
+
+    let sol_in = LAMPORTS_PER_SOL;
+    let fee_vault_before = env.lamports(launch.fee_vault).await;
+    let logs = env
+        .process(&[launch.buy_ix(&trader.pubkey(), sol_in, AUTO_SLIPPAGE)], &[&trader])
+        .await
+        .unwrap();
+    let buys = common::events::<Trade>(&logs);
+    assert_eq!(buys.len(), 1);
+    let buy = &buys[0];
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+
+    assert_eq!(buy.side, TradeDirection::Buy);
+    assert_eq!((buy.wallet, buy.mint), (trader.pubkey(), launch.mint));
+    assert_eq!(buy.sol_amount, sol_in);
+    assert_eq!(buy.token_amount, env.token_balance(trader_tokens).await);
+    assert_eq!(buy.platform_fee, env.lamports(launch.fee_vault).await - fee_vault_before);
+    assert_matches_curve(buy, &curve, start_price);
+
+    // Sell everything straight back
+    env.warp_to_timestamp(common::START_TS + 1).await;
+    let fee_vault_before = env.lamports(launch.fee_vault).await;
+    let logs = env
+        .process(&[launch.sell_ix(&trader.pubkey(), buy.token_amount, 0)], &[&trader])
+        .await
+        .unwrap();
+    let sells = common::events::<Trade>(&logs);
+    assert_eq!(sells.len(), 1);
+    let sell = &sells[0];
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+
+    assert_eq!(sell.side, TradeDirection::Sell);
+    assert_eq!((sell.wallet, sell.mint), (trader.pubkey(), launch.mint));
+    assert_eq!(sell.token_amount, buy.token_amount);
+    assert_eq!(sell.platform_fee, env.lamports(launch.fee_vault).await - fee_vault_before);
+    assert!(sell.sol_amount <= buy.sol_amount);
+    assert!(sell.timestamp > buy.timestamp);
+    assert_matches_curve(sell, &curve, buy.price);
+}
@@ -0,0 +1,53 @@
+//! The `TokensPurchased` event `buy_tokens` emits brackets the trade with the
+//! spot price before and after it, and prices the fill with fees included.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use common::{Launch, TestEnv};
+use pump_clone::events::TokensPurchased;
+use pump_clone::state::BondingCurve;
+use pump_clone::utils::{canonical_price, AUTO_SLIPPAGE, PRICE_SCALE};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn buy_event_reports_prices_around_the_trade() {
+    let launch = Launch::new();
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    let curve = launch.fresh_curve();
+    let start_price = canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap();
+    launch.seed(&mut program_test, &curve, &launch.token_launch_account(), &global);
+    let mut env = TestEnv::start(program_test).await;
+    let buyer = env.funded_wallet(10 * LAMPORTS_PER_SOL).await;
+
+    let sol_amount = LAMPORTS_PER_SOL;
+    let logs = env
+        .process(&[launch.buy_ix(&buyer.pubkey(), sol_amount, AUTO_SLIPPAGE)], &[&buyer])
+        .await
+        .unwrap();
+    let events = common::events::<TokensPurchased>(&logs);
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    let curve: BondingCurve = env.account(launch.bonding_curve).await;
+    let tokens = env
+        .token_balance(get_associated_token_address(&buyer.pubkey(), &launch.mint))
+        .await;
+
+    assert_eq!(event.price_before, start_price);
+    assert_eq!(
+        event.price_after,
+        canonical_price(curve.virtual_sol_reserves, curve.virtual_token_reserves).unwrap()
+    );
+    assert!(event.price_after > event.price_before);
+
+    // Everything the buyer paid, fees included, over the tokens received
+    assert_eq!(event.token_amount, tokens);
+    assert_eq!(
+        event.effective_price as u128,
+        sol_amount as u128 * PRICE_SCALE as u128 / tokens as u128
+    );
+    assert!(event.effective_price > event.price_before);
+}
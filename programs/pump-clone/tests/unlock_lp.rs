@@ -0,0 +1,62 @@
+//! `unlock_lp` against the bank clock: the lock holds until `unlock_ts`.
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use common::TestEnv;
+use pump_clone::state::{GlobalState, LpLock};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn locked_lp_is_released_only_once_the_clock_reaches_unlock_ts() {
+    let creator = Keypair::new();
+    let (mint, lp_mint) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (lp_lock, lp_lock_bump) = Pubkey::find_program_address(&[b"lp_lock", mint.as_ref()], &pump_clone::ID);
+    let unlock_ts = common::START_TS + GlobalState::DEFAULT_LP_LOCK_DURATION;
+    let lp_amount = 1_000_000;
+
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    common::add_program_account(&mut program_test, pump_clone::pda::global_state().0, &global, GlobalState::LEN);
+    common::add_program_account(
+        &mut program_test,
+        lp_lock,
+        &LpLock {
+            mint,
+            lp_mint,
+            creator: creator.pubkey(),
+            unlock_ts,
+            bump: lp_lock_bump,
+            ..Default::default()
+        },
+        LpLock::LEN,
+    );
+    common::add_mint(&mut program_test, lp_mint, None, lp_amount);
+    let lock_account = get_associated_token_address(&lp_lock, &lp_mint);
+    let creator_account = get_associated_token_address(&creator.pubkey(), &lp_mint);
+    common::add_token_account(&mut program_test, lock_account, lp_mint, lp_lock, lp_amount);
+    common::add_token_account(&mut program_test, creator_account, lp_mint, creator.pubkey(), 0);
+    let mut env = TestEnv::start(program_test).await;
+
+    let unlock = common::instruction(
+        pump_clone::accounts::UnlockLp {
+            creator: creator.pubkey(),
+            lp_lock,
+            lp_lock_token_account: lock_account,
+            creator_lp_account: creator_account,
+            global_state: pump_clone::pda::global_state().0,
+            token_program: spl_token::ID,
+        },
+        pump_clone::instruction::UnlockLp {},
+    );
+
+    env.warp_to_timestamp(unlock_ts - 1).await;
+    assert!(env.process(&[unlock.clone()], &[&creator]).await.is_err());
+    assert_eq!(env.token_balance(lock_account).await, lp_amount);
+
+    env.warp_to_timestamp(unlock_ts).await;
+    env.process(&[unlock], &[&creator]).await.unwrap();
+    assert_eq!(env.token_balance(lock_account).await, 0);
+    assert_eq!(env.token_balance(creator_account).await, lp_amount);
+}
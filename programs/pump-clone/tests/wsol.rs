@@ -0,0 +1,61 @@
+//! Paying for a buy with wSOL: only the buy's amount is unwrapped, and the
+//! tokens out match a native-SOL buy of the same size.
+
+mod common;
+
+use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::spl_token::native_mint;
+use common::{Launch, TestEnv};
+use pump_clone::utils::AUTO_SLIPPAGE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+#[tokio::test]
+async fn wsol_buy_unwraps_only_the_buy_and_matches_a_native_buy() {
+    let (native_launch, wsol_launch) = (Launch::new(), Launch::new());
+    let (native_buyer, wsol_buyer) = (Keypair::new(), Keypair::new());
+    let mut program_test = common::program_test();
+    let global = common::global_state(Pubkey::new_unique(), Pubkey::new_unique());
+    for launch in [&native_launch, &wsol_launch] {
+        launch.seed(&mut program_test, &launch.fresh_curve(), &launch.token_launch_account(), &global);
+    }
+    common::add_native_mint(&mut program_test);
+    common::add_system_account(&mut program_test, native_buyer.pubkey(), 10 * LAMPORTS_PER_SOL);
+    common::add_system_account(&mut program_test, wsol_buyer.pubkey(), LAMPORTS_PER_SOL);
+    let wsol_account = get_associated_token_address(&wsol_buyer.pubkey(), &native_mint::ID);
+    common::add_wsol_account(&mut program_test, wsol_account, wsol_buyer.pubkey(), 5 * LAMPORTS_PER_SOL);
+    let mut env = TestEnv::start(program_test).await;
+
+    let sol_amount = LAMPORTS_PER_SOL;
+    env.process(&[native_launch.buy_ix(&native_buyer.pubkey(), sol_amount, AUTO_SLIPPAGE)], &[&native_buyer])
+        .await
+        .unwrap();
+
+    let wsol_buy = |sol_amount| {
+        let mut accounts = wsol_launch.buy_accounts(&wsol_buyer.pubkey());
+        accounts.buyer_wsol_account = Some(wsol_account);
+        accounts.native_mint = Some(native_mint::ID);
+        accounts.wsol_unwrap_account = Some(
+            Pubkey::find_program_address(&[b"wsol_unwrap", wsol_buyer.pubkey().as_ref()], &pump_clone::ID).0,
+        );
+        common::instruction(
+            accounts,
+            pump_clone::instruction::BuyTokens { sol_amount, max_slippage_bps: AUTO_SLIPPAGE, deadline: 0 },
+        )
+    };
+    // More than the wSOL account holds is rejected outright
+    assert!(env.process(&[wsol_buy(6 * LAMPORTS_PER_SOL)], &[&wsol_buyer]).await.is_err());
+    env.process(&[wsol_buy(sol_amount)], &[&wsol_buyer]).await.unwrap();
+
+    // The rest of the buyer's wSOL is still wrapped, in the same account
+    assert_eq!(env.token_balance(wsol_account).await, 4 * LAMPORTS_PER_SOL);
+    assert_eq!(
+        env.token_balance(get_associated_token_address(&wsol_buyer.pubkey(), &wsol_launch.mint)).await,
+        env.token_balance(get_associated_token_address(&native_buyer.pubkey(), &native_launch.mint)).await
+    );
+    assert_eq!(
+        env.lamports(wsol_launch.sol_vault).await,
+        env.lamports(native_launch.sol_vault).await
+    );
+}